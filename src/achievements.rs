@@ -0,0 +1,190 @@
+//! Achievements: milestones unlocked by playing, kept from one session to the
+//! next in a small text file of `key: value` lines, like
+//!
+//! ```text
+//! generations: 123456
+//! unlocked: gunsmith
+//! unlocked: puzzler
+//! ```
+//!
+//! How each one is earned is up to the frontend, apart from the running
+//! count of generations, which is kept here.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Achievement {
+    /// What it's saved as.
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// How many generations, over every session, earn the marathon.
+pub const MARATHON_GENERATIONS: u64 = 1_000_000;
+
+/// How long a soup has to last, in generations, to earn methuselah.
+pub const METHUSELAH_GENERATIONS: u64 = 5000;
+
+/// Every achievement there is, in the order they're listed.
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement { id: "graduate", name: "Graduate", description: "finish the tutorial" },
+    Achievement { id: "marathon", name: "Marathon", description: "run 1,000,000 generations" },
+    Achievement { id: "gunsmith", name: "Gunsmith", description: "build something that keeps firing gliders" },
+    Achievement { id: "methuselah", name: "Methuselah", description: "find a soup lasting more than 5000 generations" },
+    Achievement { id: "puzzler", name: "Puzzler", description: "solve a puzzle" },
+    Achievement { id: "rivals", name: "Rivals", description: "play a versus match to the end" },
+    Achievement { id: "eden", name: "Garden of Eden", description: "find a pattern with no predecessor" },
+];
+
+/// Which achievements have been unlocked, and how many generations have
+/// been run towards the marathon.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Progress {
+    pub generations: u64,
+    unlocked: BTreeSet<&'static str>,
+}
+
+impl Progress {
+    /// Progress saved as text by `to_text`. Lines it doesn't recognize, like
+    /// achievements from a newer version, are left out.
+    pub fn parse(text: &str) -> Self {
+        let mut progress = Progress::default();
+        for line in text.lines() {
+            match line.split_once(':') {
+                Some(("generations", value)) => progress.generations = value.trim().parse().unwrap_or(0),
+                Some(("unlocked", id)) => {
+                    if let Some(achievement) = find(id.trim()) {
+                        progress.unlocked.insert(achievement.id);
+                    }
+                }
+                _ => {}
+            }
+        }
+        progress
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("generations: {}\n", self.generations);
+        for achievement in self.unlocked() {
+            text.push_str(&format!("unlocked: {}\n", achievement.id));
+        }
+        text
+    }
+
+    /// The progress saved at `path`, or none at all if nothing's been saved there yet.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Progress::default()),
+            Err(err) => Err(format!("couldn't read {}: {}", path.display(), err)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_text())
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.contains(id)
+    }
+
+    /// The unlocked achievements, in the order of `ACHIEVEMENTS`.
+    pub fn unlocked(&self) -> impl Iterator<Item = &'static Achievement> + '_ {
+        ACHIEVEMENTS.iter().filter(move |achievement| self.is_unlocked(achievement.id))
+    }
+
+    /// Unlock the achievement `id`, returning it if it wasn't already.
+    pub fn unlock(&mut self, id: &str) -> Option<&'static Achievement> {
+        let achievement = find(id)?;
+        if self.unlocked.insert(achievement.id) {
+            Some(achievement)
+        } else {
+            None
+        }
+    }
+
+    /// Count another generation run, returning the marathon if that earns it.
+    pub fn add_generation(&mut self) -> Option<&'static Achievement> {
+        self.generations += 1;
+        if self.generations >= MARATHON_GENERATIONS {
+            self.unlock("marathon")
+        } else {
+            None
+        }
+    }
+}
+
+fn find(id: &str) -> Option<&'static Achievement> {
+    ACHIEVEMENTS.iter().find(|achievement| achievement.id == id)
+}
+
+/// Watches how many gliders are on the board, checked every so often, for
+/// something that keeps making more of them: the count going up at every one
+/// of the last few checks, which debris from a soup hardly ever keeps up.
+#[derive(Clone, Debug)]
+pub struct GunWatch {
+    checks: usize,
+    counts: VecDeque<usize>,
+}
+
+impl GunWatch {
+    /// A watch that needs the glider count to rise `checks` times in a row.
+    pub fn new(checks: usize) -> Self {
+        GunWatch { checks, counts: VecDeque::with_capacity(checks + 1) }
+    }
+
+    /// Add the latest glider count, saying whether it's risen at every check
+    /// it's been watched for.
+    pub fn record(&mut self, gliders: usize) -> bool {
+        if self.counts.len() > self.checks {
+            self.counts.pop_front();
+        }
+        self.counts.push_back(gliders);
+        self.counts.len() > self.checks && self.counts.iter().zip(self.counts.iter().skip(1)).all(|(before, after)| after > before)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_round_trips_through_text() {
+        let mut progress = Progress::default();
+        assert_eq!(progress.unlock("puzzler").map(|achievement| achievement.name), Some("Puzzler"));
+        assert_eq!(progress.unlock("puzzler"), None);
+        assert_eq!(progress.unlock("no such thing"), None);
+        progress.unlock("graduate");
+        progress.generations = 42;
+
+        let text = progress.to_text();
+        assert_eq!(text, "generations: 42\nunlocked: graduate\nunlocked: puzzler\n");
+        assert_eq!(Progress::parse(&text), progress);
+        assert_eq!(Progress::parse("unlocked: from the future\ngenerations: 7\n").unlocked().count(), 0);
+    }
+
+    #[test]
+    fn the_marathon_comes_with_the_millionth_generation() {
+        let mut progress = Progress { generations: MARATHON_GENERATIONS - 2, ..Progress::default() };
+        assert_eq!(progress.add_generation(), None);
+        assert_eq!(progress.add_generation().map(|achievement| achievement.id), Some("marathon"));
+        assert_eq!(progress.add_generation(), None);
+        assert!(progress.is_unlocked("marathon"));
+    }
+
+    #[test]
+    fn guns_keep_adding_gliders() {
+        let mut watch = GunWatch::new(3);
+        assert!(!watch.record(0));
+        assert!(!watch.record(1));
+        assert!(!watch.record(2));
+        assert!(watch.record(3));
+        assert!(!watch.record(3));
+        assert!(!watch.record(4));
+    }
+}
@@ -0,0 +1,108 @@
+//! Time attack: a race against the clock to place a few cells that grow into
+//! as big a population as they can in a set number of generations, with the
+//! best scores kept in a table from one session to the next.
+
+use std::fmt;
+use std::path::Path;
+
+/// How an attempt scored: the population it ended with, and how many cells
+/// it started from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Score {
+    pub population: usize,
+    pub cells: usize,
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} cells from {}", self.population, self.cells)
+    }
+}
+
+/// The best scores, biggest population first, with fewer starting cells
+/// breaking a tie. Saved as a line of population and cells, tab separated,
+/// for each score.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HighScores {
+    scores: Vec<Score>,
+    capacity: usize,
+}
+
+impl HighScores {
+    /// An empty table with room for `capacity` scores.
+    pub fn new(capacity: usize) -> Self {
+        HighScores { scores: Vec::with_capacity(capacity + 1), capacity }
+    }
+
+    /// A table saved by `to_text`. Lines that aren't scores are left out.
+    pub fn parse(text: &str, capacity: usize) -> Self {
+        let mut scores = Self::new(capacity);
+        for line in text.lines() {
+            if let Some((population, cells)) = line.split_once('\t') {
+                if let (Ok(population), Ok(cells)) = (population.trim().parse(), cells.trim().parse()) {
+                    scores.record(Score { population, cells });
+                }
+            }
+        }
+        scores
+    }
+
+    pub fn to_text(&self) -> String {
+        self.scores.iter().map(|score| format!("{}\t{}\n", score.population, score.cells)).collect()
+    }
+
+    /// The table saved at `path`, or an empty one if nothing's been saved there yet.
+    pub fn load(path: &Path, capacity: usize) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text, capacity)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::new(capacity)),
+            Err(err) => Err(format!("couldn't read {}: {}", path.display(), err)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_text())
+    }
+
+    /// Add `score` if it's good enough and return its place, counting from 0.
+    /// A score that ties one already on the table goes after it.
+    pub fn record(&mut self, score: Score) -> Option<usize> {
+        let place = self.scores.iter().take_while(|other| (other.population, score.cells) >= (score.population, other.cells)).count();
+        if place >= self.capacity {
+            return None;
+        }
+        self.scores.insert(place, score);
+        self.scores.truncate(self.capacity);
+        Some(place)
+    }
+
+    pub fn scores(&self) -> &[Score] {
+        &self.scores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_table_keeps_the_best_scores() {
+        let score = |population, cells| Score { population, cells };
+        let mut scores = HighScores::new(3);
+
+        assert_eq!(scores.record(score(40, 10)), Some(0));
+        assert_eq!(scores.record(score(90, 12)), Some(0));
+        // as many cells from fewer goes first, and a plain tie goes after
+        assert_eq!(scores.record(score(40, 8)), Some(1));
+        assert_eq!(scores.record(score(40, 10)), None);
+        assert_eq!(scores.record(score(10, 1)), None);
+        assert_eq!(scores.scores(), &[score(90, 12), score(40, 8), score(40, 10)]);
+
+        let text = scores.to_text();
+        assert_eq!(text, "90\t12\n40\t8\n40\t10\n");
+        assert_eq!(HighScores::parse(&format!("{}not a score\n", text), 3), scores);
+    }
+}
@@ -0,0 +1,689 @@
+//! Boards: grids of cells that can be stepped under a rule. Everything goes
+//! through the `Board` trait, so the way cells are stored can change without
+//! touching the code that edits or draws them.
+
+use crate::rule::{Neighborhood, Transition};
+
+use rand::Rng;
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A cell's column and row on the board.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct GridPosition {
+    pub x: i16,
+    pub y: i16,
+}
+
+impl GridPosition {
+    pub fn new(x: i16, y: i16) -> Self {
+        GridPosition { x, y }
+    }
+}
+
+/// Positions order row by row, the same way the cells of a dense board are
+/// stored.
+impl Ord for GridPosition {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl PartialOrd for GridPosition {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<(i16, i16)> for GridPosition {
+    fn from(pos: (i16, i16)) -> Self {
+        GridPosition { x: pos.0, y: pos.1 }
+    }
+}
+
+/// A rectangle of cells dragged out between two corners (in either order).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Selection {
+    pub start: GridPosition,
+    pub end: GridPosition,
+}
+
+impl Selection {
+    pub fn new(pos: GridPosition) -> Self {
+        Selection { start: pos, end: pos }
+    }
+
+    pub fn whole_board(grid_size: (i16, i16)) -> Self {
+        Selection {
+            start: GridPosition::new(0, 0),
+            end: GridPosition::new(grid_size.0 - 1, grid_size.1 - 1),
+        }
+    }
+
+    /// Top-left and bottom-right corners, inclusive.
+    pub fn bounds(&self) -> (GridPosition, GridPosition) {
+        (
+            GridPosition::new(self.start.x.min(self.end.x), self.start.y.min(self.end.y)),
+            GridPosition::new(self.start.x.max(self.end.x), self.start.y.max(self.end.y)),
+            )
+    }
+
+    pub fn contains(&self, pos: GridPosition) -> bool {
+        let (min, max) = self.bounds();
+        pos.x >= min.x && pos.x <= max.x && pos.y >= min.y && pos.y <= max.y
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = GridPosition> {
+        let (min, max) = self.bounds();
+        (min.x..=max.x).flat_map(move |x| (min.y..=max.y).map(move |y| GridPosition::new(x, y)))
+    }
+}
+
+/// A grid of live and dead cells. The grid doesn't wrap: cells off the edge
+/// count as dead.
+pub trait Board {
+    /// Width and height in cells.
+    fn size(&self) -> (i16, i16);
+
+    fn is_alive(&self, pos: GridPosition) -> bool;
+
+    fn set_alive(&mut self, pos: GridPosition, alive: bool);
+
+    /// The next generation under `rule`, as a new board of the same kind.
+    /// The board itself is left alone.
+    fn next_generation(&self, rule: &dyn Transition) -> Box<dyn Board>;
+
+    /// Positions of the live cells, in no particular order. This should cost
+    /// in proportion to the live cells rather than the size of the board, since
+    /// drawing goes through it every frame.
+    fn live_cells(&self) -> Box<dyn Iterator<Item = GridPosition> + '_>;
+
+    /// The live cells inside `region`, like `live_cells`. Worth overriding
+    /// for boards that can find them without looking at every live cell, so
+    /// drawing a corner of a big board only costs what's in the corner.
+    fn live_cells_in(&self, region: &Selection) -> Box<dyn Iterator<Item = GridPosition> + '_> {
+        let region = *region;
+        Box::new(self.live_cells().filter(move |&pos| region.contains(pos)))
+    }
+
+    /// An empty board of the same kind, `size` cells big.
+    fn blank(&self, size: (i16, i16)) -> Box<dyn Board>;
+
+    fn box_clone(&self) -> Box<dyn Board>;
+
+    fn population(&self) -> usize {
+        self.live_cells().count()
+    }
+
+    /// The smallest selection holding every live cell, if there are any.
+    fn bounds(&self) -> Option<Selection> {
+        self.live_cells().fold(None, |bounds, pos| match bounds {
+            None => Some(Selection::new(pos)),
+            Some(Selection { start, end }) => Some(Selection {
+                start: GridPosition::new(start.x.min(pos.x), start.y.min(pos.y)),
+                end: GridPosition::new(end.x.max(pos.x), end.y.max(pos.y)),
+            }),
+        })
+    }
+}
+
+impl Clone for Box<dyn Board> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+/// The generation after `board` under `rule`.
+pub fn step(board: &dyn Board, rule: &dyn Transition) -> Box<dyn Board> {
+    board.next_generation(rule)
+}
+
+/// The generation after `board` under `rule`, with the cells in the
+/// `frozen` regions left as they are. If `counted`, frozen cells are
+/// neighbors like any other to the cells around them; if not, the rest of
+/// the board sees them as dead.
+pub fn step_frozen(board: &dyn Board, rule: &dyn Transition, frozen: &[Selection], counted: bool) -> Box<dyn Board> {
+    if frozen.is_empty() {
+        return step(board, rule);
+    }
+
+    let mut next = if counted {
+        step(board, rule)
+    } else {
+        let mut thawed = board.box_clone();
+        for region in frozen {
+            set_region(thawed.as_mut(), region, true);
+        }
+        step(thawed.as_ref(), rule)
+    };
+    for region in frozen {
+        for pos in region.positions() {
+            next.set_alive(pos, board.is_alive(pos));
+        }
+    }
+    next
+}
+
+/// Cells held alive or dead whatever the rule says, to wall in an arena,
+/// lay out a maze or drop obstacles in a pattern's way. Live walls are
+/// neighbors like any live cell.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Walls {
+    walls: BTreeMap<GridPosition, bool>,
+}
+
+impl Walls {
+    pub fn is_empty(&self) -> bool {
+        self.walls.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.walls.len()
+    }
+
+    /// Whether the cell at `pos` is held alive or dead, if it's a wall.
+    pub fn get(&self, pos: GridPosition) -> Option<bool> {
+        self.walls.get(&pos).copied()
+    }
+
+    /// Hold the cell at `pos` alive or dead, or let it follow the rule again.
+    pub fn set(&mut self, pos: GridPosition, wall: Option<bool>) {
+        match wall {
+            Some(alive) => self.walls.insert(pos, alive),
+            None => self.walls.remove(&pos),
+        };
+    }
+
+    pub fn clear(&mut self) {
+        self.walls.clear();
+    }
+
+    /// Every wall, and whether it's alive, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (GridPosition, bool)> + '_ {
+        self.walls.iter().map(|(&pos, &alive)| (pos, alive))
+    }
+
+    /// Put every wall on `board` back as it's held, leaving off the ones
+    /// past its edge.
+    pub fn apply(&self, board: &mut dyn Board) {
+        let (width, height) = board.size();
+        for (pos, alive) in self.iter().filter(|(pos, _)| pos.x >= 0 && pos.y >= 0 && pos.x < width && pos.y < height) {
+            board.set_alive(pos, alive);
+        }
+    }
+
+    /// Move every wall by `offset`, as when the board is resized around its
+    /// middle, dropping the ones that end up off a board of `size`.
+    pub fn shift(&mut self, offset: (i16, i16), size: (i16, i16)) {
+        self.walls = std::mem::take(&mut self.walls)
+            .into_iter()
+            .map(|(pos, alive)| (GridPosition::new(pos.x + offset.0, pos.y + offset.1), alive))
+            .filter(|(pos, _)| pos.x >= 0 && pos.x < size.0 && pos.y >= 0 && pos.y < size.1)
+            .collect();
+    }
+}
+
+/// Replace the cells in `region` with random noise where each cell is
+/// alive with probability `density`.
+pub fn fill_random<R: Rng>(board: &mut dyn Board, region: &Selection, density: f32, rng: &mut R) {
+    for pos in region.positions() {
+        board.set_alive(pos, rng.gen::<f32>() < density);
+    }
+}
+
+/// The symmetry a random soup is made with, in the names soup searches use:
+/// C2 and C4 turn the soup a half and a quarter turn about its center onto
+/// itself, D2 mirrors it left to right, and D4 mirrors it both ways.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SoupSymmetry {
+    Asymmetric,
+    C2,
+    C4,
+    D2,
+    D4,
+}
+
+pub const SOUP_SYMMETRIES: [SoupSymmetry; 5] = [SoupSymmetry::Asymmetric, SoupSymmetry::C2, SoupSymmetry::C4, SoupSymmetry::D2, SoupSymmetry::D4];
+
+impl SoupSymmetry {
+    pub fn name(self) -> &'static str {
+        match self {
+            SoupSymmetry::Asymmetric => "asymmetric",
+            SoupSymmetry::C2 => "C2",
+            SoupSymmetry::C4 => "C4",
+            SoupSymmetry::D2 => "D2",
+            SoupSymmetry::D4 => "D4",
+        }
+    }
+
+    /// The symmetry called `name`, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        SOUP_SYMMETRIES.iter().copied().find(|symmetry| symmetry.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The cells that have to match the one at `pos` in a soup `size` big,
+    /// `pos` among them, all measured from the soup's corner. A quarter turn
+    /// only fits a square, so C4 keeps to the biggest square in the middle
+    /// and leaves nothing to match the cells outside it.
+    fn images(self, pos: (i16, i16), size: (i16, i16)) -> Vec<(i16, i16)> {
+        let (x, y) = pos;
+        let (width, height) = size;
+        match self {
+            SoupSymmetry::Asymmetric => vec![pos],
+            SoupSymmetry::C2 => vec![pos, (width - 1 - x, height - 1 - y)],
+            SoupSymmetry::C4 => {
+                let side = width.min(height);
+                let corner = ((width - side) / 2, (height - side) / 2);
+                let (x, y) = (x - corner.0, y - corner.1);
+                if x < 0 || y < 0 || x >= side || y >= side {
+                    return Vec::new();
+                }
+                let turns = [(x, y), (side - 1 - y, x), (side - 1 - x, side - 1 - y), (y, side - 1 - x)];
+                turns.iter().map(|&(x, y)| (x + corner.0, y + corner.1)).collect()
+            }
+            SoupSymmetry::D2 => vec![pos, (width - 1 - x, y)],
+            SoupSymmetry::D4 => vec![pos, (width - 1 - x, y), (x, height - 1 - y), (width - 1 - x, height - 1 - y)],
+        }
+    }
+}
+
+/// Like `fill_random`, but with the noise made `symmetry`'s way about the
+/// middle of `region`. An asymmetric soup is exactly the one `fill_random`
+/// would make.
+pub fn fill_symmetric<R: Rng>(board: &mut dyn Board, region: &Selection, density: f32, symmetry: SoupSymmetry, rng: &mut R) {
+    if symmetry == SoupSymmetry::Asymmetric {
+        return fill_random(board, region, density, rng);
+    }
+
+    let (min, max) = region.bounds();
+    let size = (max.x - min.x + 1, max.y - min.y + 1);
+    for pos in region.positions() {
+        let local = (pos.x - min.x, pos.y - min.y);
+        let images = symmetry.images(local, size);
+        // every set of matching cells is decided once, at the first of them
+        // to come up
+        match images.iter().min_by_key(|&&(x, y)| (x, y)) {
+            None => board.set_alive(pos, false),
+            Some(&first) if first == local => {
+                let alive = rng.gen::<f32>() < density;
+                for (x, y) in images {
+                    board.set_alive(GridPosition::new(min.x + x, min.y + y), alive);
+                }
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// Kill or revive every cell in `region`.
+pub fn set_region(board: &mut dyn Board, region: &Selection, dead: bool) {
+    for pos in region.positions() {
+        board.set_alive(pos, !dead);
+    }
+}
+
+/// The cells that differ between two boards: those alive only in `after`,
+/// then those alive only in `before`. The boards needn't be the same size.
+pub fn diff(before: &dyn Board, after: &dyn Board) -> (Vec<GridPosition>, Vec<GridPosition>) {
+    let born = after.live_cells().filter(|&pos| !alive_in(before, pos)).collect();
+    let died = before.live_cells().filter(|&pos| !alive_in(after, pos)).collect();
+    (born, died)
+}
+
+/// Whether the cell at `pos` is alive, with anything off the board dead.
+fn alive_in(board: &dyn Board, pos: GridPosition) -> bool {
+    let (width, height) = board.size();
+    pos.x >= 0 && pos.y >= 0 && pos.x < width && pos.y < height && board.is_alive(pos)
+}
+
+/// The cell at `pos` and the eight around it, with the ones past the edge
+/// dead, since boards don't wrap.
+pub fn neighborhood(board: &dyn Board, pos: GridPosition) -> Neighborhood {
+    let mut mask = 0;
+    for dy in 0..3 {
+        for dx in 0..3 {
+            if alive_in(board, GridPosition::new(pos.x + dx - 1, pos.y + dy - 1)) {
+                mask |= 1 << (3 * dy + dx);
+            }
+        }
+    }
+    Neighborhood(mask)
+}
+
+/// How many generations in a row the cell at `pos` has been as it is on
+/// `board`, going back through `history` from the generation before it: 0
+/// if it's only just been born or died. If all of `history` goes by without
+/// it changing, it's been that way at least as long.
+pub fn age<'a>(board: &dyn Board, pos: GridPosition, history: impl IntoIterator<Item = &'a dyn Board>) -> usize {
+    let alive = alive_in(board, pos);
+    history.into_iter().take_while(|previous| alive_in(*previous, pos) == alive).count()
+}
+
+/// Whether each cell is alive, in one flat list, row by row, so the cell at
+/// column `x`, row `y` is at `y * width + x`. The live cells are also kept in a set, so
+/// going over them doesn't mean scanning the whole grid.
+#[derive(Clone, Debug)]
+pub struct DenseBoard {
+    width: i16,
+    height: i16,
+    cells: Vec<bool>,
+    live: BTreeSet<GridPosition>,
+}
+
+impl DenseBoard {
+    /// A board of dead cells.
+    pub fn new(grid_size: (i16, i16)) -> Self {
+        let (width, height) = grid_size;
+        let cells = vec![false; width as usize * height as usize];
+
+        DenseBoard { width, height, cells, live: BTreeSet::new() }
+    }
+
+    fn index(&self, pos: GridPosition) -> usize {
+        pos.y as usize * self.width as usize + pos.x as usize
+    }
+
+    /// The cell at `x`, `y` and the eight around it. The board doesn't wrap.
+    fn neighborhood(&self, x: usize, y: usize) -> Neighborhood {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut mask = 0;
+
+        for dy in 0..3 {
+            let row = y + dy;
+            // the first row and column are the ones before the cell
+            if row == 0 || row > height {
+                continue;
+            }
+            let row = &self.cells[(row - 1) * width..row * width];
+
+            for dx in 0..3 {
+                let column = x + dx;
+                if column != 0 && column <= width && row[column - 1] {
+                    mask |= 1 << (3 * dy + dx);
+                }
+            }
+        }
+
+        Neighborhood(mask)
+    }
+}
+
+impl Board for DenseBoard {
+    fn size(&self) -> (i16, i16) {
+        (self.width, self.height)
+    }
+
+    fn is_alive(&self, pos: GridPosition) -> bool {
+        self.cells[self.index(pos)]
+    }
+
+    fn set_alive(&mut self, pos: GridPosition, alive: bool) {
+        let index = self.index(pos);
+        self.cells[index] = alive;
+        if alive {
+            self.live.insert(pos);
+        } else {
+            self.live.remove(&pos);
+        }
+    }
+
+    fn next_generation(&self, rule: &dyn Transition) -> Box<dyn Board> {
+        let width = self.width as usize;
+        let mut cells = vec![false; self.cells.len()];
+        let mut live = Vec::new();
+
+        for (index, next) in cells.iter_mut().enumerate() {
+            let (x, y) = (index % width, index / width);
+            *next = rule.next_state(self.neighborhood(x, y));
+            if *next {
+                live.push(GridPosition::new(x as i16, y as i16));
+            }
+        }
+
+        // already in order, which lets the set be built in one go
+        let live = live.into_iter().collect();
+        Box::new(DenseBoard { width: self.width, height: self.height, cells, live })
+    }
+
+    fn live_cells(&self) -> Box<dyn Iterator<Item = GridPosition> + '_> {
+        Box::new(self.live.iter().copied())
+    }
+
+    fn live_cells_in(&self, region: &Selection) -> Box<dyn Iterator<Item = GridPosition> + '_> {
+        // cells are ordered row by row, so the rows in the region are one run of them
+        let (min, max) = region.bounds();
+        Box::new(self.live.range(min..=max).copied().filter(move |pos| pos.x >= min.x && pos.x <= max.x))
+    }
+
+    fn population(&self) -> usize {
+        self.live.len()
+    }
+
+    fn blank(&self, size: (i16, i16)) -> Box<dyn Board> {
+        Box::new(DenseBoard::new(size))
+    }
+
+    fn box_clone(&self) -> Box<dyn Board> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library;
+    use crate::pattern::Pattern;
+    use crate::rule::Rule;
+
+    fn library_pattern(name: &str) -> Pattern {
+        let (_, cells) = library::PATTERNS.iter().find(|(pattern, _)| *pattern == name).expect("no such pattern");
+        Pattern::from_plaintext(cells)
+    }
+
+    /// A board `size` cells big with `pattern` stamped at `origin`.
+    fn board_with(pattern: &Pattern, size: (i16, i16), origin: GridPosition) -> Box<dyn Board> {
+        let mut board = DenseBoard::new(size);
+        pattern.stamp(&mut board, origin);
+        Box::new(board)
+    }
+
+    fn live(board: &dyn Board) -> BTreeSet<GridPosition> {
+        board.live_cells().collect()
+    }
+
+    fn step_times(board: &dyn Board, rule: &dyn Transition, count: usize) -> Box<dyn Board> {
+        (0..count).fold(board.box_clone(), |board, _| step(board.as_ref(), rule))
+    }
+
+    #[test]
+    fn every_neighborhood_follows_conway() {
+        let rule = Rule::default();
+        let center = GridPosition::new(1, 1);
+
+        for neighborhood in 0..512u16 {
+            let mut board = DenseBoard::new((3, 3));
+            for (bit, pos) in Selection::whole_board((3, 3)).positions().enumerate() {
+                board.set_alive(pos, neighborhood & 1 << bit != 0);
+            }
+
+            let alive = board.is_alive(center);
+            let neighbors = (neighborhood & !(1 << 4)).count_ones();
+            let expected = neighbors == 3 || (alive && neighbors == 2);
+
+            let next = step(&board, &rule);
+            assert_eq!(next.is_alive(center), expected, "neighborhood {:09b}", neighborhood);
+        }
+    }
+
+    #[test]
+    fn step_leaves_the_board_alone() {
+        let board = board_with(&library_pattern("r-pentomino"), (20, 20), GridPosition::new(8, 8));
+        let before = live(board.as_ref());
+
+        let next = step(board.as_ref(), &Rule::default());
+        assert_eq!(live(board.as_ref()), before);
+        assert_ne!(live(next.as_ref()), before);
+        assert_eq!(next.population(), next.live_cells().count());
+    }
+
+    #[test]
+    fn still_lifes_stay_put() {
+        for name in &["block", "beehive", "loaf", "boat"] {
+            let board = board_with(&library_pattern(name), (10, 10), GridPosition::new(3, 3));
+            let next = step(board.as_ref(), &Rule::default());
+            assert_eq!(live(next.as_ref()), live(board.as_ref()), "{}", name);
+        }
+    }
+
+    #[test]
+    fn oscillators_return_after_their_period() {
+        for &(name, period) in &[("blinker", 2), ("toad", 2), ("beacon", 2), ("pulsar", 3), ("pentadecathlon", 15)] {
+            let board = board_with(&library_pattern(name), (30, 30), GridPosition::new(10, 10));
+            let start = live(board.as_ref());
+
+            for generation in 1..period {
+                assert_ne!(live(step_times(board.as_ref(), &Rule::default(), generation).as_ref()), start, "{} at {}", name, generation);
+            }
+            assert_eq!(live(step_times(board.as_ref(), &Rule::default(), period).as_ref()), start, "{}", name);
+        }
+    }
+
+    #[test]
+    fn spaceships_move() {
+        for &(name, period, (dx, dy)) in &[("glider", 4, (1, 1)), ("lightweight spaceship", 4, (-2, 0))] {
+            let board = board_with(&library_pattern(name), (30, 30), GridPosition::new(10, 10));
+            let moved: BTreeSet<_> = board.live_cells().map(|pos| GridPosition::new(pos.x + dx, pos.y + dy)).collect();
+
+            assert_eq!(live(step_times(board.as_ref(), &Rule::default(), period).as_ref()), moved, "{}", name);
+        }
+    }
+
+    #[test]
+    fn cells_off_the_edge_are_dead() {
+        // a blinker against the edge loses the cells it would grow past it
+        let board = board_with(&library_pattern("blinker"), (3, 3), GridPosition::new(0, 0));
+        let next = step(board.as_ref(), &Rule::default());
+        assert_eq!(live(next.as_ref()), [(1, 0), (1, 1)].iter().map(|&pos| pos.into()).collect());
+    }
+
+    #[test]
+    fn live_cells_in_a_region() {
+        let board = board_with(&library_pattern("pulsar"), (20, 20), GridPosition::new(2, 2));
+        let region = Selection { start: GridPosition::new(12, 3), end: GridPosition::new(4, 9) };
+
+        let expected: BTreeSet<_> = board.live_cells().filter(|&pos| region.contains(pos)).collect();
+        assert!(!expected.is_empty());
+        assert_eq!(board.live_cells_in(&region).collect::<BTreeSet<_>>(), expected);
+    }
+
+    #[test]
+    fn diff_finds_births_and_deaths() {
+        let before = board_with(&library_pattern("blinker"), (5, 5), GridPosition::new(1, 2));
+        let after = step(before.as_ref(), &Rule::default());
+        let (born, died) = diff(before.as_ref(), after.as_ref());
+
+        assert_eq!(born, vec![GridPosition::new(2, 1), GridPosition::new(2, 3)]);
+        assert_eq!(died, vec![GridPosition::new(1, 2), GridPosition::new(3, 2)]);
+        assert_eq!(diff(after.as_ref(), after.as_ref()), (vec![], vec![]));
+    }
+
+    #[test]
+    fn neighborhoods_and_ages_of_single_cells() {
+        let before = board_with(&library_pattern("blinker"), (5, 5), GridPosition::new(1, 2));
+        let after = step(before.as_ref(), &Rule::default());
+
+        let middle = neighborhood(after.as_ref(), GridPosition::new(2, 2));
+        assert!(middle.center());
+        assert_eq!(middle.live_neighbors(), 2);
+        assert_eq!(middle.to_string(), ".O.\n.O.\n.O.");
+        assert_eq!(neighborhood(after.as_ref(), GridPosition::new(2, 0)).to_string(), "...\n...\n.O.");
+
+        let history = [before.as_ref(), after.as_ref(), before.as_ref()];
+        assert_eq!(age(after.as_ref(), GridPosition::new(2, 2), history.iter().copied()), 3);
+        assert_eq!(age(after.as_ref(), GridPosition::new(2, 1), history.iter().copied()), 0);
+        assert_eq!(age(after.as_ref(), GridPosition::new(0, 0), history.iter().copied()), 3);
+    }
+
+    #[test]
+    fn frozen_cells_stay_put_and_count_if_asked() {
+        // a blinker with its bottom cell frozen, next to an empty frozen region
+        let board = board_with(&library_pattern("blinker"), (7, 7), GridPosition::new(2, 3));
+        let frozen = [Selection::new(GridPosition::new(4, 3)), Selection { start: GridPosition::new(0, 0), end: GridPosition::new(6, 1) }];
+        let counted = step_frozen(board.as_ref(), &Rule::default(), &frozen, true);
+        assert_eq!(
+            live(counted.as_ref()),
+            [(3, 2), (3, 3), (3, 4), (4, 3)].iter().map(|&pos| GridPosition::from(pos)).collect()
+        );
+
+        // the frozen cell still lives, but the blinker's middle dies alone
+        let uncounted = step_frozen(board.as_ref(), &Rule::default(), &frozen, false);
+        assert_eq!(live(uncounted.as_ref()), [(4, 3)].iter().map(|&pos| GridPosition::from(pos)).collect());
+
+        let free = step_frozen(board.as_ref(), &Rule::default(), &[], false);
+        assert_eq!(live(free.as_ref()), live(step(board.as_ref(), &Rule::default()).as_ref()));
+    }
+
+    #[test]
+    fn walls_hold_whatever_the_rule_says() {
+        // a live wall keeps a lone cell company, and a dead one stops a birth
+        let mut board = DenseBoard::new((6, 6));
+        for &pos in &[(1, 1), (2, 1), (3, 1), (1, 4)] {
+            board.set_alive(GridPosition::from(pos), true);
+        }
+        let mut walls = Walls::default();
+        walls.set(GridPosition::new(1, 4), Some(true));
+        walls.set(GridPosition::new(2, 0), Some(false));
+        walls.set(GridPosition::new(9, 9), Some(true));
+        walls.set(GridPosition::new(9, 9), None);
+        assert_eq!(walls.len(), 2);
+
+        let mut next = step(&board, &Rule::default());
+        walls.apply(next.as_mut());
+        assert_eq!(live(next.as_ref()), [(2, 1), (2, 2), (1, 4)].iter().map(|&pos| GridPosition::from(pos)).collect());
+
+        walls.shift((4, 0), (6, 6));
+        assert_eq!(walls.iter().collect::<Vec<_>>(), vec![(GridPosition::new(5, 4), true)]);
+    }
+
+    #[test]
+    fn symmetric_soups_match_their_images() {
+        use rand::SeedableRng;
+
+        let region = Selection { start: GridPosition::new(2, 3), end: GridPosition::new(13, 10) };
+        let soup = |symmetry| {
+            let mut board = DenseBoard::new((16, 16));
+            fill_symmetric(&mut board, &region, 0.5, symmetry, &mut rand::rngs::StdRng::seed_from_u64(3));
+            board
+        };
+        let alive = |board: &DenseBoard, x: i16, y: i16| board.is_alive(GridPosition::new(x, y));
+
+        // the region's 12x8, from columns 2 to 13 and rows 3 to 10
+        let c2 = soup(SoupSymmetry::C2);
+        let d2 = soup(SoupSymmetry::D2);
+        let d4 = soup(SoupSymmetry::D4);
+        for pos in region.positions() {
+            let (x, y) = (pos.x, pos.y);
+            assert_eq!(alive(&c2, x, y), alive(&c2, 15 - x, 13 - y));
+            assert_eq!(alive(&d2, x, y), alive(&d2, 15 - x, y));
+            assert_eq!(alive(&d4, x, y), alive(&d4, x, 13 - y));
+            assert_eq!(alive(&d4, x, y), alive(&d4, 15 - x, 13 - y));
+        }
+        assert!(c2.population() > 10);
+        assert!(c2.live_cells().all(|pos| region.contains(pos)));
+
+        // the quarter turns fill the 8x8 square in the middle, columns 4 to 11
+        let c4 = soup(SoupSymmetry::C4);
+        let square = Selection { start: GridPosition::new(4, 3), end: GridPosition::new(11, 10) };
+        for pos in square.positions() {
+            let (x, y) = (pos.x - 4, pos.y - 3);
+            assert_eq!(alive(&c4, pos.x, pos.y), alive(&c4, 4 + 7 - y, 3 + x));
+        }
+        assert!(c4.live_cells().all(|pos| pos.x >= 4 && pos.x <= 11));
+
+        let mut asymmetric = DenseBoard::new((16, 16));
+        fill_random(&mut asymmetric, &region, 0.5, &mut rand::rngs::StdRng::seed_from_u64(3));
+        assert_eq!(live(&soup(SoupSymmetry::Asymmetric)), live(&asymmetric));
+        assert_eq!(SoupSymmetry::from_name("d4"), Some(SoupSymmetry::D4));
+    }
+}
@@ -0,0 +1,484 @@
+//! Broadcasting a board for others to watch, read only: a long search or a
+//! demo can run in one game while other games, started with `--watch`, and
+//! browsers follow along live.
+//!
+//! The board goes out over a WebSocket, in the same lines as a shared board
+//! (see `net`), one to a text frame: the whole board to each spectator as
+//! they arrive, then what's changed each time it's sent. Anything that asks
+//! for a page instead of a WebSocket gets one that connects back and draws
+//! the board, so `http://<address>:7879` in a browser is all it takes.
+//! Spectators are never listened to, apart from them leaving.
+
+use crate::board::{self, Board};
+use crate::net::{self, Message, Socket};
+
+use std::io;
+use std::net::{TcpListener, TcpStream};
+
+/// The port games broadcast on and watch unless told otherwise.
+pub const DEFAULT_PORT: u16 = 7879;
+
+/// The page served to browsers that come to watch.
+const WATCH_PAGE: &str = include_str!("../web/watch.html");
+
+/// How much a spectator can send without it making sense, in bytes, before
+/// it's let go.
+const MAX_INCOMING: usize = 64 * 1024;
+/// How much a broadcast can send without finishing a frame, in bytes, before
+/// it's let go: room for the whole of the biggest board there is, at ` x,y`
+/// for every cell.
+const MAX_WATCHED: usize = MAX_INCOMING + 10 * net::MAX_BOARD_SIZE as usize * net::MAX_BOARD_SIZE as usize;
+
+const TEXT: u8 = 0x1;
+const CLOSE: u8 = 0x8;
+
+/// Where a spectator has got to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    /// Still sending its request.
+    Arriving,
+    /// Connected, and waiting for the whole board.
+    Joining,
+    Watching,
+    /// Asked for the page, and gets let go once it's been sent.
+    Leaving,
+}
+
+#[derive(Debug)]
+struct Spectator {
+    socket: Socket,
+    state: State,
+}
+
+impl Spectator {
+    /// Take in whatever it's sent: its request, if it hasn't finished
+    /// sending that yet, and after that only whether it's leaving.
+    fn read(&mut self) {
+        self.socket.read();
+        match self.state {
+            State::Arriving => {
+                let end = match self.socket.incoming.windows(4).position(|bytes| bytes == b"\r\n\r\n") {
+                    Some(end) => end,
+                    None => {
+                        if self.socket.incoming.len() > MAX_INCOMING {
+                            self.socket.close();
+                        }
+                        return;
+                    }
+                };
+                let request = String::from_utf8_lossy(&self.socket.incoming[..end]).into_owned();
+                self.socket.incoming.clear();
+                self.answer(&request);
+            }
+            State::Joining | State::Watching => {
+                while let Some((opcode, _, used)) = decode_frame(&self.socket.incoming) {
+                    self.socket.incoming.drain(..used);
+                    if opcode == CLOSE {
+                        self.socket.write(&encode_frame(CLOSE, &[]));
+                        self.socket.close();
+                    }
+                }
+                if self.socket.incoming.len() > MAX_INCOMING {
+                    self.socket.close();
+                }
+            }
+            State::Leaving => self.socket.incoming.clear(),
+        }
+        if self.state == State::Leaving && self.socket.is_flushed() {
+            self.socket.close();
+        }
+    }
+
+    /// Answer the HTTP `request`: a WebSocket if that's what it asks for, and
+    /// the page otherwise.
+    fn answer(&mut self, request: &str) {
+        if !request.starts_with("GET ") {
+            self.socket.write(b"HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            self.state = State::Leaving;
+            return;
+        }
+
+        let key = request.lines().skip(1).find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            if name.trim().eq_ignore_ascii_case("sec-websocket-key") {
+                Some(value.trim())
+            } else {
+                None
+            }
+        });
+        match key {
+            Some(key) => {
+                let response = format!(
+                    "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                    accept_key(key)
+                );
+                self.socket.write(response.as_bytes());
+                self.state = State::Joining;
+            }
+            None => {
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    WATCH_PAGE.len(),
+                    WATCH_PAGE
+                );
+                self.socket.write(response.as_bytes());
+                self.state = State::Leaving;
+            }
+        }
+    }
+
+    fn send(&mut self, message: &Message) {
+        self.socket.write(&encode_frame(TEXT, message.to_line().as_bytes()));
+    }
+}
+
+/// The broadcasting end: everyone watching, the socket more can come to
+/// watch on, and the board as they last saw it.
+pub struct Broadcast {
+    listener: TcpListener,
+    spectators: Vec<Spectator>,
+    seen: Option<(Box<dyn Board>, u64)>,
+}
+
+impl Broadcast {
+    /// Broadcast on `address`, which can be just a port to broadcast on every interface.
+    pub fn bind(address: &str) -> io::Result<Self> {
+        Ok(Broadcast { listener: net::listen(address)?, spectators: Vec::new(), seen: None })
+    }
+
+    /// The port it's broadcasting on, which is handy when asked for port 0.
+    pub fn port(&self) -> io::Result<u16> {
+        Ok(self.listener.local_addr()?.port())
+    }
+
+    /// How many are watching, not counting browsers that only came for the page.
+    pub fn spectator_count(&self) -> usize {
+        self.spectators.iter().filter(|spectator| matches!(spectator.state, State::Joining | State::Watching)).count()
+    }
+
+    /// Let in everyone who's come to watch, hear from everyone already here,
+    /// and show them all `board`, at `generation`: the whole of it to anyone
+    /// who's just arrived, and what's changed since last time to the rest.
+    /// Returns how many arrived and how many left since last time.
+    pub fn send(&mut self, board: &dyn Board, generation: u64) -> (usize, usize) {
+        while let Ok((stream, _)) = self.listener.accept() {
+            if let Ok(socket) = Socket::new(stream) {
+                self.spectators.push(Spectator { socket, state: State::Arriving });
+            }
+        }
+        for spectator in &mut self.spectators {
+            spectator.read();
+        }
+        let gone = self.spectators.iter().filter(|spectator| spectator.socket.is_closed() && matches!(spectator.state, State::Joining | State::Watching)).count();
+        self.spectators.retain(|spectator| !spectator.socket.is_closed());
+
+        // nobody to tell, so there's no need to keep track
+        if self.spectator_count() == 0 {
+            self.seen = None;
+            return (0, gone);
+        }
+
+        let changes = match &self.seen {
+            Some((seen, seen_generation)) if seen.size() == board.size() => {
+                let (born, died) = board::diff(seen.as_ref(), board);
+                if born.is_empty() && died.is_empty() && *seen_generation == generation {
+                    None
+                } else {
+                    Some(Message::Changes { generation, born, died })
+                }
+            }
+            _ => Some(Message::whole_board(board, generation)),
+        };
+        let mut whole = None;
+        let mut arrived = 0;
+        for spectator in &mut self.spectators {
+            match spectator.state {
+                State::Joining => {
+                    spectator.send(whole.get_or_insert_with(|| Message::whole_board(board, generation)));
+                    spectator.state = State::Watching;
+                    arrived += 1;
+                }
+                State::Watching => {
+                    if let Some(changes) = &changes {
+                        spectator.send(changes);
+                    }
+                }
+                State::Arriving | State::Leaving => {}
+            }
+        }
+        self.seen = Some((board.box_clone(), generation));
+        (arrived, gone)
+    }
+}
+
+/// The watching end, connected to a game's broadcast.
+#[derive(Debug)]
+pub struct Watcher {
+    socket: Socket,
+    connected: bool,
+}
+
+impl Watcher {
+    /// An address, like `example.com:7879`, with the default port if it doesn't give one.
+    pub fn connect(address: &str) -> io::Result<Self> {
+        let address = net::resolve(address, DEFAULT_PORT)?;
+        let mut socket = Socket::new(TcpStream::connect(address)?)?;
+        let key: [u8; 16] = rand::random();
+        let request = format!(
+            "GET / HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\r\n",
+            address,
+            base64::encode(&key)
+        );
+        socket.write(request.as_bytes());
+        Ok(Watcher { socket, connected: false })
+    }
+
+    /// Every message that's arrived whole since last time. Frames that
+    /// aren't messages are left out.
+    pub fn receive(&mut self) -> Vec<Message> {
+        self.socket.read();
+        if !self.connected {
+            let end = match self.socket.incoming.windows(4).position(|bytes| bytes == b"\r\n\r\n") {
+                Some(end) => end,
+                None => {
+                    if self.socket.incoming.len() > MAX_INCOMING {
+                        self.socket.close();
+                    }
+                    return Vec::new();
+                }
+            };
+            let response: Vec<u8> = self.socket.incoming.drain(..end + 4).collect();
+            if !response.starts_with(b"HTTP/1.1 101") {
+                self.socket.close();
+                return Vec::new();
+            }
+            self.connected = true;
+        }
+
+        let mut messages = Vec::new();
+        while let Some((opcode, payload, used)) = decode_frame(&self.socket.incoming) {
+            self.socket.incoming.drain(..used);
+            match opcode {
+                TEXT => {
+                    if let Ok(message) = Message::parse(&String::from_utf8_lossy(&payload)) {
+                        messages.push(message);
+                    }
+                }
+                CLOSE => self.socket.close(),
+                _ => {}
+            }
+        }
+        if self.socket.incoming.len() > MAX_WATCHED {
+            self.socket.close();
+        }
+        messages
+    }
+
+    /// Whether the broadcast has ended.
+    pub fn is_closed(&self) -> bool {
+        self.socket.is_closed()
+    }
+}
+
+/// A whole, unmasked frame, the way a server sends them.
+fn encode_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len if len < 126 => frame.push(len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// The frame at the start of `bytes`, masked or not, as its opcode, its
+/// payload and how many bytes it took up, or `None` if it hasn't all arrived.
+fn decode_frame(bytes: &[u8]) -> Option<(u8, Vec<u8>, usize)> {
+    let opcode = bytes.first()? & 0x0f;
+    let masked = bytes.get(1)? & 0x80 != 0;
+    let (len, mut used) = match bytes[1] & 0x7f {
+        126 => (u16::from_be_bytes([*bytes.get(2)?, *bytes.get(3)?]) as usize, 4),
+        127 => {
+            let mut len = [0; 8];
+            len.copy_from_slice(bytes.get(2..10)?);
+            (u64::from_be_bytes(len) as usize, 10)
+        }
+        len => (len as usize, 2),
+    };
+    let mask = if masked {
+        used += 4;
+        Some(bytes.get(used - 4..used)?)
+    } else {
+        None
+    };
+
+    let mut payload = bytes.get(used..used.checked_add(len)?)?.to_vec();
+    if let Some(mask) = mask {
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+    }
+    Some((opcode, payload, used + len))
+}
+
+/// What a server answers a WebSocket handshake's `Sec-WebSocket-Key` with.
+pub fn accept_key(key: &str) -> String {
+    base64::encode(&sha1(format!("{}258EAFA5-E914-47DA-95CA-C5AB0DC85B11", key).as_bytes()))
+}
+
+/// The SHA-1 hash of `data`, which a WebSocket handshake needs and nothing
+/// else here does.
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for chunk in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in chunk.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, &word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (h, x) in h.iter_mut().zip(&[a, b, c, d, e]) {
+            *h = h.wrapping_add(*x);
+        }
+    }
+
+    let mut hash = [0; 20];
+    for (bytes, word) in hash.chunks_mut(4).zip(&h) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{DenseBoard, GridPosition};
+
+    use std::io::{Read, Write};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn the_handshake_matches_the_rfc() {
+        let hex = |bytes: &[u8]| bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+        assert_eq!(hex(&sha1(b"abc")), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(hex(&sha1(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let hello = [0x81, 0x05, 0x48, 0x65, 0x6c, 0x6c, 0x6f];
+        assert_eq!(encode_frame(TEXT, b"Hello"), hello);
+        assert_eq!(decode_frame(&hello), Some((TEXT, b"Hello".to_vec(), 7)));
+        // the same, masked the way browsers send it
+        let masked = [0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58];
+        assert_eq!(decode_frame(&masked), Some((TEXT, b"Hello".to_vec(), 11)));
+        assert_eq!(decode_frame(&masked[..6]), None);
+
+        let long = vec![b'x'; 70_000];
+        let frame = encode_frame(TEXT, &long);
+        assert_eq!(&frame[..2], &[0x81, 127]);
+        assert_eq!(decode_frame(&frame), Some((TEXT, long, 70_010)));
+        assert_eq!(&encode_frame(TEXT, &[0; 300])[..4], &[0x81, 126, 1, 44]);
+    }
+
+    #[test]
+    fn watchers_see_the_board_change() {
+        let mut broadcast = Broadcast::bind("127.0.0.1:0").unwrap();
+        let address = format!("127.0.0.1:{}", broadcast.port().unwrap());
+        let mut watcher = Watcher::connect(&address).unwrap();
+
+        let mut board = DenseBoard::new((8, 8));
+        board.set_alive(GridPosition::new(1, 2), true);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut received = Vec::new();
+        while received.is_empty() && Instant::now() < deadline {
+            broadcast.send(&board, 3);
+            received = watcher.receive();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(received, vec![Message::Board { size: (8, 8), generation: 3, cells: vec![GridPosition::new(1, 2)] }]);
+        assert_eq!(broadcast.spectator_count(), 1);
+
+        board.set_alive(GridPosition::new(1, 2), false);
+        broadcast.send(&board, 4);
+        let mut received = Vec::new();
+        while received.is_empty() && Instant::now() < deadline {
+            received = watcher.receive();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(received, vec![Message::Changes { generation: 4, born: Vec::new(), died: vec![GridPosition::new(1, 2)] }]);
+    }
+
+    #[test]
+    fn watchers_drop_broadcasts_that_never_finish() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut watcher = Watcher::connect(&listener.local_addr().unwrap().to_string()).unwrap();
+        let (mut stream, _) = listener.accept().unwrap();
+
+        // a response that never gets to the end of its headers
+        stream.write_all(&vec![b'x'; MAX_INCOMING + 1]).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !watcher.is_closed() && Instant::now() < deadline {
+            assert!(watcher.receive().is_empty());
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(watcher.is_closed());
+    }
+
+    #[test]
+    fn browsers_get_the_page() {
+        let mut broadcast = Broadcast::bind("127.0.0.1:0").unwrap();
+        let mut stream = TcpStream::connect(format!("127.0.0.1:{}", broadcast.port().unwrap())).unwrap();
+        stream.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        stream.set_read_timeout(Some(Duration::from_millis(10))).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut response = Vec::new();
+        while Instant::now() < deadline {
+            broadcast.send(&DenseBoard::new((4, 4)), 0);
+            let mut buffer = [0; 4096];
+            match stream.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => response.extend_from_slice(&buffer[..read]),
+                Err(_) => {}
+            }
+        }
+        let response = String::from_utf8(response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.ends_with(WATCH_PAGE));
+        assert_eq!(broadcast.spectator_count(), 0);
+    }
+}
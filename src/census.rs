@@ -0,0 +1,197 @@
+//! Counting the objects a board has settled into, the way soup searchers
+//! tally up ash: every group of touching live cells is looked up, in any
+//! orientation and phase, among the common still lifes, oscillators and
+//! spaceships of Conway's Life.
+
+use crate::board::{self, Board, DenseBoard, GridPosition};
+use crate::pattern::Pattern;
+use crate::rule::Rule;
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::OnceLock;
+
+/// The objects a census recognizes, in plaintext format.
+pub const OBJECTS: &[(&str, &str)] = &[
+    ("block", "OO\nOO"),
+    ("blinker", "OOO"),
+    ("beehive", ".OO.\nO..O\n.OO."),
+    ("loaf", ".OO.\nO..O\n.O.O\n..O."),
+    ("boat", "OO.\nO.O\n.O."),
+    ("ship", "OO.\nO.O\n.OO"),
+    ("tub", ".O.\nO.O\n.O."),
+    ("pond", ".OO.\nO..O\nO..O\n.OO."),
+    ("long boat", "OO..\nO.O.\n.O.O\n..O."),
+    ("barge", ".O..\nO.O.\n.O.O\n..O."),
+    ("mango", ".OO..\nO..O.\n.O..O\n..OO."),
+    ("glider", ".O.\n..O\nOOO"),
+    ("toad", ".OOO\nOOO."),
+    ("beacon", "OO..\nOO..\n..OO\n..OO"),
+    ("lightweight spaceship", ".O..O\nO....\nO...O\nOOOO."),
+];
+
+/// How many generations of each object are looked at for its phases, which
+/// covers every phase of everything in `OBJECTS`.
+const PHASES: usize = 4;
+
+/// How many of each kind of object there are, most common first.
+pub type Census = Vec<(String, usize)>;
+
+/// Take a census of `board`. Objects are told apart by which cells touch,
+/// now or next generation, so ones that touch each other count as a single
+/// unknown object.
+pub fn census(board: &dyn Board) -> Census {
+    let known = known_objects();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for object in objects(board) {
+        let name = match known.get(&canonical(&object)) {
+            Some(name) => name.to_string(),
+            None => format!("unknown {}-cell object", object.cells.len()),
+        };
+        *counts.entry(name).or_insert(0) += 1;
+    }
+
+    let mut census: Census = counts.into_iter().collect();
+    census.sort_by(|(name, count), (other_name, other_count)| other_count.cmp(count).then(name.cmp(other_name)));
+    census
+}
+
+/// A census in a line, like `12 block, 8 blinker, 1 glider`, listing at most
+/// `kinds` kinds of object.
+pub fn summary(census: &Census, kinds: usize) -> String {
+    if census.is_empty() {
+        return "nothing alive".to_string();
+    }
+
+    let mut summary: Vec<String> = census.iter().take(kinds).map(|(name, count)| format!("{} {}", count, name)).collect();
+    if census.len() > kinds {
+        summary.push(format!("{} more kinds", census.len() - kinds));
+    }
+    summary.join(", ")
+}
+
+/// The groups of live cells that touch, even at a corner, each as a
+/// pattern. Cells alive next generation join up the groups they touch too,
+/// so an oscillator like the toad, which comes apart in one of its phases,
+/// is still one object in that phase.
+fn objects(board: &dyn Board) -> Vec<Pattern> {
+    let live: BTreeSet<GridPosition> = board.live_cells().collect();
+    let mut unvisited: BTreeSet<GridPosition> = board::step(board, &Rule::default()).live_cells().chain(live.iter().copied()).collect();
+    let mut objects = Vec::new();
+
+    for &start in &live {
+        if !unvisited.remove(&start) {
+            continue;
+        }
+        let mut cells = vec![start];
+        let mut next = 0;
+        while next < cells.len() {
+            let pos = cells[next];
+            next += 1;
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let neighbor = GridPosition::new(pos.x + dx, pos.y + dy);
+                    if unvisited.remove(&neighbor) {
+                        cells.push(neighbor);
+                    }
+                }
+            }
+        }
+        cells.retain(|pos| live.contains(pos));
+        objects.push(normalized(cells));
+    }
+
+    objects
+}
+
+/// `cells` moved so the pattern starts in the top-left corner.
+fn normalized(cells: Vec<GridPosition>) -> Pattern {
+    let (left, top) = (cells.iter().map(|pos| pos.x).min().unwrap_or(0), cells.iter().map(|pos| pos.y).min().unwrap_or(0));
+    let cells: Vec<_> = cells.into_iter().map(|pos| GridPosition::new(pos.x - left, pos.y - top)).collect();
+
+    Pattern {
+        width: cells.iter().map(|pos| pos.x + 1).max().unwrap_or(0),
+        height: cells.iter().map(|pos| pos.y + 1).max().unwrap_or(0),
+        cells,
+    }
+}
+
+/// The same cells for every rotation and reflection of a pattern: the
+/// smallest of their sorted cell lists.
+fn canonical(pattern: &Pattern) -> Vec<GridPosition> {
+    let rotations = [pattern.clone(), pattern.rotate_clockwise(), pattern.rotate_clockwise().rotate_clockwise(), pattern.rotate_counterclockwise()];
+
+    rotations
+        .iter()
+        .flat_map(|rotation| vec![rotation.clone(), rotation.flip_horizontal()])
+        .map(|orientation| {
+            let mut cells = orientation.cells;
+            cells.sort();
+            cells
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// Every phase of every object in `OBJECTS`, by its canonical cells, worked
+/// out the first time a census is taken.
+fn known_objects() -> &'static HashMap<Vec<GridPosition>, &'static str> {
+    static KNOWN: OnceLock<HashMap<Vec<GridPosition>, &'static str>> = OnceLock::new();
+    KNOWN.get_or_init(|| {
+        let mut known = HashMap::new();
+
+        for &(name, cells) in OBJECTS {
+            let pattern = Pattern::from_plaintext(cells);
+            // enough room to move a cell a generation without reaching the edge
+            let margin = PHASES as i16 + 1;
+            let mut board: Box<dyn Board> = Box::new(DenseBoard::new((pattern.width + 2 * margin, pattern.height + 2 * margin)));
+            pattern.stamp(board.as_mut(), GridPosition::new(margin, margin));
+
+            for _ in 0..PHASES {
+                known.insert(canonical(&normalized(board.live_cells().collect())), name);
+                board = board::step(board.as_ref(), &Rule::default());
+            }
+        }
+
+        known
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_objects_in_any_orientation_and_phase() {
+        let mut board = DenseBoard::new((40, 40));
+        Pattern::from_plaintext("OO\nOO").stamp(&mut board, GridPosition::new(1, 1));
+        Pattern::from_plaintext("OO\nOO").stamp(&mut board, GridPosition::new(10, 1));
+        // a blinker on its side, and a glider in a later phase facing the other way
+        Pattern::from_plaintext("O\nO\nO").stamp(&mut board, GridPosition::new(20, 1));
+        Pattern::from_plaintext("O.O\n.OO\n.O.").stamp(&mut board, GridPosition::new(1, 20));
+        Pattern::from_plaintext(".OO.\nO..O\n.O.O\n..O.").rotate_clockwise().stamp(&mut board, GridPosition::new(20, 20));
+        Pattern::from_plaintext("OOOOO").stamp(&mut board, GridPosition::new(30, 30));
+
+        let expected: Census = vec![
+            ("block".to_string(), 2),
+            ("blinker".to_string(), 1),
+            ("glider".to_string(), 1),
+            ("loaf".to_string(), 1),
+            ("unknown 5-cell object".to_string(), 1),
+        ];
+        assert_eq!(census(&board), expected);
+    }
+
+    #[test]
+    fn oscillators_are_one_object_in_every_phase() {
+        for &name in &["toad", "beacon"] {
+            let pattern = Pattern::from_plaintext(OBJECTS.iter().find(|&&(object, _)| object == name).unwrap().1);
+            let mut board: Box<dyn Board> = Box::new(DenseBoard::new((12, 12)));
+            pattern.stamp(board.as_mut(), GridPosition::new(4, 4));
+            for phase in 0..2 {
+                assert_eq!(census(board.as_ref()), vec![(name.to_string(), 1)], "{} phase {}", name, phase);
+                board = board::step(board.as_ref(), &Rule::default());
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! The system clipboard, reached through whichever of the usual command
+//! line tools is installed, since ggez doesn't offer one.
+
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+
+/// Commands that put what's written to them on the clipboard, in the order
+/// they're tried.
+const COPY_COMMANDS: &[&[&str]] = &[
+    &["pbcopy"],
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+    &["clip.exe"],
+];
+
+/// Commands that write out what's on the clipboard, in the order they're tried.
+const PASTE_COMMANDS: &[&[&str]] = &[
+    &["pbpaste"],
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-out"],
+    &["xsel", "--clipboard", "--output"],
+    &["powershell.exe", "-NoProfile", "-Command", "Get-Clipboard"],
+];
+
+/// Put `text` on the clipboard.
+pub fn copy(text: &str) -> io::Result<()> {
+    first_working(COPY_COMMANDS, |command| {
+        let mut child = Command::new(command[0]).args(&command[1..]).stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null()).spawn()?;
+        child.stdin.take().expect("stdin is piped").write_all(text.as_bytes())?;
+        success(child.wait()?, command)
+    })
+}
+
+/// What's on the clipboard, as text.
+pub fn paste() -> io::Result<String> {
+    first_working(PASTE_COMMANDS, |command| {
+        let mut child = Command::new(command[0]).args(&command[1..]).stdout(Stdio::piped()).stderr(Stdio::null()).spawn()?;
+        let mut text = String::new();
+        child.stdout.take().expect("stdout is piped").read_to_string(&mut text)?;
+        success(child.wait()?, command)?;
+        Ok(text)
+    })
+}
+
+/// What `run` gives for the first of `commands` that's installed, or why
+/// the last one didn't work if none are.
+fn first_working<T>(commands: &[&[&str]], mut run: impl FnMut(&[&str]) -> io::Result<T>) -> io::Result<T> {
+    let mut last = io::Error::new(io::ErrorKind::NotFound, "no clipboard tool installed");
+    for command in commands {
+        match run(command) {
+            Ok(result) => return Ok(result),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => last = err,
+        }
+    }
+    Err(last)
+}
+
+fn success(status: std::process::ExitStatus, command: &[&str]) -> io::Result<()> {
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("{} failed", command[0])))
+    }
+}
@@ -0,0 +1,191 @@
+//! Spotting when a board settles down into a still life or an oscillator, by
+//! remembering a hash of each of its recent generations, and working out what
+//! a pattern does: dies out, oscillates or travels.
+
+use crate::board::{self, Board, DenseBoard, GridPosition};
+use crate::pattern::Pattern;
+use crate::rule::Transition;
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+
+/// A hash of the board's size and which of its cells are alive. Boards that
+/// hash the same are taken to be the same.
+pub fn board_hash(board: &dyn Board) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    board.size().hash(&mut hasher);
+    for pos in board.live_cells() {
+        pos.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// A hash of the live cells relative to the top-left one of them, so a
+/// pattern hashes the same wherever it is on the board.
+fn shape_hash(board: &dyn Board, origin: GridPosition) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for pos in board.live_cells() {
+        (pos.x - origin.x, pos.y - origin.y).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// A board repeating itself: it's been the same every `period` generations
+/// since `start`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cycle {
+    pub start: u64,
+    pub period: u64,
+}
+
+/// Watches generations go by for one that matches a recent one.
+#[derive(Clone, Debug)]
+pub struct CycleDetector {
+    max_period: usize,
+    /// Generation numbers and hashes, oldest first.
+    recent: VecDeque<(u64, u64)>,
+}
+
+impl CycleDetector {
+    /// Look for cycles up to `max_period` generations long.
+    pub fn new(max_period: usize) -> Self {
+        CycleDetector { max_period, recent: VecDeque::with_capacity(max_period) }
+    }
+
+    /// Remember `board` as it is at `generation`, and return the cycle it's
+    /// in if it's the same as one of the generations before it. Generations
+    /// have to be seen one after another; a gap starts over.
+    pub fn observe(&mut self, generation: u64, board: &dyn Board) -> Option<Cycle> {
+        if self.recent.back().is_some_and(|&(last, _)| last + 1 != generation) {
+            self.recent.clear();
+        }
+
+        let hash = board_hash(board);
+        let cycle = self
+            .recent
+            .iter()
+            .rev()
+            .find(|&&(_, seen)| seen == hash)
+            .map(|&(start, _)| Cycle { start, period: generation - start });
+
+        if self.recent.len() == self.max_period {
+            self.recent.pop_front();
+        }
+        self.recent.push_back((generation, hash));
+        cycle
+    }
+
+    /// Forget everything seen so far, for when the board is changed by hand.
+    pub fn clear(&mut self) {
+        self.recent.clear();
+    }
+}
+
+/// What a pattern turns out to do when left to run.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Behavior {
+    /// Every cell is dead by `generation`.
+    Dies { generation: u64 },
+    /// The same every `period` generations from `start` on, in the same
+    /// place. Still lifes have a period of 1.
+    Oscillates { start: u64, period: u64 },
+    /// The same shape every `period` generations from `start` on, moved by
+    /// `displacement`.
+    Travels { start: u64, period: u64, displacement: (i16, i16) },
+}
+
+/// Run `pattern` under `rule` for up to `max_generations` and see what it
+/// does, or `None` if it's still changing by then. It runs on a board of its
+/// own, with room to travel at up to half the speed of light without
+/// reaching the edge.
+pub fn classify(pattern: &Pattern, rule: &dyn Transition, max_generations: u64) -> Option<Behavior> {
+    let margin = (max_generations / 2 + 2).min(8000) as i16;
+    let size = (pattern.width + 2 * margin, pattern.height + 2 * margin);
+    let mut board: Box<dyn Board> = Box::new(DenseBoard::new(size));
+    pattern.stamp(board.as_mut(), GridPosition::new(margin, margin));
+
+    // each shape seen, with the first generation it was seen at and where
+    let mut seen: HashMap<u64, (u64, GridPosition)> = HashMap::new();
+    for generation in 0..=max_generations {
+        if generation > 0 {
+            board = board::step(board.as_ref(), rule);
+        }
+
+        let (origin, _) = match board.bounds() {
+            Some(bounds) => bounds.bounds(),
+            None => return Some(Behavior::Dies { generation }),
+        };
+        let hash = shape_hash(board.as_ref(), origin);
+
+        if let Some(&(start, first)) = seen.get(&hash) {
+            let period = generation - start;
+            let displacement = (origin.x - first.x, origin.y - first.y);
+            return Some(if displacement == (0, 0) {
+                Behavior::Oscillates { start, period }
+            } else {
+                Behavior::Travels { start, period, displacement }
+            });
+        }
+        seen.insert(hash, (generation, origin));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{self, DenseBoard, GridPosition};
+    use crate::pattern::Pattern;
+    use crate::rule::Rule;
+
+    /// The first cycle found stepping `cells` on a 20x20 board, within `generations`.
+    fn first_cycle(cells: &str, generations: u64) -> Option<Cycle> {
+        let mut board: Box<dyn Board> = Box::new(DenseBoard::new((20, 20)));
+        Pattern::from_plaintext(cells).stamp(board.as_mut(), GridPosition::new(8, 8));
+
+        let mut detector = CycleDetector::new(8);
+        (0..=generations).find_map(|generation| {
+            if generation > 0 {
+                board = board::step(board.as_ref(), &Rule::default());
+            }
+            detector.observe(generation, board.as_ref())
+        })
+    }
+
+    #[test]
+    fn still_lifes_and_oscillators_cycle() {
+        assert_eq!(first_cycle("OO\nOO", 4), Some(Cycle { start: 0, period: 1 }));
+        assert_eq!(first_cycle("OOO", 4), Some(Cycle { start: 0, period: 2 }));
+        // a pre-block becomes a block, which then stays put
+        assert_eq!(first_cycle("OO\nO.", 4), Some(Cycle { start: 1, period: 1 }));
+    }
+
+    #[test]
+    fn spaceships_never_cycle_in_place() {
+        assert_eq!(first_cycle(".O.\n..O\nOOO", 24), None);
+    }
+
+    #[test]
+    fn patterns_are_classified() {
+        let classify = |cells: &str| classify(&Pattern::from_plaintext(cells), &Rule::default(), 64);
+
+        assert_eq!(classify("OO\nOO"), Some(Behavior::Oscillates { start: 0, period: 1 }));
+        assert_eq!(classify("OOO"), Some(Behavior::Oscillates { start: 0, period: 2 }));
+        assert_eq!(classify(".O.\n..O\nOOO"), Some(Behavior::Travels { start: 0, period: 4, displacement: (1, 1) }));
+        assert_eq!(classify("O.O"), Some(Behavior::Dies { generation: 1 }));
+        // the R-pentomino is still going long after 64 generations
+        assert_eq!(classify(".OO\nOO.\n.O."), None);
+    }
+
+    #[test]
+    fn gaps_start_over() {
+        let board = DenseBoard::new((4, 4));
+        let mut detector = CycleDetector::new(8);
+
+        assert_eq!(detector.observe(0, &board), None);
+        assert_eq!(detector.observe(5, &board), None);
+        assert_eq!(detector.observe(6, &board), Some(Cycle { start: 5, period: 1 }));
+    }
+}
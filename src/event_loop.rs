@@ -0,0 +1,95 @@
+//! A copy of ggez's main loop that also forwards touch events, which
+//! `ggez::event::run` silently drops.
+
+use ggez::event::winit_event::{ElementState, Event, KeyboardInput, MouseScrollDelta, TouchPhase, WindowEvent};
+use ggez::event::{self, Axis, Button, EventHandler, EventsLoop};
+use ggez::input::gamepad::gilrs;
+use ggez::input::{keyboard, mouse};
+use ggez::{graphics, Context, GameResult};
+
+/// Events on top of the ones `EventHandler` gets. Gamepad events come in
+/// here rather than through `EventHandler` because a `GamepadId` can only be
+/// made inside ggez.
+pub trait InputHandler: EventHandler {
+    fn touch_event(&mut self, ctx: &mut Context, id: u64, phase: TouchPhase, x: f32, y: f32);
+
+    fn gamepad_button(&mut self, ctx: &mut Context, btn: Button);
+
+    fn gamepad_axis(&mut self, ctx: &mut Context, axis: Axis, value: f32);
+}
+
+pub fn run<S: InputHandler>(ctx: &mut Context, events_loop: &mut EventsLoop, state: &mut S) -> GameResult {
+    while ctx.continuing {
+        ctx.timer_context.tick();
+        events_loop.poll_events(|event| {
+            ctx.process_event(&event);
+            let (window_id, event) = match event {
+                Event::WindowEvent { window_id, event } => (window_id, event),
+                _ => return,
+            };
+
+            match event {
+                WindowEvent::Resized(size) => state.resize_event(ctx, size.width as f32, size.height as f32),
+                WindowEvent::HiDpiFactorChanged(_) => {
+                    // ggez ignores these, so pass it on as a resize to rebuild
+                    // the backbuffer at the new scale
+                    if let Some(size) = graphics::window(ctx).get_inner_size() {
+                        ctx.process_event(&Event::WindowEvent { window_id, event: WindowEvent::Resized(size) });
+                        state.resize_event(ctx, size.width as f32, size.height as f32);
+                    }
+                }
+                WindowEvent::CloseRequested if !state.quit_event(ctx) => event::quit(ctx),
+                WindowEvent::Focused(gained) => state.focus_event(ctx, gained),
+                WindowEvent::ReceivedCharacter(ch) => state.text_input_event(ctx, ch),
+                WindowEvent::KeyboardInput {
+                    input: KeyboardInput { state: key_state, virtual_keycode: Some(keycode), modifiers, .. },
+                    ..
+                } => match key_state {
+                    ElementState::Pressed => {
+                        let repeat = keyboard::is_key_repeated(ctx);
+                        state.key_down_event(ctx, keycode, modifiers.into(), repeat);
+                    }
+                    ElementState::Released => state.key_up_event(ctx, keycode, modifiers.into()),
+                },
+                WindowEvent::MouseWheel { delta, .. } => {
+                    let (x, y) = match delta {
+                        MouseScrollDelta::LineDelta(x, y) => (x, y),
+                        MouseScrollDelta::PixelDelta(position) => (position.x as f32, position.y as f32),
+                    };
+                    state.mouse_wheel_event(ctx, x, y);
+                }
+                WindowEvent::MouseInput { state: button_state, button, .. } => {
+                    let position = mouse::position(ctx);
+                    match button_state {
+                        ElementState::Pressed => state.mouse_button_down_event(ctx, button, position.x, position.y),
+                        ElementState::Released => state.mouse_button_up_event(ctx, button, position.x, position.y),
+                    }
+                }
+                WindowEvent::CursorMoved { .. } => {
+                    let position = mouse::position(ctx);
+                    let delta = mouse::delta(ctx);
+                    state.mouse_motion_event(ctx, position.x, position.y, delta.x, delta.y);
+                }
+                WindowEvent::Touch(touch) => {
+                    let (x, y) = (touch.location.x as f32, touch.location.y as f32);
+                    state.touch_event(ctx, touch.id, touch.phase, x, y);
+                }
+                _ => {},
+            }
+        });
+
+        // the gamepad context never has events when the gamepad module is off
+        while let Some(gilrs::Event { event, .. }) = ctx.gamepad_context.next_event() {
+            match event {
+                gilrs::EventType::ButtonPressed(button, _) => state.gamepad_button(ctx, button),
+                gilrs::EventType::AxisChanged(axis, value, _) => state.gamepad_axis(ctx, axis, value),
+                _ => {},
+            }
+        }
+
+        state.update(ctx)?;
+        state.draw(ctx)?;
+    }
+
+    Ok(())
+}
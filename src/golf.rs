@@ -0,0 +1,178 @@
+//! Life golf: making something happen from as few starting cells as
+//! possible. Each hole has a goal to reach within a number of generations,
+//! and the score for it is how many cells the board started with.
+
+use crate::board::Board;
+use crate::census;
+
+use std::path::Path;
+
+/// What a hole asks for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Goal {
+    /// At least one of an object a census recognizes, on its own somewhere
+    /// on the board, at any generation up to the limit.
+    Object(&'static str),
+    /// At least this many cells alive at once, at any generation up to the limit.
+    Grow(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hole {
+    pub name: &'static str,
+    pub goal: Goal,
+    pub generations: u64,
+    /// The fewest cells it's known to take.
+    pub par: usize,
+}
+
+/// Every hole, easiest first.
+pub const HOLES: &[Hole] = &[
+    Hole { name: "Block", goal: Goal::Object("block"), generations: 10, par: 3 },
+    Hole { name: "Beehive", goal: Goal::Object("beehive"), generations: 10, par: 4 },
+    Hole { name: "Pond", goal: Goal::Object("pond"), generations: 10, par: 4 },
+    Hole { name: "Glider", goal: Goal::Object("glider"), generations: 100, par: 5 },
+    Hole { name: "Blinker", goal: Goal::Object("blinker"), generations: 20, par: 3 },
+    Hole { name: "Growth spurt", goal: Goal::Grow(100), generations: 1000, par: 5 },
+];
+
+impl Hole {
+    /// What to do, in a line.
+    pub fn description(&self) -> String {
+        match self.goal {
+            Goal::Object(object) => format!("make a {} within {} generations", object, self.generations),
+            Goal::Grow(cells) => format!("grow to {} cells within {} generations", cells, self.generations),
+        }
+    }
+
+    /// Whether `board`, at `generation` of an attempt, reaches the goal.
+    pub fn is_met(&self, board: &dyn Board, generation: u64) -> bool {
+        if generation > self.generations {
+            return false;
+        }
+        match self.goal {
+            Goal::Object(object) => census::census(board).iter().any(|(name, _)| name == object),
+            Goal::Grow(cells) => board.population() >= cells,
+        }
+    }
+}
+
+/// How a score compares with par, in golf's terms.
+pub fn versus_par(cells: usize, par: usize) -> String {
+    match cells as i64 - par as i64 {
+        -1 => "a birdie".to_string(),
+        0 => "par".to_string(),
+        1 => "a bogey".to_string(),
+        over if over > 0 => format!("{} over par", over),
+        under => format!("{} under par", -under),
+    }
+}
+
+/// The best score at each hole, saved as a line of hole name and cells, tab
+/// separated, for each hole played.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Scorecard {
+    best: Vec<Option<usize>>,
+}
+
+impl Scorecard {
+    /// A scorecard saved by `to_text`. Holes it doesn't know are left out.
+    pub fn parse(text: &str) -> Self {
+        let mut scorecard = Scorecard::default();
+        for line in text.lines() {
+            if let Some((name, cells)) = line.split_once('\t') {
+                let hole = HOLES.iter().position(|hole| hole.name == name);
+                if let (Some(hole), Ok(cells)) = (hole, cells.trim().parse()) {
+                    scorecard.record(hole, cells);
+                }
+            }
+        }
+        scorecard
+    }
+
+    pub fn to_text(&self) -> String {
+        HOLES
+            .iter()
+            .enumerate()
+            .filter_map(|(index, hole)| Some(format!("{}\t{}\n", hole.name, self.best(index)?)))
+            .collect()
+    }
+
+    /// The scorecard saved at `path`, or an empty one if nothing's been saved there yet.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Scorecard::default()),
+            Err(err) => Err(format!("couldn't read {}: {}", path.display(), err)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_text())
+    }
+
+    /// The fewest cells the hole at `index` has been played in, if it has.
+    pub fn best(&self, index: usize) -> Option<usize> {
+        self.best.get(index).copied().flatten()
+    }
+
+    /// Record playing the hole at `index` in `cells`, saying whether it's a
+    /// new best.
+    pub fn record(&mut self, index: usize, cells: usize) -> bool {
+        if self.best(index).is_some_and(|best| best <= cells) {
+            return false;
+        }
+        if self.best.len() <= index {
+            self.best.resize(index + 1, None);
+        }
+        self.best[index] = Some(cells);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{self, DenseBoard, GridPosition};
+    use crate::pattern::Pattern;
+    use crate::rule::Rule;
+
+    #[test]
+    fn every_hole_can_be_made_in_par() {
+        let solutions = ["OO\nO.", "OOOO", "O..\n.OO\n.O.", ".O.\n..O\nOOO", "OOO", ".OO\nOO.\n.O."];
+        assert_eq!(HOLES.len(), solutions.len());
+
+        for (hole, solution) in HOLES.iter().zip(&solutions) {
+            let solution = Pattern::from_plaintext(solution);
+            assert_eq!(solution.cells.len(), hole.par, "{} isn't in par", hole.name);
+
+            let mut board: Box<dyn Board> = Box::new(DenseBoard::new((160, 160)));
+            solution.stamp(board.as_mut(), GridPosition::new(80, 80));
+            let made = (1..=hole.generations).any(|generation| {
+                board = board::step(board.as_ref(), &Rule::default());
+                hole.is_met(board.as_ref(), generation)
+            });
+            assert!(made, "{} isn't made", hole.name);
+        }
+    }
+
+    #[test]
+    fn the_scorecard_keeps_the_fewest_cells() {
+        let mut scorecard = Scorecard::default();
+        assert!(scorecard.record(3, 7));
+        assert!(!scorecard.record(3, 8));
+        assert!(scorecard.record(3, 5));
+        assert!(scorecard.record(0, 3));
+        assert_eq!((scorecard.best(3), scorecard.best(1)), (Some(5), None));
+
+        let text = scorecard.to_text();
+        assert_eq!(text, "Block\t3\nGlider\t5\n");
+        assert_eq!(Scorecard::parse(&format!("{}Mystery\t1\n", text)), scorecard);
+
+        assert_eq!(versus_par(4, 5), "a birdie");
+        assert_eq!(versus_par(8, 5), "3 over par");
+    }
+}
@@ -0,0 +1,104 @@
+//! Hook points for things that want to follow the simulation, like
+//! statistics, sound or recording, without living in the update loop.
+
+use crate::board::{self, Board, GridPosition};
+
+type StepHook<C> = Box<dyn FnMut(&mut C, &dyn Board, &dyn Board)>;
+type GenerationHook<C> = Box<dyn FnMut(&mut C, u64, &dyn Board)>;
+type CellsChangedHook<C> = Box<dyn FnMut(&mut C, &[GridPosition], &[GridPosition])>;
+
+/// Callbacks run every time the board steps, in the order they were added.
+/// Each is handed the `C` the hooks are run with, for whatever state it
+/// keeps up to date that others need to read too.
+pub struct Hooks<C = ()> {
+    on_step: Vec<StepHook<C>>,
+    on_generation: Vec<GenerationHook<C>>,
+    on_cells_changed: Vec<CellsChangedHook<C>>,
+}
+
+impl<C> Default for Hooks<C> {
+    fn default() -> Self {
+        Hooks {
+            on_step: Vec::new(),
+            on_generation: Vec::new(),
+            on_cells_changed: Vec::new(),
+        }
+    }
+}
+
+impl<C> Hooks<C> {
+    /// Call `hook` with the board before and after each step.
+    pub fn on_step<F: FnMut(&mut C, &dyn Board, &dyn Board) + 'static>(&mut self, hook: F) {
+        self.on_step.push(Box::new(hook));
+    }
+
+    /// Call `hook` with the generation number and the board after each step.
+    pub fn on_generation<F: FnMut(&mut C, u64, &dyn Board) + 'static>(&mut self, hook: F) {
+        self.on_generation.push(Box::new(hook));
+    }
+
+    /// Call `hook` with the cells born and the cells that died in each step.
+    pub fn on_cells_changed<F: FnMut(&mut C, &[GridPosition], &[GridPosition]) + 'static>(&mut self, hook: F) {
+        self.on_cells_changed.push(Box::new(hook));
+    }
+
+    /// Run the hooks with `context` for a step from `previous` to `board`,
+    /// which is now at `generation`. Births and deaths are only worked out if
+    /// something is listening for them.
+    pub fn stepped(&mut self, context: &mut C, generation: u64, previous: &dyn Board, board: &dyn Board) {
+        for hook in &mut self.on_step {
+            hook(context, previous, board);
+        }
+        for hook in &mut self.on_generation {
+            hook(context, generation, board);
+        }
+
+        if !self.on_cells_changed.is_empty() {
+            let (births, deaths) = board::diff(previous, board);
+            for hook in &mut self.on_cells_changed {
+                hook(context, &births, &deaths);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::DenseBoard;
+
+    #[test]
+    fn hooks_run_in_order_with_the_context() {
+        let mut hooks: Hooks<Vec<String>> = Hooks::default();
+        hooks.on_cells_changed(|log: &mut Vec<String>, births, deaths| log.push(format!("{} born, {} died", births.len(), deaths.len())));
+        hooks.on_generation(|log: &mut Vec<String>, generation, board| log.push(format!("generation {}: {} cells", generation, board.population())));
+        hooks.on_step(|log: &mut Vec<String>, previous, board| log.push(format!("{} to {}", previous.population(), board.population())));
+        hooks.on_generation(|log: &mut Vec<String>, generation, _| log.push(format!("then generation {}", generation)));
+
+        let mut previous = DenseBoard::new((5, 5));
+        for x in 1..4 {
+            previous.set_alive(GridPosition::new(x, 2), true);
+        }
+        let mut board = DenseBoard::new((5, 5));
+        board.set_alive(GridPosition::new(2, 2), true);
+        board.set_alive(GridPosition::new(2, 1), true);
+
+        let mut log = vec!["before".to_string()];
+        hooks.stepped(&mut log, 7, &previous, &board);
+        hooks.stepped(&mut log, 8, &board, &board);
+        assert_eq!(
+            log,
+            [
+                "before",
+                "3 to 2",
+                "generation 7: 2 cells",
+                "then generation 7",
+                "1 born, 2 died",
+                "2 to 2",
+                "generation 8: 2 cells",
+                "then generation 8",
+                "0 born, 0 died",
+            ]
+        );
+    }
+}
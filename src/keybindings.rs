@@ -0,0 +1,788 @@
+use ggez::event::{Button, KeyCode, KeyMods};
+use ggez::{filesystem, Context};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+
+use crate::options::CONFIG_FILE;
+
+/// Where user key bindings are read from, relative to ggez's user config directory.
+const KEYBINDINGS_FILE: &str = "/keybindings.toml";
+
+/// Everything a key can be bound to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    ToggleRun,
+    Step,
+    StepBack,
+    IncreaseRewind,
+    DecreaseRewind,
+    Reset,
+    Undo,
+    Redo,
+    Copy,
+    Cut,
+    Paste,
+    RotateClockwise,
+    RotateCounterclockwise,
+    FlipHorizontal,
+    FlipVertical,
+    ToggleSelectMode,
+    Cancel,
+    ClearSelection,
+    FillSelection,
+    StampMode,
+    StampSelection,
+    NextPattern,
+    PreviousPattern,
+    NextBrush,
+    PreviousBrush,
+    NextSymmetry,
+    CursorLeft,
+    CursorRight,
+    CursorUp,
+    CursorDown,
+    CursorToggle,
+    ToggleHelp,
+    Randomize,
+    IncreaseDensity,
+    DecreaseDensity,
+    Reseed,
+    Invert,
+    RandomizeSelection,
+    ToggleTheme,
+    Faster,
+    Slower,
+    CycleOnionSkin,
+    GrowBoard,
+    ShrinkBoard,
+    NewTab,
+    CloseTab,
+    NextTab,
+    PreviousTab,
+    ToggleTurbo,
+    TurboFaster,
+    TurboMuchFaster,
+    TurboSlower,
+    TurboMuchSlower,
+    FindPeriod,
+    Census,
+    ToggleSoupSearch,
+    ToggleStats,
+    ToggleBounds,
+    TakeSnapshot,
+    ToggleDiff,
+    FindPredecessor,
+    ShowLeaderboard,
+    ExportReport,
+    ToggleActivity,
+    ToggleVersus,
+    NextPuzzle,
+    QuitPuzzle,
+    ShowAchievements,
+    ToggleTimeAttack,
+    ToggleQuiz,
+    Answer1,
+    Answer2,
+    Answer3,
+    Answer4,
+    NextHole,
+    QuitGolf,
+    CopyShareCode,
+    PasteShareCode,
+    ToggleSound,
+    ToggleBoardMusic,
+    ToggleMusic,
+    NextTrack,
+    ToggleRecording,
+    PlayReplay,
+    ToggleInspect,
+    ToggleGhost,
+    ToggleRuleEditor,
+    PlaceRPentomino,
+    PlaceAcorn,
+    PlaceDiehard,
+    PlaceBHeptomino,
+    NewLayer,
+    NextLayer,
+    ToggleLayerVisible,
+    MergeLayer,
+    LabelCell,
+    ToggleFreeze,
+    ThawAll,
+    CycleWallPaint,
+    ClearWalls,
+    WarpToGeneration,
+    SaveBoard,
+}
+
+impl Action {
+    pub const ALL: [Action; 102] = [
+        Action::ToggleRun,
+        Action::Step,
+        Action::StepBack,
+        Action::IncreaseRewind,
+        Action::DecreaseRewind,
+        Action::Reset,
+        Action::Undo,
+        Action::Redo,
+        Action::Copy,
+        Action::Cut,
+        Action::Paste,
+        Action::RotateClockwise,
+        Action::RotateCounterclockwise,
+        Action::FlipHorizontal,
+        Action::FlipVertical,
+        Action::ToggleSelectMode,
+        Action::Cancel,
+        Action::ClearSelection,
+        Action::FillSelection,
+        Action::StampMode,
+        Action::StampSelection,
+        Action::NextPattern,
+        Action::PreviousPattern,
+        Action::NextBrush,
+        Action::PreviousBrush,
+        Action::NextSymmetry,
+        Action::CursorLeft,
+        Action::CursorRight,
+        Action::CursorUp,
+        Action::CursorDown,
+        Action::CursorToggle,
+        Action::ToggleHelp,
+        Action::Randomize,
+        Action::IncreaseDensity,
+        Action::DecreaseDensity,
+        Action::Reseed,
+        Action::Invert,
+        Action::RandomizeSelection,
+        Action::ToggleTheme,
+        Action::Faster,
+        Action::Slower,
+        Action::CycleOnionSkin,
+        Action::GrowBoard,
+        Action::ShrinkBoard,
+        Action::NewTab,
+        Action::CloseTab,
+        Action::NextTab,
+        Action::PreviousTab,
+        Action::ToggleTurbo,
+        Action::TurboFaster,
+        Action::TurboMuchFaster,
+        Action::TurboSlower,
+        Action::TurboMuchSlower,
+        Action::FindPeriod,
+        Action::Census,
+        Action::ToggleSoupSearch,
+        Action::ToggleStats,
+        Action::ToggleBounds,
+        Action::TakeSnapshot,
+        Action::ToggleDiff,
+        Action::FindPredecessor,
+        Action::ShowLeaderboard,
+        Action::ExportReport,
+        Action::ToggleActivity,
+        Action::ToggleVersus,
+        Action::NextPuzzle,
+        Action::QuitPuzzle,
+        Action::ShowAchievements,
+        Action::ToggleTimeAttack,
+        Action::ToggleQuiz,
+        Action::Answer1,
+        Action::Answer2,
+        Action::Answer3,
+        Action::Answer4,
+        Action::NextHole,
+        Action::QuitGolf,
+        Action::CopyShareCode,
+        Action::PasteShareCode,
+        Action::ToggleSound,
+        Action::ToggleBoardMusic,
+        Action::ToggleMusic,
+        Action::NextTrack,
+        Action::ToggleRecording,
+        Action::PlayReplay,
+        Action::ToggleInspect,
+        Action::ToggleGhost,
+        Action::ToggleRuleEditor,
+        Action::PlaceRPentomino,
+        Action::PlaceAcorn,
+        Action::PlaceDiehard,
+        Action::PlaceBHeptomino,
+        Action::NewLayer,
+        Action::NextLayer,
+        Action::ToggleLayerVisible,
+        Action::MergeLayer,
+        Action::LabelCell,
+        Action::ToggleFreeze,
+        Action::ThawAll,
+        Action::CycleWallPaint,
+        Action::ClearWalls,
+        Action::WarpToGeneration,
+        Action::SaveBoard,
+    ];
+
+    /// The name used for the action in the key bindings file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::ToggleRun => "toggle_run",
+            Action::Step => "step",
+            Action::StepBack => "step_back",
+            Action::IncreaseRewind => "increase_rewind",
+            Action::DecreaseRewind => "decrease_rewind",
+            Action::Reset => "reset",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::Copy => "copy",
+            Action::Cut => "cut",
+            Action::Paste => "paste",
+            Action::RotateClockwise => "rotate_clockwise",
+            Action::RotateCounterclockwise => "rotate_counterclockwise",
+            Action::FlipHorizontal => "flip_horizontal",
+            Action::FlipVertical => "flip_vertical",
+            Action::ToggleSelectMode => "toggle_select_mode",
+            Action::Cancel => "cancel",
+            Action::ClearSelection => "clear_selection",
+            Action::FillSelection => "fill_selection",
+            Action::StampMode => "stamp_mode",
+            Action::StampSelection => "stamp_selection",
+            Action::NextPattern => "next_pattern",
+            Action::PreviousPattern => "previous_pattern",
+            Action::NextBrush => "next_brush",
+            Action::PreviousBrush => "previous_brush",
+            Action::NextSymmetry => "next_symmetry",
+            Action::CursorLeft => "cursor_left",
+            Action::CursorRight => "cursor_right",
+            Action::CursorUp => "cursor_up",
+            Action::CursorDown => "cursor_down",
+            Action::CursorToggle => "cursor_toggle",
+            Action::ToggleHelp => "toggle_help",
+            Action::Randomize => "randomize",
+            Action::IncreaseDensity => "increase_density",
+            Action::DecreaseDensity => "decrease_density",
+            Action::Reseed => "reseed",
+            Action::Invert => "invert",
+            Action::RandomizeSelection => "randomize_selection",
+            Action::ToggleTheme => "toggle_theme",
+            Action::Faster => "faster",
+            Action::Slower => "slower",
+            Action::CycleOnionSkin => "cycle_onion_skin",
+            Action::GrowBoard => "grow_board",
+            Action::ShrinkBoard => "shrink_board",
+            Action::NewTab => "new_tab",
+            Action::CloseTab => "close_tab",
+            Action::NextTab => "next_tab",
+            Action::PreviousTab => "previous_tab",
+            Action::ToggleTurbo => "toggle_turbo",
+            Action::TurboFaster => "turbo_faster",
+            Action::TurboMuchFaster => "turbo_much_faster",
+            Action::TurboSlower => "turbo_slower",
+            Action::TurboMuchSlower => "turbo_much_slower",
+            Action::FindPeriod => "find_period",
+            Action::Census => "census",
+            Action::ToggleSoupSearch => "toggle_soup_search",
+            Action::ToggleStats => "toggle_stats",
+            Action::ToggleBounds => "toggle_bounds",
+            Action::TakeSnapshot => "take_snapshot",
+            Action::ToggleDiff => "toggle_diff",
+            Action::FindPredecessor => "find_predecessor",
+            Action::ShowLeaderboard => "show_leaderboard",
+            Action::ExportReport => "export_report",
+            Action::ToggleActivity => "toggle_activity",
+            Action::ToggleVersus => "toggle_versus",
+            Action::NextPuzzle => "next_puzzle",
+            Action::QuitPuzzle => "quit_puzzle",
+            Action::ShowAchievements => "show_achievements",
+            Action::ToggleTimeAttack => "toggle_time_attack",
+            Action::ToggleQuiz => "toggle_quiz",
+            Action::Answer1 => "answer_1",
+            Action::Answer2 => "answer_2",
+            Action::Answer3 => "answer_3",
+            Action::Answer4 => "answer_4",
+            Action::NextHole => "next_hole",
+            Action::QuitGolf => "quit_golf",
+            Action::CopyShareCode => "copy_share_code",
+            Action::PasteShareCode => "paste_share_code",
+            Action::ToggleSound => "toggle_sound",
+            Action::ToggleBoardMusic => "toggle_board_music",
+            Action::ToggleMusic => "toggle_music",
+            Action::NextTrack => "next_track",
+            Action::ToggleRecording => "toggle_recording",
+            Action::PlayReplay => "play_replay",
+            Action::ToggleInspect => "toggle_inspect",
+            Action::ToggleGhost => "toggle_ghost",
+            Action::ToggleRuleEditor => "toggle_rule_editor",
+            Action::PlaceRPentomino => "place_r_pentomino",
+            Action::PlaceAcorn => "place_acorn",
+            Action::PlaceDiehard => "place_diehard",
+            Action::PlaceBHeptomino => "place_b_heptomino",
+            Action::NewLayer => "new_layer",
+            Action::NextLayer => "next_layer",
+            Action::ToggleLayerVisible => "toggle_layer_visible",
+            Action::MergeLayer => "merge_layer",
+            Action::LabelCell => "label_cell",
+            Action::ToggleFreeze => "toggle_freeze",
+            Action::ThawAll => "thaw_all",
+            Action::CycleWallPaint => "cycle_wall_paint",
+            Action::ClearWalls => "clear_walls",
+            Action::WarpToGeneration => "warp_to_generation",
+            Action::SaveBoard => "save_board",
+        }
+    }
+
+    /// What the action does, as shown in the help overlay.
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::ToggleRun => "run / pause",
+            Action::Step => "step one generation",
+            Action::StepBack => "step back through recent generations",
+            Action::IncreaseRewind => "step back further at a time",
+            Action::DecreaseRewind => "step back less at a time",
+            Action::Reset => "clear the board",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::Copy => "copy selection",
+            Action::Cut => "cut selection",
+            Action::Paste => "paste",
+            Action::RotateClockwise => "rotate selection or paste clockwise",
+            Action::RotateCounterclockwise => "rotate selection or paste counterclockwise",
+            Action::FlipHorizontal => "flip selection or paste horizontally",
+            Action::FlipVertical => "flip selection or paste vertically",
+            Action::ToggleSelectMode => "toggle selection mode",
+            Action::Cancel => "cancel paste, move or selection",
+            Action::ClearSelection => "clear selection",
+            Action::FillSelection => "fill selection",
+            Action::StampMode => "stamp library patterns",
+            Action::StampSelection => "stamp copies of the selection",
+            Action::NextPattern => "next library pattern",
+            Action::PreviousPattern => "previous library pattern",
+            Action::NextBrush => "next brush",
+            Action::PreviousBrush => "previous brush",
+            Action::NextSymmetry => "cycle painting symmetry",
+            Action::CursorLeft => "move cursor left",
+            Action::CursorRight => "move cursor right",
+            Action::CursorUp => "move cursor up",
+            Action::CursorDown => "move cursor down",
+            Action::CursorToggle => "toggle cell / stamp at cursor",
+            Action::ToggleHelp => "show / hide this help",
+            Action::Randomize => "fill the board with random cells",
+            Action::IncreaseDensity => "raise random fill density",
+            Action::DecreaseDensity => "lower random fill density",
+            Action::Reseed => "start over from a new soup",
+            Action::Invert => "swap live and dead cells",
+            Action::RandomizeSelection => "fill selection with random cells",
+            Action::ToggleTheme => "switch between dark and light themes",
+            Action::Faster => "run faster",
+            Action::Slower => "run slower",
+            Action::CycleOnionSkin => "show more or fewer previous generations",
+            Action::GrowBoard => "make the board bigger",
+            Action::ShrinkBoard => "make the board smaller",
+            Action::NewTab => "open an empty board in a new tab",
+            Action::CloseTab => "close the current tab",
+            Action::NextTab => "switch to the next tab",
+            Action::PreviousTab => "switch to the previous tab",
+            Action::ToggleTurbo => "run many generations per frame",
+            Action::TurboFaster => "double the generations per turbo frame",
+            Action::TurboMuchFaster => "run ten times the generations per turbo frame",
+            Action::TurboSlower => "halve the generations per turbo frame",
+            Action::TurboMuchSlower => "run a tenth of the generations per turbo frame",
+            Action::FindPeriod => "find the period of the selection or board",
+            Action::Census => "count the objects on the board",
+            Action::ToggleSoupSearch => "search random soups, recording what they leave",
+            Action::ToggleStats => "show density and entropy",
+            Action::ToggleBounds => "outline the live cells",
+            Action::TakeSnapshot => "snapshot the board to diff against",
+            Action::ToggleDiff => "highlight changes since the snapshot",
+            Action::FindPredecessor => "find what the selection came from",
+            Action::ShowLeaderboard => "list the longest lived soups",
+            Action::ExportReport => "save a JSON report of the session",
+            Action::ToggleActivity => "track births and deaths by region",
+            Action::ToggleVersus => "start or end a two-player match",
+            Action::NextPuzzle => "start the next puzzle",
+            Action::QuitPuzzle => "leave the puzzle",
+            Action::ShowAchievements => "list the achievements unlocked so far",
+            Action::ToggleTimeAttack => "start or end a time attack",
+            Action::ToggleQuiz => "start or end the pattern quiz",
+            Action::Answer1 => "answer the quiz with the first name",
+            Action::Answer2 => "answer the quiz with the second name",
+            Action::Answer3 => "answer the quiz with the third name",
+            Action::Answer4 => "answer the quiz with the fourth name",
+            Action::NextHole => "start the next golf hole",
+            Action::QuitGolf => "leave life golf",
+            Action::CopyShareCode => "copy the board as a share code",
+            Action::PasteShareCode => "paste a share code",
+            Action::ToggleSound => "turn sound effects on or off",
+            Action::ToggleBoardMusic => "play the board as music, or stop",
+            Action::ToggleMusic => "play or pause the background music",
+            Action::NextTrack => "skip to the next music track",
+            Action::ToggleRecording => "start or stop recording the session",
+            Action::PlayReplay => "play back the recorded session",
+            Action::ToggleInspect => "inspect the cell under the mouse",
+            Action::ToggleGhost => "outline what the next step will change while paused",
+            Action::ToggleRuleEditor => "show buttons for each birth and survival count of the rule",
+            Action::PlaceRPentomino => "start over from an R-pentomino",
+            Action::PlaceAcorn => "start over from an acorn",
+            Action::PlaceDiehard => "start over from a diehard",
+            Action::PlaceBHeptomino => "start over from a B-heptomino",
+            Action::NewLayer => "add a sketch layer over the board and edit it",
+            Action::NextLayer => "edit the next layer, back to the board after the last",
+            Action::ToggleLayerVisible => "show or hide the layer being edited while editing others",
+            Action::MergeLayer => "merge the layer being edited into the one under it",
+            Action::LabelCell => "label the cell under the cursor, or change its label",
+            Action::ToggleFreeze => "freeze the selection so it doesn't change, or thaw it",
+            Action::ThawAll => "thaw every frozen region",
+            Action::CycleWallPaint => "paint cells, live walls or dead walls",
+            Action::ClearWalls => "knock down every wall",
+            Action::WarpToGeneration => "warp to a generation without drawing the ones on the way, or stop warping",
+            Action::SaveBoard => "save the board and its labels as RLE",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.iter().copied().find(|action| action.name() == name)
+    }
+}
+
+/// A key together with the modifiers that have to be held with it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Chord {
+    pub key: KeyCode,
+    pub mods: KeyMods,
+}
+
+impl Chord {
+    pub fn new(key: KeyCode, mods: KeyMods) -> Self {
+        // the logo key is never part of a binding
+        Chord { key, mods: mods & (KeyMods::CTRL | KeyMods::SHIFT | KeyMods::ALT) }
+    }
+
+    /// Parse chords written like `Space`, `Shift+R` or `Ctrl+Shift+Z`.
+    pub fn parse(text: &str) -> Option<Chord> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let key = key_from_name(parts.pop()?)?;
+        let mut mods = KeyMods::NONE;
+
+        for part in parts {
+            mods |= match part.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyMods::CTRL,
+                "shift" => KeyMods::SHIFT,
+                "alt" => KeyMods::ALT,
+                _ => return None,
+            };
+        }
+
+        Some(Chord::new(key, mods))
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.mods.contains(KeyMods::CTRL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.mods.contains(KeyMods::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.mods.contains(KeyMods::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        write!(f, "{:?}", self.key)
+    }
+}
+
+/// Keys that can appear in a binding, looked up by their `KeyCode` name.
+const BINDABLE_KEYS: [KeyCode; 89] = [
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::Numpad0, KeyCode::Numpad1, KeyCode::Numpad2, KeyCode::Numpad3, KeyCode::Numpad4,
+    KeyCode::Numpad5, KeyCode::Numpad6, KeyCode::Numpad7, KeyCode::Numpad8, KeyCode::Numpad9,
+    KeyCode::Escape, KeyCode::Space, KeyCode::Back, KeyCode::Return, KeyCode::Tab,
+    KeyCode::Insert, KeyCode::Delete, KeyCode::Home, KeyCode::End, KeyCode::PageUp, KeyCode::PageDown,
+    KeyCode::Left, KeyCode::Right, KeyCode::Up, KeyCode::Down,
+    KeyCode::Comma, KeyCode::Period, KeyCode::Slash, KeyCode::Backslash, KeyCode::Semicolon,
+    KeyCode::Apostrophe, KeyCode::Grave, KeyCode::Minus, KeyCode::Equals,
+    KeyCode::LBracket, KeyCode::RBracket,
+    KeyCode::Add, KeyCode::Subtract, KeyCode::Multiply, KeyCode::Divide, KeyCode::Pause,
+];
+
+fn key_from_name(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS
+        .iter()
+        .copied()
+        .find(|key| format!("{:?}", key).eq_ignore_ascii_case(name))
+}
+
+/// The action map the event handler consults for every key press.
+#[derive(Clone)]
+pub struct KeyBindings {
+    bindings: HashMap<Chord, Action>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let defaults = [
+            ("Space", Action::ToggleRun),
+            ("Period", Action::Step),
+            ("Comma", Action::StepBack),
+            ("PageUp", Action::IncreaseRewind),
+            ("PageDown", Action::DecreaseRewind),
+            ("Back", Action::Reset),
+            ("Ctrl+Z", Action::Undo),
+            ("Ctrl+Shift+Z", Action::Redo),
+            ("Ctrl+C", Action::Copy),
+            ("Ctrl+X", Action::Cut),
+            ("Ctrl+V", Action::Paste),
+            ("R", Action::RotateClockwise),
+            ("Shift+R", Action::RotateCounterclockwise),
+            ("X", Action::FlipHorizontal),
+            ("Y", Action::FlipVertical),
+            ("S", Action::ToggleSelectMode),
+            ("Escape", Action::Cancel),
+            ("Delete", Action::ClearSelection),
+            ("F", Action::FillSelection),
+            ("T", Action::StampMode),
+            ("Shift+T", Action::StampSelection),
+            ("Tab", Action::NextPattern),
+            ("Shift+Tab", Action::PreviousPattern),
+            ("B", Action::NextBrush),
+            ("Shift+B", Action::PreviousBrush),
+            ("M", Action::NextSymmetry),
+            ("Left", Action::CursorLeft),
+            ("H", Action::CursorLeft),
+            ("Right", Action::CursorRight),
+            ("L", Action::CursorRight),
+            ("Up", Action::CursorUp),
+            ("K", Action::CursorUp),
+            ("Down", Action::CursorDown),
+            ("J", Action::CursorDown),
+            ("Return", Action::CursorToggle),
+            ("F1", Action::ToggleHelp),
+            ("Shift+Slash", Action::ToggleHelp),
+            ("G", Action::Randomize),
+            ("RBracket", Action::IncreaseDensity),
+            ("LBracket", Action::DecreaseDensity),
+            ("N", Action::Reseed),
+            ("I", Action::Invert),
+            ("Shift+G", Action::RandomizeSelection),
+            ("D", Action::ToggleTheme),
+            ("Equals", Action::Faster),
+            ("Minus", Action::Slower),
+            ("O", Action::CycleOnionSkin),
+            ("Ctrl+Equals", Action::GrowBoard),
+            ("Ctrl+Minus", Action::ShrinkBoard),
+            ("Ctrl+T", Action::NewTab),
+            ("Ctrl+W", Action::CloseTab),
+            ("Ctrl+Tab", Action::NextTab),
+            ("Ctrl+Shift+Tab", Action::PreviousTab),
+            ("Shift+Period", Action::ToggleTurbo),
+            ("Shift+Equals", Action::TurboFaster),
+            ("Ctrl+Shift+Equals", Action::TurboMuchFaster),
+            ("Shift+Minus", Action::TurboSlower),
+            ("Ctrl+Shift+Minus", Action::TurboMuchSlower),
+            ("P", Action::FindPeriod),
+            ("C", Action::Census),
+            ("Shift+N", Action::ToggleSoupSearch),
+            ("F3", Action::ToggleStats),
+            ("E", Action::ToggleBounds),
+            ("Ctrl+S", Action::TakeSnapshot),
+            ("Shift+D", Action::ToggleDiff),
+            ("Shift+P", Action::FindPredecessor),
+            ("Shift+L", Action::ShowLeaderboard),
+            ("Ctrl+E", Action::ExportReport),
+            ("Shift+A", Action::ToggleActivity),
+            ("F5", Action::ToggleVersus),
+            ("F6", Action::NextPuzzle),
+            ("Shift+F6", Action::QuitPuzzle),
+            ("Ctrl+A", Action::ShowAchievements),
+            ("F7", Action::ToggleTimeAttack),
+            ("F8", Action::ToggleQuiz),
+            ("Key1", Action::Answer1),
+            ("Key2", Action::Answer2),
+            ("Key3", Action::Answer3),
+            ("Key4", Action::Answer4),
+            ("F9", Action::NextHole),
+            ("Shift+F9", Action::QuitGolf),
+            ("Ctrl+Shift+C", Action::CopyShareCode),
+            ("Ctrl+Shift+V", Action::PasteShareCode),
+            ("Ctrl+M", Action::ToggleSound),
+            ("Ctrl+Shift+M", Action::ToggleBoardMusic),
+            ("F10", Action::ToggleMusic),
+            ("Shift+F10", Action::NextTrack),
+            ("F11", Action::ToggleRecording),
+            ("Shift+F11", Action::PlayReplay),
+            ("Shift+I", Action::ToggleInspect),
+            ("Shift+O", Action::ToggleGhost),
+            ("Ctrl+R", Action::ToggleRuleEditor),
+            ("Ctrl+Key1", Action::PlaceRPentomino),
+            ("Ctrl+Key2", Action::PlaceAcorn),
+            ("Ctrl+Key3", Action::PlaceDiehard),
+            ("Ctrl+Key4", Action::PlaceBHeptomino),
+            ("Ctrl+L", Action::NewLayer),
+            ("Alt+L", Action::NextLayer),
+            ("Alt+H", Action::ToggleLayerVisible),
+            ("Alt+M", Action::MergeLayer),
+            ("Shift+Return", Action::LabelCell),
+            ("Ctrl+F", Action::ToggleFreeze),
+            ("Ctrl+Shift+F", Action::ThawAll),
+            ("Alt+W", Action::CycleWallPaint),
+            ("Alt+Shift+W", Action::ClearWalls),
+            ("Ctrl+G", Action::WarpToGeneration),
+            ("Ctrl+Shift+S", Action::SaveBoard),
+        ];
+
+        let bindings = defaults
+            .iter()
+            .map(|(chord, action)| (Chord::parse(chord).expect("invalid default binding"), *action))
+            .collect();
+
+        KeyBindings { bindings }
+    }
+}
+
+impl KeyBindings {
+    /// The default bindings with any overrides from the `[keybindings]` table of
+    /// the config file, then any from the user's key bindings file.
+    pub fn load(ctx: &mut Context, config: &toml::value::Table) -> Self {
+        let mut bindings = KeyBindings::default();
+        if let Err(e) = bindings.apply_table(config.clone()) {
+            eprintln!("ignoring key bindings in {}: {}", CONFIG_FILE, e);
+            bindings = KeyBindings::default();
+        }
+        let configured = bindings.clone();
+
+        if !filesystem::exists(ctx, KEYBINDINGS_FILE) {
+            return bindings;
+        }
+
+        let mut contents = String::new();
+        let read = filesystem::open(ctx, KEYBINDINGS_FILE)
+            .map_err(|e| e.to_string())
+            .and_then(|mut file| file.read_to_string(&mut contents).map_err(|e| e.to_string()));
+
+        match read.and_then(|_| bindings.apply_overrides(&contents)) {
+            Ok(()) => bindings,
+            Err(e) => {
+                eprintln!("ignoring {}: {}", KEYBINDINGS_FILE, e);
+                configured
+            }
+        }
+    }
+
+    /// Rebind actions from a TOML table mapping action names to a chord or a
+    /// list of chords. Bindings for an action replace all of its defaults.
+    pub fn apply_overrides(&mut self, contents: &str) -> Result<(), String> {
+        self.apply_table(toml::from_str(contents).map_err(|e| e.to_string())?)
+    }
+
+    /// Rebind actions from an already parsed table, as in `apply_overrides`.
+    pub fn apply_table(&mut self, table: toml::value::Table) -> Result<(), String> {
+        for (name, value) in table {
+            let action = Action::from_name(&name).ok_or(format!("unknown action `{}`", name))?;
+            let chords = match value {
+                toml::Value::String(chord) => vec![chord],
+                toml::Value::Array(chords) => chords
+                    .into_iter()
+                    .map(|chord| chord.as_str().map(String::from).ok_or(format!("`{}` must be a list of keys", name)))
+                    .collect::<Result<_, _>>()?,
+                _ => return Err(format!("`{}` must be a key or a list of keys", name)),
+            };
+
+            self.bindings.retain(|_, bound| *bound != action);
+            for chord in chords {
+                let parsed = Chord::parse(&chord).ok_or(format!("unknown key `{}`", chord))?;
+                self.bindings.insert(parsed, action);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Every chord bound to `action`, as written in the key bindings file.
+    pub fn chords(&self, action: Action) -> Vec<String> {
+        let mut chords: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|(_, bound)| **bound == action)
+            .map(|(chord, _)| chord.to_string())
+            .collect();
+        chords.sort();
+        chords
+    }
+
+    /// The action bound to a key press, with exactly the modifiers held.
+    pub fn action(&self, key: KeyCode, mods: KeyMods) -> Option<Action> {
+        self.bindings.get(&Chord::new(key, mods)).copied()
+    }
+}
+
+/// The action for a gamepad button. Unlike keys these aren't configurable;
+/// the sticks are handled separately since they're held rather than pressed.
+pub fn gamepad_action(button: Button) -> Option<Action> {
+    match button {
+        Button::South => Some(Action::CursorToggle),
+        Button::East => Some(Action::Cancel),
+        Button::Start => Some(Action::ToggleRun),
+        Button::Select => Some(Action::ToggleHelp),
+        Button::RightTrigger | Button::RightTrigger2 => Some(Action::Faster),
+        Button::LeftTrigger | Button::LeftTrigger2 => Some(Action::Slower),
+        Button::DPadUp => Some(Action::CursorUp),
+        Button::DPadDown => Some(Action::CursorDown),
+        Button::DPadLeft => Some(Action::CursorLeft),
+        Button::DPadRight => Some(Action::CursorRight),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chords_parse_modifiers_and_key_names() {
+        assert_eq!(Chord::parse("Space"), Some(Chord::new(KeyCode::Space, KeyMods::NONE)));
+        assert_eq!(Chord::parse("ctrl + shift + z"), Some(Chord::new(KeyCode::Z, KeyMods::CTRL | KeyMods::SHIFT)));
+        assert_eq!(Chord::parse("Control+Alt+F5"), Some(Chord::new(KeyCode::F5, KeyMods::CTRL | KeyMods::ALT)));
+        assert_eq!(Chord::parse("Shift+Key1").map(|chord| chord.to_string()), Some("Shift+Key1".to_string()));
+        assert_eq!(Chord::new(KeyCode::A, KeyMods::LOGO | KeyMods::SHIFT), Chord::new(KeyCode::A, KeyMods::SHIFT));
+    }
+
+    #[test]
+    fn unknown_keys_and_modifiers_are_refused() {
+        for text in ["", "Shift+", "Hyper+A", "Ctrl+Foo", "F13", "LShift"] {
+            assert_eq!(Chord::parse(text), None, "{:?}", text);
+        }
+
+        let mut bindings = KeyBindings::default();
+        assert_eq!(bindings.apply_overrides("toggle_run = \"Ctrl+Foo\""), Err("unknown key `Ctrl+Foo`".to_string()));
+        assert_eq!(bindings.apply_overrides("fly = \"F\""), Err("unknown action `fly`".to_string()));
+        assert_eq!(bindings.apply_overrides("toggle_run = 3"), Err("`toggle_run` must be a key or a list of keys".to_string()));
+    }
+
+    #[test]
+    fn overrides_replace_the_default_chords() {
+        let mut bindings = KeyBindings::default();
+        assert_eq!(bindings.chords(Action::ToggleRun), ["Space"]);
+
+        bindings.apply_overrides("toggle_run = [\"Return\", \"Ctrl+P\"]").unwrap();
+        assert_eq!(bindings.chords(Action::ToggleRun), ["Ctrl+P", "Return"]);
+        assert_eq!(bindings.action(KeyCode::Space, KeyMods::NONE), None);
+        assert_eq!(bindings.action(KeyCode::P, KeyMods::CTRL), Some(Action::ToggleRun));
+        // other actions keep their defaults
+        assert_eq!(bindings.action(KeyCode::C, KeyMods::CTRL), Some(Action::Copy));
+    }
+
+    #[test]
+    fn unbound_chords_do_nothing() {
+        let bindings = KeyBindings::default();
+        assert_eq!(bindings.action(KeyCode::C, KeyMods::NONE), Some(Action::Census));
+        assert_eq!(bindings.action(KeyCode::C, KeyMods::ALT), None);
+        assert_eq!(bindings.action(KeyCode::Space, KeyMods::SHIFT), None);
+    }
+}
@@ -0,0 +1,124 @@
+//! Labels pinned to cells, to note what the parts of a construction are
+//! for. They're saved with the board as comment lines at the top of its RLE
+//! file, each with its cell's column and row from the pattern's top-left
+//! corner and then its text, like
+//!
+//! ```text
+//! #C label 40 12 reflector here
+//! #C label 63 30 collision point
+//! ```
+//!
+//! so other programs reading the file just see comments.
+
+use std::collections::BTreeMap;
+
+use crate::board::GridPosition;
+
+/// What starts a comment line holding a label.
+const PREFIX: &str = "#C label ";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Labels {
+    labels: BTreeMap<GridPosition, String>,
+}
+
+impl Labels {
+    /// The labels in the comment lines of an RLE file, for the pattern in it
+    /// placed with its top-left corner at `origin`. Other lines are left out.
+    pub fn from_comments(text: &str, origin: GridPosition) -> Self {
+        let mut labels = Labels::default();
+        for line in text.lines().filter_map(|line| line.trim().strip_prefix(PREFIX)) {
+            let mut fields = line.splitn(3, ' ');
+            let x = fields.next().and_then(|x| x.parse::<i16>().ok());
+            let y = fields.next().and_then(|y| y.parse::<i16>().ok());
+            if let (Some(x), Some(y), Some(text)) = (x, y, fields.next()) {
+                labels.set(GridPosition::new(origin.x + x, origin.y + y), text);
+            }
+        }
+        labels
+    }
+
+    /// The labels as comment lines for an RLE file of the pattern whose
+    /// top-left corner is at `origin`.
+    pub fn to_comments(&self, origin: GridPosition) -> String {
+        self.labels.iter().map(|(pos, text)| format!("{}{} {} {}\n", PREFIX, pos.x - origin.x, pos.y - origin.y, text)).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn get(&self, pos: GridPosition) -> Option<&str> {
+        self.labels.get(&pos).map(String::as_str)
+    }
+
+    /// Label the cell at `pos` with `text`, on one line and without spaces
+    /// at either end, replacing any label it had. Blank text takes the label off.
+    pub fn set(&mut self, pos: GridPosition, text: &str) {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            self.labels.remove(&pos);
+        } else {
+            self.labels.insert(pos, text);
+        }
+    }
+
+    /// Every label, from the top row down and left to right along each.
+    pub fn iter(&self) -> impl Iterator<Item = (GridPosition, &str)> {
+        self.labels.iter().map(|(&pos, text)| (pos, text.as_str()))
+    }
+
+    /// Move every label by `offset`, as when the board is resized around
+    /// its middle, dropping the ones that end up off a board of `size`.
+    pub fn shift(&mut self, offset: (i16, i16), size: (i16, i16)) {
+        self.labels = std::mem::take(&mut self.labels)
+            .into_iter()
+            .map(|(pos, text)| (GridPosition::new(pos.x + offset.0, pos.y + offset.1), text))
+            .filter(|(pos, _)| pos.x >= 0 && pos.x < size.0 && pos.y >= 0 && pos.y < size.1)
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::Pattern;
+    use crate::rule::Rule;
+
+    #[test]
+    fn labels_round_trip_through_rle_comments() {
+        let mut labels = Labels::default();
+        labels.set(GridPosition::new(63, 30), "collision point");
+        labels.set(GridPosition::new(40, 12), "  reflector\there ");
+        labels.set(GridPosition::new(5, 5), "   ");
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels.get(GridPosition::new(40, 12)), Some("reflector here"));
+
+        let origin = GridPosition::new(38, 10);
+        let comments = labels.to_comments(origin);
+        assert_eq!(comments, "#C label 2 2 reflector here\n#C label 25 20 collision point\n");
+
+        // read back from a whole file, placed somewhere else
+        let rle = format!("{}#C not a label\n#C label 7 x nor this\n{}", comments, Pattern::from_plaintext("OO\nOO").to_rle(Rule::default()));
+        assert!(Pattern::from_rle(&rle).is_some());
+        let moved = Labels::from_comments(&rle, GridPosition::new(0, 0));
+        assert_eq!(moved.iter().collect::<Vec<_>>(), vec![(GridPosition::new(2, 2), "reflector here"), (GridPosition::new(25, 20), "collision point")]);
+        assert_eq!(Labels::from_comments(&rle, origin), labels);
+
+        labels.set(GridPosition::new(40, 12), "");
+        assert_eq!(labels.get(GridPosition::new(40, 12)), None);
+    }
+
+    #[test]
+    fn shifting_drops_labels_off_the_board() {
+        let mut labels = Labels::default();
+        labels.set(GridPosition::new(2, 2), "stays");
+        labels.set(GridPosition::new(9, 0), "goes");
+        labels.shift((3, 1), (10, 10));
+        assert_eq!(labels.iter().collect::<Vec<_>>(), vec![(GridPosition::new(5, 3), "stays")]);
+    }
+}
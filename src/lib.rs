@@ -0,0 +1,53 @@
+//! The Game of Life engine: boards, rules, stepping and patterns, with no
+//! ggez dependency. The `life` binary is a ggez frontend on top of it.
+//! Depend on it with `default-features = false` to leave the frontend out.
+//!
+//! To embed it, make a [`Universe`](universe::Universe), set its rule, place
+//! patterns and step it:
+//!
+//! ```
+//! use life::board::GridPosition;
+//! use life::pattern::Pattern;
+//! use life::rule::Rule;
+//! use life::universe::Universe;
+//!
+//! let mut universe = Universe::new((64, 64));
+//! universe.set_rule(Rule::from_name_or_rulestring("HighLife").unwrap());
+//! universe.place(&Pattern::from_plaintext(".O.\n..O\nOOO"), GridPosition::new(10, 10));
+//!
+//! universe.step_by(4);
+//! assert_eq!(universe.generation(), 4);
+//! assert_eq!(universe.population(), 5);
+//! assert!(universe.is_alive(GridPosition::new(12, 13)));
+//! ```
+
+pub mod achievements;
+pub mod attack;
+pub mod board;
+pub mod broadcast;
+pub mod census;
+pub mod cycle;
+pub mod golf;
+pub mod hooks;
+pub mod label;
+pub mod library;
+pub mod net;
+pub mod pattern;
+pub mod playlist;
+pub mod plugin;
+pub mod predecessor;
+pub mod puzzle;
+pub mod quiz;
+pub mod replay;
+pub mod report;
+pub mod rule;
+#[cfg(feature = "scripting")]
+pub mod script;
+pub mod search;
+pub mod share;
+pub mod sound;
+pub mod stats;
+pub mod universe;
+pub mod versus;
+#[cfg(feature = "web")]
+pub mod web;
@@ -0,0 +1,88 @@
+//! Built-in patterns, written in plaintext format: `O` is a live cell, `.` a
+//! dead one and every line is a row.
+
+pub const PATTERNS: &[(&str, &str)] = &[
+    ("glider", "\
+.O.
+..O
+OOO"),
+    ("lightweight spaceship", "\
+.O..O
+O....
+O...O
+OOOO."),
+    ("blinker", "OOO"),
+    ("toad", "\
+.OOO
+OOO."),
+    ("beacon", "\
+OO..
+OO..
+..OO
+..OO"),
+    ("pulsar", "\
+..OOO...OOO..
+.............
+O....O.O....O
+O....O.O....O
+O....O.O....O
+..OOO...OOO..
+.............
+..OOO...OOO..
+O....O.O....O
+O....O.O....O
+O....O.O....O
+.............
+..OOO...OOO.."),
+    ("pentadecathlon", "\
+..O....O..
+OO.OOOO.OO
+..O....O.."),
+    ("gosper glider gun", "\
+........................O...........
+......................O.O...........
+............OO......OO............OO
+...........O...O....OO............OO
+OO........O.....O...OO..............
+OO........O...O.OO....O.O...........
+..........O.....O.......O...........
+...........O...O....................
+............OO......................"),
+    ("r-pentomino", "\
+.OO
+OO.
+.O."),
+    ("acorn", "\
+.O.....
+...O...
+OO..OOO"),
+    ("diehard", "\
+......O.
+OO......
+.O...OOO"),
+    ("b-heptomino", "\
+O.OO
+OOO.
+.O.."),
+    ("block", "\
+OO
+OO"),
+    ("beehive", "\
+.OO.
+O..O
+.OO."),
+    ("loaf", "\
+.OO.
+O..O
+.O.O
+..O."),
+    ("boat", "\
+OO.
+O.O
+.O."),
+];
+
+/// The small patterns that take longest to settle down, which everyone wants
+/// to watch first, by their names in `PATTERNS` and how many generations it
+/// takes them on an unbounded board.
+pub const METHUSELAHS: &[(&str, u64)] = &[("r-pentomino", 1103), ("acorn", 5206), ("diehard", 130), ("b-heptomino", 148)];
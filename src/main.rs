@@ -1,330 +1,5487 @@
-use ggez;
-use rand;
+mod clipboard;
+mod event_loop;
+mod keybindings;
+mod options;
+#[cfg(feature = "pixel-buffer")]
+mod pixel_buffer;
+#[cfg(feature = "tui")]
+mod tui;
+mod tutorial;
 
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use ggez::event::{self, MouseButton, KeyCode, KeyMods};
+use life::achievements::{self, Achievement, GunWatch, Progress};
+use life::attack::{HighScores, Score};
+use life::board::{self, Board, DenseBoard, GridPosition, Selection, SoupSymmetry, Walls, SOUP_SYMMETRIES};
+use life::broadcast::{Broadcast, Watcher};
+use life::census;
+use life::cycle::{self, Behavior, Cycle, CycleDetector};
+use life::golf::{self, Scorecard};
+use life::hooks::Hooks;
+use life::label::Labels;
+use life::net::{self, Host, Message, Peer};
+use life::pattern::Pattern;
+use life::playlist::Playlist;
+use life::plugin::Plugins;
+use life::puzzle::Puzzle;
+use life::quiz::Quiz;
+use life::predecessor::{self, Search};
+use life::replay::{Event, Player, Recorder, Replay};
+use life::report::{PopulationCurve, Report};
+use life::rule::{self, Rule, Transition};
+use life::search::{self, Leaderboard, Lifespan, SoupResult};
+use life::share::Setup;
+use life::sound;
+use life::stats::{self, Activity};
+use life::versus::Match;
+use life::library;
+
+use ggez::event::winit_event::TouchPhase;
+use ggez::event::{self, Axis, Button, EventHandler, MouseButton, KeyCode, KeyMods};
+use ggez::audio::{self, SoundSource};
 use ggez::{graphics, Context, GameResult};
+use event_loop::InputHandler;
+use keybindings::{gamepad_action, Action, KeyBindings};
+use options::Options;
+use tutorial::Tutorial;
+
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
-const GRID_SIZE: (i16, i16) = (200, 150);
-const GRID_CELL_SIZE: (i16, i16) = (8, 8);
+/// The ggez game id and author, which also name the user config directory.
+const GAME_ID: &str = "Life";
+const AUTHOR: &str = "Jon Liss";
+
+const STATUS_BAR_HEIGHT: f32 = 24.0;
+
+const MIN_CELL_SIZE: f32 = 1.0;
+const MAX_ZOOM: f32 = 16.0;
+const ZOOM_STEP: f32 = 1.25;
+
+/// Stick deflection below this is treated as centered.
+const STICK_DEADZONE: f32 = 0.25;
+/// How far a fully deflected left stick pans the view each second, in pixels.
+const STICK_PAN_SPEED: f32 = 800.0;
+/// How often a held right stick moves the cursor a cell.
+const STICK_CURSOR_REPEAT: Duration = Duration::from_millis(80);
+
+/// A touch that moves less than this, in pixels, is a tap rather than a drag.
+const TAP_SLOP: f32 = 10.0;
+
+const MINIMAP_SIZE: f32 = 200.0;
+const MINIMAP_MARGIN: f32 = 10.0;
+const MINIMAP_BLOCK: i16 = 4;
+
+/// The rule editor's buttons, and the gap around them.
+const RULE_BUTTON_SIZE: f32 = 22.0;
+const RULE_BUTTON_GAP: f32 = 4.0;
+
+/// How tall the timeline of past generations is, and how far it sits above the status bar.
+const TIMELINE_HEIGHT: f32 = 10.0;
+const TIMELINE_MARGIN: f32 = 6.0;
+
+/// Each grow or shrink scales both sides of the board by this much.
+const BOARD_RESIZE_STEP: f32 = 1.25;
+const MIN_BOARD_SIZE: i16 = net::MIN_BOARD_SIZE;
+const MAX_BOARD_SIZE: i16 = net::MAX_BOARD_SIZE;
+
+const UNDO_LIMIT: usize = 100;
+const GENERATION_HISTORY_LIMIT: usize = 256;
+
+/// How many previous generations the onion skin can show at once.
+const ONION_SKIN_LAYERS: usize = 3;
+/// Opacity of the most recent previous generation; older ones fade from there.
+const ONION_SKIN_ALPHA: f32 = 0.45;
+
+/// Cells smaller than this, in pixels, are always drawn as plain squares.
+const MIN_SHAPED_CELL_SIZE: f32 = 4.0;
+/// Space left around shaped cells, as a fraction of the cell size.
+const CELL_GAP: f32 = 0.1;
+
+/// Width and height of the pictures cells are drawn from, in pixels.
+const CELL_IMAGE_SIZE: u16 = 64;
+
+/// Width and height in cells of the tiles the board image is cut into, so
+/// only the tiles something changed in need making again.
+const BOARD_TILE_SIZE: usize = 128;
+
+const DENSITY: f32 = 0.25;
+const MIN_DENSITY: f32 = 0.05;
+const MAX_DENSITY: f32 = 0.5;
+const DENSITY_STEP: f32 = 0.05;
 
-const SCREEN_SIZE: (f32, f32) = (
-    GRID_SIZE.0 as f32 * GRID_CELL_SIZE.0 as f32,
-    GRID_SIZE.1 as f32 * GRID_CELL_SIZE.1 as f32,
-    );
+const VOLUME: f32 = 0.5;
+const VOLUME_STEP: f32 = 0.1;
+/// How loud the ticks are at full volume and the busiest the board gets,
+/// kept low so they're a texture rather than a racket.
+const TICK_VOLUME: f32 = 0.3;
+/// The pitch and length, in Hz and seconds, of the ticks for births and for deaths.
+const BIRTH_TICK: (f32, f32) = (1320.0, 0.03);
+const DEATH_TICK: (f32, f32) = (330.0, 0.04);
+/// How many notes the board plays at once when it's played as music, and
+/// how loud they are at full volume.
+const BOARD_NOTES: usize = 6;
+const BOARD_MUSIC_VOLUME: f32 = 0.5;
+/// The shortest and longest a generation's chord lasts, in seconds, however
+/// fast or slow the board is stepping.
+const CHORD_DURATION: (f32, f32) = (0.05, 1.0);
+/// Where the background music comes from, relative to where the game is run.
+const MUSIC_DIR: &str = "assets/music";
 
 const UPDATES_PER_SECOND: f32 = 20.0;
-const MILLIS_PER_UPDATE: u64 = (1.0 / UPDATES_PER_SECOND * 1000.0) as u64;
+/// The most generations a single update catches up on after a slow frame,
+/// beyond which the board falls behind rather than never catching up.
+const MAX_CATCH_UP_STEPS: u32 = 8;
+/// How long each update spends stepping towards a warp's generation before
+/// letting a frame be drawn to show how far it's got.
+const WARP_FRAME_TIME: Duration = Duration::from_millis(30);
+/// Width of the bar showing how far a warp has got.
+const WARP_BAR_WIDTH: f32 = 320.0;
+/// Generations run each frame in turbo mode, to start with and at most.
+const TURBO_GENERATIONS: u32 = 100;
+const MAX_TURBO_GENERATIONS: u32 = 100_000;
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-struct GridPosition {
-    x: i16,
-    y: i16,
+/// Running pauses by itself once the board repeats with at most this period.
+const AUTO_PAUSE_MAX_PERIOD: usize = 30;
+/// How long finding a period runs for before giving up.
+const PERIOD_SEARCH_GENERATIONS: u64 = 256;
+/// The biggest selection, each way, to search for a predecessor of, and how
+/// many rows of cells to try before giving up.
+const PREDECESSOR_MAX_SIZE: i16 = 8;
+const PREDECESSOR_SEARCH_STEPS: u64 = 20_000_000;
+/// The most kinds of object a census notice lists before summing up the rest.
+const CENSUS_NOTICE_KINDS: usize = 6;
+/// How many cells square the regions activity is tracked in are, and over how
+/// many generations.
+const ACTIVITY_REGION_SIZE: i16 = 16;
+const ACTIVITY_WINDOW: usize = 100;
+/// How many cells each player places in a versus match, how long the match
+/// then runs for, and the players' colors.
+const VERSUS_CELLS: u32 = 20;
+const VERSUS_GENERATIONS: u64 = 200;
+const VERSUS_COLORS: [[f32; 4]; 2] = [[0.2, 0.6, 1.0, 1.0], [1.0, 0.3, 0.3, 1.0]];
+const VERSUS_NAMES: [&str; 2] = ["blue", "red"];
+/// How many layers a board can have, counting itself, and the colors the
+/// sketches on top of it are drawn in while another layer is being edited.
+const MAX_LAYERS: usize = 4;
+const LAYER_COLORS: [[f32; 4]; 3] = [[0.9, 0.6, 0.0, 1.0], [0.35, 0.7, 0.9, 1.0], [0.8, 0.6, 0.7, 1.0]];
+/// The icy tint over frozen regions.
+const FROZEN_COLOR: [f32; 4] = [0.6, 0.85, 1.0, 1.0];
+/// Walls are drawn in stone whatever the palette: solid if they're held
+/// alive, faint with a cross through them if they're held dead.
+const WALL_COLOR: [f32; 4] = [0.6, 0.6, 0.65, 1.0];
+/// How many cells a time attack allows, how long there is to place them, and
+/// how many generations they run for before the population is scored.
+const TIME_ATTACK_CELLS: usize = 12;
+const TIME_ATTACK_SECONDS: u64 = 60;
+const TIME_ATTACK_GENERATIONS: u64 = 100;
+/// Where the best life golf scores are kept, in the user data directory.
+const SCORECARD_FILE: &str = "golf.tsv";
+/// How many names each quiz question offers to pick from, one for each answer key.
+const QUIZ_CHOICES: usize = 4;
+/// The least time between boards a headless run sends to its broadcast's watchers.
+const HEADLESS_BROADCAST_INTERVAL: Duration = Duration::from_millis(50);
+/// How many time attack scores are kept, and where, in the user data directory.
+const HIGH_SCORES_SIZE: usize = 10;
+const HIGH_SCORES_FILE: &str = "time_attack.tsv";
+/// How big the cells of a puzzle's target are drawn.
+const PUZZLE_TARGET_CELL_SIZE: f32 = 6.0;
+/// How many of the longest lived soups of a session are remembered.
+const LEADERBOARD_SIZE: usize = 10;
+/// A soup search gives up on a soup that hasn't settled by this generation.
+const SOUP_MAX_GENERATIONS: u64 = 10_000;
+/// How long a notice stays at the top of the screen.
+const NOTICE_DURATION: Duration = Duration::from_secs(5);
+/// How long an unlocked achievement stays up in the top right corner.
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+/// Where achievements are kept, in the user data directory.
+const ACHIEVEMENTS_FILE: &str = "achievements.txt";
+/// How often, in generations, the gliders on the board are counted while
+/// watching for a gun, and how many counts in a row have to go up.
+const GUN_CHECK_INTERVAL: u64 = 30;
+const GUN_CHECKS: usize = 4;
+const SPEEDS: [f32; 9] = [1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0, 240.0];
+
+/// Colors used to draw cells. `selection` outlines selections and
+/// `collision` marks stamped cells that would land on live ones.
+#[derive(Clone, Copy, Debug)]
+struct Palette {
+    name: &'static str,
+    cell: [f32; 4],
+    selection: [f32; 4],
+    collision: [f32; 4],
 }
 
-impl GridPosition {
-    pub fn new(x: i16, y: i16) -> Self {
-        GridPosition { x, y }
+impl Palette {
+    pub fn translucent(color: [f32; 4], alpha: f32) -> graphics::Color {
+        [color[0], color[1], color[2], alpha].into()
     }
-    pub fn random(max_x: i16, max_y: i16) -> Self {
-        let mut rng = rand::thread_rng();
-        (
-            rng.gen_range::<i16, i16, i16>(0, max_x),
-            rng.gen_range::<i16, i16, i16>(0, max_y),
+}
+
+// the colorblind palettes are built from the Okabe-Ito colors, chosen so the
+// cells, selections and collisions stay distinct under each kind of color blindness
+const PALETTES: [Palette; 6] = [
+    Palette { name: "Classic", cell: [1.0, 0.5, 0.0, 1.0], selection: [0.2, 0.6, 1.0, 1.0], collision: [1.0, 0.1, 0.1, 1.0] },
+    Palette { name: "Green", cell: [0.3, 0.9, 0.4, 1.0], selection: [0.2, 0.6, 1.0, 1.0], collision: [1.0, 0.1, 0.1, 1.0] },
+    Palette { name: "Cyan", cell: [0.4, 0.85, 1.0, 1.0], selection: [0.9, 0.3, 0.9, 1.0], collision: [1.0, 0.1, 0.1, 1.0] },
+    Palette { name: "Deuteranopia", cell: [0.902, 0.624, 0.0, 1.0], selection: [0.337, 0.706, 0.914, 1.0], collision: [0.0, 0.447, 0.698, 1.0] },
+    Palette { name: "Protanopia", cell: [0.941, 0.894, 0.259, 1.0], selection: [0.337, 0.706, 0.914, 1.0], collision: [0.0, 0.447, 0.698, 1.0] },
+    Palette { name: "Tritanopia", cell: [0.835, 0.369, 0.0, 1.0], selection: [0.0, 0.62, 0.451, 1.0], collision: [0.0, 0.0, 0.0, 1.0] },
+];
+
+/// Colors for everything around the cells: the background, grid lines and HUD.
+#[derive(Clone, Copy, Debug)]
+struct Theme {
+    name: &'static str,
+    background: [f32; 4],
+    grid: [f32; 4],
+    hud_text: [f32; 4],
+    hud_background: [f32; 4],
+}
+
+const THEMES: [Theme; 3] = [
+    Theme { name: "Classic", background: [0.439, 0.439, 0.439, 1.0], grid: [0.0, 0.0, 0.0, 0.15], hud_text: [1.0, 1.0, 1.0, 1.0], hud_background: [0.1, 0.1, 0.1, 1.0] },
+    Theme { name: "Dark", background: [0.08, 0.08, 0.1, 1.0], grid: [1.0, 1.0, 1.0, 0.08], hud_text: [0.9, 0.9, 0.9, 1.0], hud_background: [0.0, 0.0, 0.0, 1.0] },
+    Theme { name: "Light", background: [0.96, 0.96, 0.94, 1.0], grid: [0.0, 0.0, 0.0, 0.12], hud_text: [0.1, 0.1, 0.1, 1.0], hud_background: [0.85, 0.85, 0.85, 1.0] },
+];
+const DARK_THEME: usize = 1;
+const LIGHT_THEME: usize = 2;
+
+/// Grid lines are left out when cells get smaller than this, in pixels.
+const MIN_GRID_CELL_SIZE: f32 = 4.0;
+
+/// Maps between grid positions and screen pixels. At zoom 1 the grid is
+/// scaled to fit the window and centered in whatever space is left over;
+/// zooming in scales it up from there and it can then be panned around.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Viewport {
+    /// Size of the board in cells.
+    grid: (i16, i16),
+    origin: (f32, f32),
+    cell_size: (f32, f32),
+    screen: (f32, f32),
+    zoom: f32,
+    /// Backbuffer pixels per screen unit, above 1 on scaled displays.
+    scale_factor: f32,
+}
+
+impl Viewport {
+    pub fn new(grid: (i16, i16), cell_size: f32, width: f32, height: f32) -> Self {
+        let mut viewport = Viewport {
+            grid,
+            origin: (0.0, 0.0),
+            cell_size: (cell_size, cell_size),
+            screen: (width, height),
+            zoom: 1.0,
+            scale_factor: 1.0,
+        };
+        viewport.fit(width, height);
+        viewport
+    }
+
+    fn fit_size(&self, width: f32, height: f32) -> f32 {
+        (width / self.grid.0 as f32)
+            .min(height / self.grid.1 as f32)
+            .max(MIN_CELL_SIZE)
+    }
+
+    /// Rescale the cells for a window of the given size, keeping the zoom and
+    /// whatever was in the middle of the screen.
+    pub fn fit(&mut self, width: f32, height: f32) {
+        let center = self.grid_point(self.screen.0 / 2.0, self.screen.1 / 2.0);
+        let size = self.fit_size(width, height) * self.zoom;
+
+        self.screen = (width, height);
+        self.cell_size = (size, size);
+        self.center_on(center.0, center.1);
+    }
+
+    /// Switch to a board of a different size, where `offset` is how far the old
+    /// board's cells moved on the new one.
+    pub fn resize_grid(&mut self, grid: (i16, i16), offset: (i16, i16)) {
+        let center = self.grid_point(self.screen.0 / 2.0, self.screen.1 / 2.0);
+        self.grid = grid;
+
+        let size = self.fit_size(self.screen.0, self.screen.1) * self.zoom;
+        self.cell_size = (size, size);
+        self.center_on(center.0 + offset.0 as f32, center.1 + offset.1 as f32);
+    }
+
+    /// Grid coordinates (in fractional cells) of a screen point.
+    fn grid_point(&self, x: f32, y: f32) -> (f32, f32) {
+        ((x - self.origin.0) / self.cell_size.0, (y - self.origin.1) / self.cell_size.1)
+    }
+
+    /// Put the given grid coordinates in the middle of the screen.
+    pub fn center_on(&mut self, grid_x: f32, grid_y: f32) {
+        self.origin = (
+            self.screen.0 / 2.0 - grid_x * self.cell_size.0,
+            self.screen.1 / 2.0 - grid_y * self.cell_size.1,
+            );
+        self.clamp();
+    }
+
+    /// Keep the board on screen: centered along any axis where it fits, and
+    /// with no empty margin along any axis where it doesn't.
+    fn clamp(&mut self) {
+        let board = (self.grid.0 as f32 * self.cell_size.0, self.grid.1 as f32 * self.cell_size.1);
+        let clamp_axis = |origin: f32, board: f32, screen: f32| {
+            if board <= screen {
+                (screen - board) / 2.0
+            } else {
+                origin.min(0.0).max(screen - board)
+            }
+        };
+
+        self.origin = (
+            clamp_axis(self.origin.0, board.0, self.screen.0),
+            clamp_axis(self.origin.1, board.1, self.screen.1),
+            );
+    }
+
+    /// Zoom by `factor`, keeping the grid point under the given screen point still.
+    pub fn zoom_at(&mut self, x: f32, y: f32, factor: f32) {
+        let (grid_x, grid_y) = self.grid_point(x, y);
+
+        self.zoom = (self.zoom * factor).clamp(1.0, MAX_ZOOM);
+        let size = self.fit_size(self.screen.0, self.screen.1) * self.zoom;
+        self.cell_size = (size, size);
+
+        self.origin = (x - grid_x * size, y - grid_y * size);
+        self.clamp();
+    }
+
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        self.origin = (self.origin.0 + dx, self.origin.1 + dy);
+        self.clamp();
+    }
+
+    /// Whether part of the board is off screen.
+    pub fn is_cropped(&self) -> bool {
+        self.grid.0 as f32 * self.cell_size.0 > self.screen.0 + 0.5
+            || self.grid.1 as f32 * self.cell_size.1 > self.screen.1 + 0.5
+    }
+
+    /// The part of the board that's on screen, in fractional grid coordinates.
+    pub fn visible_grid_rect(&self) -> graphics::Rect {
+        let (left, top) = self.grid_point(0.0, 0.0);
+        let (right, bottom) = self.grid_point(self.screen.0, self.screen.1);
+
+        graphics::Rect::new(
+            left.max(0.0),
+            top.max(0.0),
+            right.min(self.grid.0 as f32) - left.max(0.0),
+            bottom.min(self.grid.1 as f32) - top.max(0.0),
             )
-            .into()
+    }
+
+    /// The cells at least partly on screen, if any are.
+    pub fn visible_cells(&self) -> Option<Selection> {
+        let visible = self.visible_grid_rect();
+        if visible.w <= 0.0 || visible.h <= 0.0 {
+            return None;
+        }
+
+        Some(Selection {
+            start: GridPosition::new(visible.x.floor() as i16, visible.y.floor() as i16),
+            end: GridPosition::new(
+                ((visible.x + visible.w).ceil() as i16 - 1).min(self.grid.0 - 1),
+                ((visible.y + visible.h).ceil() as i16 - 1).min(self.grid.1 - 1),
+            ),
+        })
+    }
+
+    /// Round a screen coordinate to the nearest backbuffer pixel, so cell
+    /// edges stay sharp and seamless at fractional display scales.
+    fn snap(&self, coordinate: f32) -> f32 {
+        (coordinate * self.scale_factor).round() / self.scale_factor
+    }
+
+    pub fn cell_rect(&self, pos: GridPosition) -> graphics::Rect {
+        let left = self.snap(self.origin.0 + pos.x as f32 * self.cell_size.0);
+        let top = self.snap(self.origin.1 + pos.y as f32 * self.cell_size.1);
+        let right = self.snap(self.origin.0 + (pos.x + 1) as f32 * self.cell_size.0);
+        let bottom = self.snap(self.origin.1 + (pos.y + 1) as f32 * self.cell_size.1);
+
+        graphics::Rect::new(left, top, right - left, bottom - top)
+    }
+
+    /// Screen rectangle covering every cell of the given selection.
+    pub fn selection_rect(&self, selection: &Selection) -> graphics::Rect {
+        let (min, max) = selection.bounds();
+        let mut rect = self.cell_rect(min);
+        let last = self.cell_rect(max);
+        rect.w = last.right() - rect.x;
+        rect.h = last.bottom() - rect.y;
+        rect
+    }
+
+    /// The grid position under the given screen point, if it lands on the board.
+    pub fn grid_position(&self, x: f32, y: f32) -> Option<GridPosition> {
+        let (grid_x, grid_y) = self.grid_point(x, y);
+        let (grid_x, grid_y) = (grid_x.floor(), grid_y.floor());
+
+        if (0.0..self.grid.0 as f32).contains(&grid_x) && (0.0..self.grid.1 as f32).contains(&grid_y) {
+            Some(GridPosition::new(grid_x as i16, grid_y as i16))
+        } else {
+            None
+        }
     }
 }
 
-impl From<GridPosition> for graphics::Rect {
-    fn from(pos: GridPosition) -> Self {
-        graphics::Rect::new_i32(
-            pos.x as i32 * GRID_CELL_SIZE.0 as i32,
-            pos.y as i32 * GRID_CELL_SIZE.1 as i32,
-            GRID_CELL_SIZE.0 as i32,
-            GRID_CELL_SIZE.1 as i32,
+/// A scaled down overview of the whole board, shown in the bottom right corner
+/// whenever the viewport is zoomed in past it.
+struct Minimap;
+
+impl Minimap {
+    /// Screen area the minimap takes up for the given viewport.
+    pub fn rect(viewport: &Viewport) -> graphics::Rect {
+        let (grid_w, grid_h) = viewport.grid;
+        let scale = MINIMAP_SIZE / grid_w.max(grid_h) as f32;
+        let (w, h) = (grid_w as f32 * scale, grid_h as f32 * scale);
+
+        graphics::Rect::new(
+            viewport.screen.0 - w - MINIMAP_MARGIN,
+            viewport.screen.1 - h - MINIMAP_MARGIN,
+            w,
+            h,
             )
     }
+
+    /// Grid coordinates under a screen point, if the point is on the minimap.
+    pub fn grid_point(viewport: &Viewport, x: f32, y: f32) -> Option<(f32, f32)> {
+        let rect = Self::rect(viewport);
+
+        if viewport.is_cropped() && rect.contains(ggez::mint::Point2 { x, y }) {
+            Some((
+                (x - rect.x) / rect.w * viewport.grid.0 as f32,
+                (y - rect.y) / rect.h * viewport.grid.1 as f32,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Shade blocks of the board by how many of their cells are alive and
+    /// outline the part that's currently on screen.
+    pub fn draw(ctx: &mut Context, viewport: &Viewport, board: &dyn Board, theme: &Theme, color: [f32; 4]) -> GameResult<()> {
+        let rect = Self::rect(viewport);
+        let (grid_w, grid_h) = viewport.grid;
+        let scale = (rect.w / grid_w as f32, rect.h / grid_h as f32);
+        let mut minimap = graphics::MeshBuilder::new();
+        minimap.rectangle(graphics::DrawMode::fill(), rect, Palette::translucent(theme.hud_background, 0.7));
+
+        // count the live cells in each block, keyed by the block's top-left cell
+        let mut blocks = HashMap::new();
+        for pos in board.live_cells() {
+            let corner = (pos.x - pos.x % MINIMAP_BLOCK, pos.y - pos.y % MINIMAP_BLOCK);
+            *blocks.entry(corner).or_insert(0) += 1;
+        }
+
+        for ((block_x, block_y), live) in blocks {
+            let density = live as f32 / (MINIMAP_BLOCK * MINIMAP_BLOCK) as f32;
+            let (max_x, max_y) = ((block_x + MINIMAP_BLOCK - 1).min(grid_w - 1), (block_y + MINIMAP_BLOCK - 1).min(grid_h - 1));
+            let block_rect = graphics::Rect::new(
+                rect.x + block_x as f32 * scale.0,
+                rect.y + block_y as f32 * scale.1,
+                (max_x - block_x + 1) as f32 * scale.0,
+                (max_y - block_y + 1) as f32 * scale.1,
+                );
+            // even a single live cell should be visible
+            let shade = [color[0], color[1], color[2], 0.3 + 0.7 * density];
+            minimap.rectangle(graphics::DrawMode::fill(), block_rect, shade.into());
+        }
+
+        let visible = viewport.visible_grid_rect();
+        let camera = graphics::Rect::new(
+            rect.x + visible.x * scale.0,
+            rect.y + visible.y * scale.1,
+            visible.w * scale.0,
+            visible.h * scale.1,
+            );
+        minimap.rectangle(graphics::DrawMode::stroke(1.0), camera, theme.hud_text.into());
+        minimap.rectangle(graphics::DrawMode::stroke(1.0), rect, [0.6, 0.6, 0.6, 1.0].into());
+
+        let minimap = minimap.build(ctx)?;
+        graphics::draw(ctx, &minimap, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))
+    }
+}
+
+/// Which screen the app is showing. The editor and running modes show the
+/// board and take edits; the others are menus drawn over it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Mode {
+    /// The choice of what to play, shown at startup.
+    Start,
+    MainMenu,
+    Editor,
+    Running,
+    PatternBrowser,
+    Settings,
 }
 
-impl From<(i16, i16)> for GridPosition {
-    fn from(pos: (i16, i16)) -> Self {
-        GridPosition { x: pos.0, y: pos.1 }
+impl Mode {
+    pub fn shows_board(self) -> bool {
+        self == Mode::Editor || self == Mode::Running
     }
 }
 
-#[derive(Clone, Debug)]
-struct Cell {
-    position: GridPosition,
-    dead: bool
+/// Entries on the settings screen, in the order they're listed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Setting {
+    Rule,
+    Speed,
+    Colors,
+    Theme,
+    Density,
+    SoupSymmetry,
+    Seed,
+    Brush,
+    Symmetry,
+    OnionSkin,
+    CellShape,
+    Animation,
+    AutoPause,
+    FrozenCounted,
+    ActivityOverlay,
+    BoardSize,
+    Sound,
+    BoardMusic,
+    Volume,
+    MusicVolume,
 }
 
-impl Cell {
-    pub fn new(pos: GridPosition, dead: bool) -> Self {
-        Cell {
-            position: pos,
-            dead: dead
-        }
+const SETTINGS: [Setting; 20] = [
+    Setting::Rule,
+    Setting::Speed,
+    Setting::Colors,
+    Setting::Theme,
+    Setting::Density,
+    Setting::SoupSymmetry,
+    Setting::Seed,
+    Setting::Brush,
+    Setting::Symmetry,
+    Setting::OnionSkin,
+    Setting::CellShape,
+    Setting::Animation,
+    Setting::AutoPause,
+    Setting::FrozenCounted,
+    Setting::ActivityOverlay,
+    Setting::BoardSize,
+    Setting::Sound,
+    Setting::BoardMusic,
+    Setting::Volume,
+    Setting::MusicVolume,
+];
+
+/// The shape of cells painted around the cursor, given by its radius in cells.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Brush {
+    Square(i16),
+    Circle(i16),
+}
+
+const BRUSHES: [Brush; 5] = [
+    Brush::Square(0),
+    Brush::Square(1),
+    Brush::Square(2),
+    Brush::Circle(2),
+    Brush::Circle(4),
+];
+
+impl Brush {
+    /// Positions covered by the brush when centered on `center`.
+    pub fn cells(self, center: GridPosition, grid_size: (i16, i16)) -> impl Iterator<Item = GridPosition> {
+        let radius = match self {
+            Brush::Square(radius) | Brush::Circle(radius) => radius,
+        };
+
+        (-radius..=radius)
+            .flat_map(move |dx| (-radius..=radius).map(move |dy| (dx, dy)))
+            .filter(move |&(dx, dy)| match self {
+                Brush::Square(_) => true,
+                // the extra `radius` rounds off the flat edges of small circles
+                Brush::Circle(_) => dx * dx + dy * dy <= radius * radius + radius,
+            })
+            .map(move |(dx, dy)| GridPosition::new(center.x + dx, center.y + dy))
+            .filter(move |pos| pos.x >= 0 && pos.x < grid_size.0 && pos.y >= 0 && pos.y < grid_size.1)
     }
 
-    fn draw(&self, ctx: &mut Context) -> GameResult<()> {
-        if !self.dead {
-            let rectangle = graphics::Mesh::new_rectangle(
-                ctx,
-                graphics::DrawMode::fill(),
-                self.position.into(),
-                [1.0, 0.5, 0.0, 1.0].into(),
-                )?;
-            graphics::draw(ctx, &rectangle, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
-            Ok(())
-        } else {
-            Ok(())
+    pub fn name(self) -> String {
+        match self {
+            Brush::Square(radius) => format!("{0}x{0} square", radius * 2 + 1),
+            Brush::Circle(radius) => format!("{}-wide circle", radius * 2 + 1),
         }
     }
 }
 
-struct GameState {
-    board: Vec<Vec<Cell>>,
-    last_update: Instant,
-    run: bool,
-    reset_board: bool,
-    mouse_down: bool,
-    lshift_pressed: bool,
+/// Mirroring applied to everything painted, reflecting across the board's center lines.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Symmetry {
+    None,
+    Horizontal,
+    Vertical,
+    FourFold,
+    Diagonal,
 }
 
-impl GameState {
-    pub fn new(cell_count: i16) -> Self {
-        let board = Self::generate_board(cell_count);
+const SYMMETRIES: [Symmetry; 5] = [
+    Symmetry::None,
+    Symmetry::Horizontal,
+    Symmetry::Vertical,
+    Symmetry::FourFold,
+    Symmetry::Diagonal,
+];
 
-        GameState {
-            board: board,
-            last_update: Instant::now(),
-            run: false,
-            reset_board: false,
-            mouse_down: false,
-            lshift_pressed: false,
-        }
+impl Symmetry {
+    /// `pos` and all of its mirror images that land on the board.
+    pub fn images(self, pos: GridPosition, grid_size: (i16, i16)) -> Vec<GridPosition> {
+        let mirror_x = GridPosition::new(grid_size.0 - 1 - pos.x, pos.y);
+        let mirror_y = GridPosition::new(pos.x, grid_size.1 - 1 - pos.y);
+
+        let mut images = match self {
+            Symmetry::None => vec![pos],
+            Symmetry::Horizontal => vec![pos, mirror_x],
+            Symmetry::Vertical => vec![pos, mirror_y],
+            Symmetry::FourFold => vec![pos, mirror_x, mirror_y, GridPosition::new(mirror_x.x, mirror_y.y)],
+            Symmetry::Diagonal => {
+                // swap the offsets from the center, which may fall off a non-square board
+                let (center_x, center_y) = (grid_size.0 / 2, grid_size.1 / 2);
+                vec![pos, GridPosition::new(center_x + (pos.y - center_y), center_y + (pos.x - center_x))]
+            }
+        };
+
+        images.retain(|pos| pos.x >= 0 && pos.x < grid_size.0 && pos.y >= 0 && pos.y < grid_size.1);
+        images
     }
+}
+
+/// A pattern following the mouse, waiting to be stamped onto the board. Sticky
+/// placements stay around after stamping so the pattern can be placed repeatedly.
+#[derive(Clone, Debug)]
+struct Placement {
+    pattern: Pattern,
+    sticky: bool,
+    /// The library pattern it was picked up from, if it was.
+    name: Option<&'static str>,
+}
 
-    fn generate_board(cell_count: i16) -> Vec<Vec<Cell>> {
-        let mut board = vec![];
+/// Selection contents that have been lifted off the board and are following
+/// the mouse until they're dropped.
+#[derive(Clone, Debug)]
+struct DragMove {
+    pattern: Pattern,
+    source: GridPosition,
+    destination: GridPosition,
+    grab_offset: GridPosition,
+}
 
-        // generate full grid of cells
-        for x in 0..GRID_SIZE.0 {
-            board.push( Vec::new());
+impl DragMove {
+    pub fn follow(&mut self, pos: GridPosition) {
+        self.destination = GridPosition::new(pos.x - self.grab_offset.x, pos.y - self.grab_offset.y);
+    }
+}
 
-            for y in 0..GRID_SIZE.1 {
-                let cell_pos = GridPosition::new(x, y);
-                let cell = Cell::new(cell_pos, true);
-                board[x as usize].push(cell);
-            }
-        }
+/// Snapshots of the board taken before each edit, so edits can be stepped
+/// back and forth.
+#[derive(Default)]
+struct EditHistory {
+    undo: Vec<Box<dyn Board>>,
+    redo: Vec<Box<dyn Board>>,
+    /// How many edits have been made, undone or redone, to tell whether the
+    /// board has been touched since some point.
+    changes: u64,
+}
 
-        let mut rng = rand::thread_rng();
-        let mut random_positions = Vec::new();
+impl EditHistory {
+    /// Remember the board as it is before an edit is made to it.
+    pub fn record(&mut self, board: &dyn Board) {
+        if self.undo.len() == UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(board.box_clone());
+        self.redo.clear();
+        self.changes += 1;
+    }
 
-        // get cell_count of random grid positions
-        for _ in 0..cell_count {
-            let random_pos = GridPosition::new(rng.gen_range(0, GRID_SIZE.0), rng.gen_range(0, GRID_SIZE.1));
-            random_positions.push(random_pos);
+    pub fn undo(&mut self, board: &mut Box<dyn Board>) {
+        if let Some(previous) = self.undo.pop() {
+            self.redo.push(std::mem::replace(board, previous));
+            self.changes += 1;
         }
+    }
 
-        // at these positions, set the cells to be alive (which will cause them to be displayed)
-        for position in &random_positions {
-            board[position.x as usize][position.y as usize].dead = false;
+    pub fn redo(&mut self, board: &mut Box<dyn Board>) {
+        if let Some(next) = self.redo.pop() {
+            self.undo.push(std::mem::replace(board, next));
+            self.changes += 1;
         }
+    }
+}
+
+/// A board that's drawn over the one that runs and edited on its own, to
+/// sketch a construction on before merging it down, with its own undo
+/// history and whether it's drawn while another layer is being edited.
+/// The layer being edited is the game's board, with a blank left here.
+struct Layer {
+    board: Box<dyn Board>,
+    history: EditHistory,
+    visible: bool,
+}
 
-        board
+impl Layer {
+    fn new(board: Box<dyn Board>) -> Self {
+        Layer { board, history: EditHistory::default(), visible: true }
     }
+}
 
-    fn neighbor_count(board: &Vec<Vec<Cell>>, cell: &Cell) -> i16 {
-        let mut neighbors = 0;
+/// How live cells are drawn. Anything but touching squares keeps neighboring
+/// cells apart, which shows a pattern's structure better when zoomed in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum CellShape {
+    Square,
+    Gapped,
+    Circle,
+    Rounded,
+}
 
-        let cell_x = cell.position.x as usize;
-        let cell_y = cell.position.y as usize;
+const CELL_SHAPES: [CellShape; 4] = [
+    CellShape::Square,
+    CellShape::Gapped,
+    CellShape::Circle,
+    CellShape::Rounded,
+];
 
-        if cell_x != 0 {
-            // check left
-            if !board[cell_x - 1][cell_y].dead {
-                neighbors += 1;
-            }
+impl CellShape {
+    pub fn name(self) -> &'static str {
+        match self {
+            CellShape::Square => "squares",
+            CellShape::Gapped => "squares with gaps",
+            CellShape::Circle => "circles",
+            CellShape::Rounded => "rounded squares",
+        }
+    }
 
-            if cell_y != 0 {
-                // check top left
-                if !board[cell_x - 1][cell_y - 1].dead {
-                    neighbors += 1;
-                }
-            }
+    /// Whether the point `x`, `y` of a cell, each from 0 to 1 across it, is
+    /// covered by the shape.
+    fn covers(self, x: f32, y: f32) -> bool {
+        let inside = |x: f32| (CELL_GAP..=1.0 - CELL_GAP).contains(&x);
 
-            if cell_y != GRID_SIZE.1 as usize - 1  {
-                // check bottom left
-                if !board[cell_x - 1][cell_y + 1].dead {
-                    neighbors += 1;
-                }
+        match self {
+            CellShape::Square => true,
+            CellShape::Gapped => inside(x) && inside(y),
+            CellShape::Circle => (x - 0.5).hypot(y - 0.5) <= 0.5 - CELL_GAP,
+            CellShape::Rounded => {
+                // the distance past the straight edges, which is only ever off
+                // both of them in a corner
+                let radius = (1.0 - 2.0 * CELL_GAP) / 4.0;
+                let edge = CELL_GAP + radius;
+                let past = |x: f32| (edge - x).max(x - (1.0 - edge)).max(0.0);
+                inside(x) && inside(y) && past(x).hypot(past(y)) <= radius
             }
         }
+    }
 
-        if cell_x != GRID_SIZE.0 as usize - 1 {
-            // check right
-            if !board[cell_x + 1][cell_y].dead {
-                neighbors += 1;
-            }
+    /// A white picture of the shape a cell is drawn with, antialiased by
+    /// sampling each pixel at a few points.
+    fn image(self, ctx: &mut Context) -> GameResult<graphics::Image> {
+        const SAMPLES: usize = 4;
+        let size = CELL_IMAGE_SIZE as usize;
 
-            if cell_y != 0 {
-                // check top right
-                if !board[cell_x + 1][cell_y - 1].dead {
-                    neighbors += 1;
-                }
+        let mut rgba = Vec::with_capacity(size * size * 4);
+        for y in 0..size {
+            for x in 0..size {
+                // where the `sample`th point of pixel `pixel` falls across the cell
+                let across = |pixel: usize, sample: usize| (pixel as f32 + (sample as f32 + 0.5) / SAMPLES as f32) / size as f32;
+                let covered = (0..SAMPLES * SAMPLES)
+                    .filter(|sample| self.covers(across(x, sample % SAMPLES), across(y, sample / SAMPLES)))
+                    .count();
+                let alpha = (covered * 255 / (SAMPLES * SAMPLES)) as u8;
+                rgba.extend_from_slice(&[255, 255, 255, alpha]);
             }
+        }
 
-            if cell_y != GRID_SIZE.1 as usize - 1  {
-                // check bottom right
-                if !board[cell_x + 1][cell_y + 1].dead {
-                    neighbors += 1;
-                }
+        let mut image = graphics::Image::from_rgba8(ctx, CELL_IMAGE_SIZE, CELL_IMAGE_SIZE, &rgba)?;
+        // plain squares should stay sharp however far they're scaled
+        image.set_filter(if self == CellShape::Square { graphics::FilterMode::Nearest } else { graphics::FilterMode::Linear });
+        Ok(image)
+    }
+}
+
+/// Everything besides the board itself that decides how its cells look, so
+/// the batch can tell when they need adding again.
+#[derive(Clone, Copy, PartialEq)]
+struct CellLook {
+    /// Where the batch was laid out for, or none for the board image, which
+    /// is the same wherever it's drawn.
+    viewport: Option<Viewport>,
+    shape_index: usize,
+    palette_index: usize,
+    onion_skin: usize,
+    fade_progress: Option<f32>,
+}
+
+/// Every cell drawn in one go. Cells drawn as plain squares are painted into
+/// an image with a pixel per cell, drawn scaled up, so drawing them costs the
+/// same however many are alive. The image is in tiles, and a generation only
+/// remakes the tiles that changed. Otherwise there's a sprite per cell, all
+/// sharing a picture of the cell shape, tinted and scaled to fit.
+struct CellBatch {
+    /// A picture for each of `CELL_SHAPES`.
+    images: Vec<graphics::Image>,
+    batch: graphics::spritebatch::SpriteBatch,
+    /// The board as tiles of an image row by row, the size of board they
+    /// cover and the pixels they were made from.
+    tiles: Vec<graphics::Image>,
+    tiled_size: (i16, i16),
+    pixels: Vec<u8>,
+    /// The next pixels, painted here and then compared with the last.
+    painting: Vec<u8>,
+    /// How the cells were last drawn, if they have been yet.
+    look: Option<CellLook>,
+}
+
+impl CellBatch {
+    pub fn new(ctx: &mut Context) -> GameResult<Self> {
+        let images = CELL_SHAPES.iter().map(|shape| shape.image(ctx)).collect::<GameResult<Vec<_>>>()?;
+        let batch = graphics::spritebatch::SpriteBatch::new(images[0].clone());
+        Ok(CellBatch { images, batch, tiles: Vec::new(), tiled_size: (0, 0), pixels: Vec::new(), painting: Vec::new(), look: None })
+    }
+
+    /// Paint `layers` of cells into the board image, each over the ones
+    /// before it, and remake whichever tiles came out different.
+    pub fn paint(&mut self, ctx: &mut Context, size: (i16, i16), layers: &[(&dyn Board, [f32; 4])]) -> GameResult<()> {
+        let (width, height) = (size.0 as usize, size.1 as usize);
+        self.painting.clear();
+        self.painting.resize(width * height * 4, 0);
+
+        for &(board, [r, g, b, a]) in layers {
+            for pos in board.live_cells() {
+                let pixel = &mut self.painting[(pos.y as usize * width + pos.x as usize) * 4..][..4];
+                let below = pixel[3] as f32 / 255.0;
+                let alpha = a + below * (1.0 - a);
+                let blend = |channel: f32, under: u8| {
+                    ((channel * a + under as f32 / 255.0 * below * (1.0 - a)) / alpha * 255.0).round() as u8
+                };
+                pixel.copy_from_slice(&[blend(r, pixel[0]), blend(g, pixel[1]), blend(b, pixel[2]), (alpha * 255.0).round() as u8]);
             }
         }
 
-        if cell_y != 0 {
-            // check top
-            if !board[cell_x][cell_y - 1].dead {
-                neighbors += 1;
-            }
+        // a different size of board needs a whole new set of tiles
+        let resized = self.tiled_size != size;
+        if resized {
+            self.tiles.clear();
         }
 
-        if cell_y != GRID_SIZE.1 as usize - 1 {
-            // check bottom
-            if !board[cell_x][cell_y + 1].dead {
-                neighbors += 1;
+        let mut index = 0;
+        for top in (0..height).step_by(BOARD_TILE_SIZE) {
+            for left in (0..width).step_by(BOARD_TILE_SIZE) {
+                let (w, h) = (BOARD_TILE_SIZE.min(width - left), BOARD_TILE_SIZE.min(height - top));
+                let row = |y: usize| (y * width + left) * 4..(y * width + left + w) * 4;
+
+                if resized || (top..top + h).any(|y| self.painting[row(y)] != self.pixels[row(y)]) {
+                    let rgba: Vec<u8> = (top..top + h).flat_map(|y| self.painting[row(y)].iter().copied()).collect();
+                    let mut tile = graphics::Image::from_rgba8(ctx, w as u16, h as u16, &rgba)?;
+                    tile.set_filter(graphics::FilterMode::Nearest);
+                    if resized {
+                        self.tiles.push(tile);
+                    } else {
+                        self.tiles[index] = tile;
+                    }
+                }
+                index += 1;
             }
         }
 
-        neighbors
+        std::mem::swap(&mut self.pixels, &mut self.painting);
+        self.tiled_size = size;
+        Ok(())
     }
 
-    fn toggle_cell(board: &mut Vec<Vec<Cell>>, x: f32, y: f32, mouse_motion: bool, lshift_pressed: bool) {
-        let grid_x = x as i16 / GRID_CELL_SIZE.0;
-        let grid_y = y as i16 / GRID_CELL_SIZE.1;
+    /// Start a new batch of cells drawn as `shape`, or as plain squares if
+    /// they're too small for the shape to show.
+    pub fn begin(&mut self, shape: CellShape, viewport: &Viewport) {
+        let shape = if viewport.cell_size.0 < MIN_SHAPED_CELL_SIZE { CellShape::Square } else { shape };
+        let index = CELL_SHAPES.iter().position(|&candidate| candidate == shape).unwrap_or(0);
 
-        if lshift_pressed {
-            board[grid_x as usize][grid_y as usize].dead = true;
-        } else {
-            match board[grid_x as usize][grid_y as usize] {
-                Cell { dead: true, .. } => board[grid_x as usize][grid_y as usize].dead = false,
-                Cell { dead: false, .. } => {
-                    if !mouse_motion {
-                        board[grid_x as usize][grid_y as usize].dead = true;
-                    }
+        self.batch.clear();
+        self.batch.set_image(self.images[index].clone());
+    }
+
+    /// Add a cell shrunk and faded to `amount`, from 0 for gone to 1 for fully drawn.
+    pub fn add(&mut self, viewport: &Viewport, pos: GridPosition, color: [f32; 4], amount: f32) {
+        if amount <= 0.0 {
+            return;
+        }
+
+        let rect = viewport.cell_rect(pos);
+        let (w, h) = (rect.w * amount, rect.h * amount);
+        let color = [color[0], color[1], color[2], color[3] * amount];
+
+        self.batch.add(
+            graphics::DrawParam::new()
+                .dest(ggez::mint::Point2 { x: rect.x + (rect.w - w) / 2.0, y: rect.y + (rect.h - h) / 2.0 })
+                .scale(ggez::mint::Vector2 { x: w / CELL_IMAGE_SIZE as f32, y: h / CELL_IMAGE_SIZE as f32 })
+                .color(color.into()),
+        );
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, viewport: &Viewport) -> GameResult<()> {
+        if let Some(CellLook { viewport: None, .. }) = self.look {
+            let across = (self.tiled_size.0 as usize).div_ceil(BOARD_TILE_SIZE);
+            let visible = match viewport.visible_cells() {
+                Some(visible) => visible,
+                None => return Ok(()),
+            };
+            let (first, last) = visible.bounds();
+
+            for (index, tile) in self.tiles.iter().enumerate() {
+                let (left, top) = (index % across * BOARD_TILE_SIZE, index / across * BOARD_TILE_SIZE);
+                let (past_x, past_y) = (left + tile.width() as usize, top + tile.height() as usize);
+                if past_x <= first.x as usize || left > last.x as usize || past_y <= first.y as usize || top > last.y as usize {
+                    continue;
                 }
+                let (left, top) = (left as f32, top as f32);
+                // stretch each tile to where the next one starts, so there are no seams between them
+                let edge = |origin: f32, cell_size: f32, cell: f32| viewport.snap(origin + cell * cell_size);
+                let (x, y) = (edge(viewport.origin.0, viewport.cell_size.0, left), edge(viewport.origin.1, viewport.cell_size.1, top));
+                let right = edge(viewport.origin.0, viewport.cell_size.0, left + tile.width() as f32);
+                let bottom = edge(viewport.origin.1, viewport.cell_size.1, top + tile.height() as f32);
+
+                graphics::draw(
+                    ctx,
+                    tile,
+                    graphics::DrawParam::new()
+                        .dest(ggez::mint::Point2 { x, y })
+                        .scale(ggez::mint::Vector2 { x: (right - x) / tile.width() as f32, y: (bottom - y) / tile.height() as f32 }),
+                )?;
             }
+            Ok(())
+        } else {
+            graphics::draw(ctx, &self.batch, graphics::DrawParam::new())
         }
     }
 }
 
-impl event::EventHandler for GameState {
-    fn update(&mut self, _ctx: &mut Context) -> GameResult {
-        if Instant::now() - self.last_update >= Duration::from_millis(MILLIS_PER_UPDATE) {
-            if self.reset_board {
-                for x in 0..GRID_SIZE.0 {
-                    for y in 0..GRID_SIZE.1 {
-                        self.board[x as usize][y as usize].dead = true;
-                    }
-                }
+/// A search through random soups, each run until it settles and then added
+/// to the results file along with a census of what it left.
+struct SoupSearch {
+    results: PathBuf,
+    soups: u64,
+    rare_finds: u64,
+}
 
-                self.reset_board = false;
-            }
+impl SoupSearch {
+    /// Start a search adding to `results`, which gets a header if it's new.
+    pub fn start(results: PathBuf) -> std::io::Result<Self> {
+        if !results.exists() {
+            std::fs::write(&results, format!("{}\n", search::RESULTS_HEADER))?;
+        }
+        Ok(SoupSearch { results, soups: 0, rare_finds: 0 })
+    }
+
+    pub fn record(&mut self, result: &SoupResult) -> std::io::Result<()> {
+        let mut file = std::fs::OpenOptions::new().append(true).open(&self.results)?;
+        writeln!(file, "{}", result)?;
+        self.soups += 1;
+        Ok(())
+    }
+}
 
-            if self.run {
-                let board_copy = self.board.to_vec();
+/// The puzzle being played: which one, and the board an attempt at it
+/// started from while the attempt is running.
+struct PuzzleRun {
+    index: usize,
+    start: Option<Box<dyn Board>>,
+    solved: bool,
+}
 
-                for x in 0..GRID_SIZE.0 {
-                    for y in 0..GRID_SIZE.1 {
-                        let cell = self.board[x as usize][y as usize].clone();
-                        let neighbors = Self::neighbor_count(&board_copy, &cell);
+/// A time attack going on: when the clock for placing cells started, and the
+/// board the attempt started from while it's running.
+struct AttackRun {
+    started: Instant,
+    start: Option<Box<dyn Board>>,
+}
 
-                        if cell.dead {
-                            if neighbors == 3 {
-                                self.board[x as usize][y as usize].dead = false;
-                            }
-                        } else {
-                            if neighbors < 2 || neighbors >= 4 {
-                                self.board[x as usize][y as usize].dead = true;
-                            }
-                        }
+/// The golf hole being played, as an index into `golf::HOLES`, and the
+/// board an attempt at it started from while the attempt is running.
+struct GolfRun {
+    hole: usize,
+    start: Option<Box<dyn Board>>,
+}
+
+/// Ticks for the births and deaths on the board, a tick of each for every
+/// frame with any, as loud as the generations since the last frame were
+/// busy on average. The board can also be played as music, a chord for each
+/// generation lasting as long as the generation does.
+struct SoundEffects {
+    enabled: bool,
+    music: bool,
+    volume: f32,
+    birth: audio::SoundData,
+    death: audio::SoundData,
+    /// Births and deaths since they were last played, and over how many generations.
+    born: usize,
+    died: usize,
+    generations: u32,
+    /// The generation the board was last played at as music.
+    played: Option<u64>,
+}
+
+impl SoundEffects {
+    fn new(enabled: bool, volume: f32) -> Self {
+        let tick = |(frequency, duration)| audio::SoundData::from_bytes(&sound::to_wav(&sound::tone(frequency, duration)));
+        SoundEffects { enabled, music: false, volume, birth: tick(BIRTH_TICK), death: tick(DEATH_TICK), born: 0, died: 0, generations: 0, played: None }
+    }
+
+    fn record(&mut self, born: usize, died: usize) {
+        self.born += born;
+        self.died += died;
+        self.generations += 1;
+    }
+
+    /// Play the ticks for everything since last time, for a board of `cells` cells.
+    fn play(&mut self, ctx: &mut Context, cells: usize) {
+        if self.enabled && self.generations > 0 {
+            for (sound, changes) in &[(&self.birth, self.born), (&self.death, self.died)] {
+                let loudness = sound::loudness(*changes as f32 / self.generations as f32, cells);
+                if loudness > 0.0 {
+                    // no sound device, or a sound it can't play, just means silence
+                    if let Ok(mut source) = audio::Source::from_data(ctx, (*sound).clone()) {
+                        source.set_volume(loudness * self.volume * TICK_VOLUME);
+                        let _ = source.play_detached();
                     }
                 }
             }
-            self.last_update = Instant::now();
         }
-        Ok(())
+        self.born = 0;
+        self.died = 0;
+        self.generations = 0;
     }
 
-    fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        graphics::clear(ctx, [0.439, 0.439, 0.439, 1.0].into());
-        for vec in self.board.iter() {
-            for cell in vec.iter() {
-                cell.draw(ctx)?;
-            }
+    /// Play `board`'s chord for `generation`, lasting `duration` seconds, if
+    /// it hasn't been played already.
+    fn play_board(&mut self, ctx: &mut Context, board: &dyn Board, generation: u64, duration: f32) {
+        if !self.music || self.played == Some(generation) {
+            return;
         }
+        self.played = Some(generation);
 
-        graphics::present(ctx)?;
-        ggez::timer::yield_now();
-        Ok(())
+        let notes = sound::notes(board, BOARD_NOTES);
+        if notes.is_empty() {
+            return;
+        }
+        let duration = duration.clamp(CHORD_DURATION.0, CHORD_DURATION.1);
+        let chord = audio::SoundData::from_bytes(&sound::to_wav(&sound::chord(&notes, duration)));
+        if let Ok(mut source) = audio::Source::from_data(ctx, chord) {
+            source.set_volume(self.volume * BOARD_MUSIC_VOLUME);
+            let _ = source.play_detached();
+        }
     }
+}
 
-    fn key_down_event(&mut self, _ctx: &mut Context, keycode: KeyCode, _keymod: KeyMods, _repeat: bool) {
-        match keycode {
-            KeyCode::Space => {
-                if self.run {
-                    self.run = false;
-                } else {
-                    self.run = true;
+/// The background music: the tracks from the music directory, the one
+/// playing, if any, and how loud.
+struct Music {
+    playlist: Playlist,
+    playing: Option<audio::Source>,
+    volume: f32,
+}
+
+impl Music {
+    fn new(playlist: Playlist, volume: f32) -> Self {
+        Music { playlist, playing: None, volume }
+    }
+
+    /// Play the current track from the start, going past any that won't
+    /// play, and say what it is.
+    fn start(&mut self, ctx: &mut Context) -> String {
+        self.playing = None;
+        while let Some(track) = self.playlist.current() {
+            let name = track.file_name().map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+            let played = std::fs::File::open(track)
+                .map_err(ggez::GameError::from)
+                .and_then(|mut file| audio::SoundData::from_read(&mut file))
+                .and_then(|data| audio::Source::from_data(ctx, data))
+                .and_then(|mut source| {
+                    source.set_volume(self.volume);
+                    source.play()?;
+                    Ok(source)
+                });
+            match played {
+                Ok(source) => {
+                    self.playing = Some(source);
+                    return format!("playing {}", name);
                 }
-            },
+                Err(err) => {
+                    eprintln!("skipping {}: {}", name, err);
+                    self.playlist.remove_current();
+                }
+            }
+        }
+        format!("no music to play: put .ogg, .flac, .wav or .mp3 files in {}", MUSIC_DIR)
+    }
 
-            KeyCode::Back => {
-                self.reset_board = true;
-            },
+    /// Pause the music if it's playing, and play it otherwise.
+    fn toggle(&mut self, ctx: &mut Context) -> String {
+        match &self.playing {
+            Some(source) if source.paused() => {
+                source.resume();
+                "music playing".to_string()
+            }
+            Some(source) => {
+                source.pause();
+                "music paused".to_string()
+            }
+            None => self.start(ctx),
+        }
+    }
 
-            KeyCode::LShift => {
-                self.lshift_pressed = true;
-            },
+    fn next(&mut self, ctx: &mut Context) -> String {
+        self.playlist.advance();
+        self.start(ctx)
+    }
 
-            _ => println!("{:?} is not a valid command!", keycode)
+    /// Move on to the next track once one finishes.
+    fn update(&mut self, ctx: &mut Context) {
+        if self.playing.as_ref().is_some_and(SoundSource::stopped) {
+            self.next(ctx);
         }
     }
 
-    fn key_up_event(&mut self, _ctx: &mut Context, keycode: KeyCode, _keymod: KeyMods) {
-        if keycode == KeyCode::LShift {
-            self.lshift_pressed = false;
+    fn set_volume(&mut self, volume: f32) {
+        self.volume = volume;
+        if let Some(source) = &mut self.playing {
+            source.set_volume(volume);
         }
     }
+}
 
-    fn mouse_button_up_event(&mut self, _ctx: &mut Context, _button: MouseButton, _x: f32, _y: f32) {
-        self.mouse_down = false;
+/// A strip along the bottom of the board with a mark for every generation
+/// that can be stepped back to, dragged along to go back and forth between them.
+struct Timeline;
+
+impl Timeline {
+    /// Screen area the timeline takes up for the given viewport, stopping
+    /// short of the minimap if it's showing.
+    pub fn rect(viewport: &Viewport) -> graphics::Rect {
+        let right = if viewport.is_cropped() { Minimap::rect(viewport).x } else { viewport.screen.0 } - MINIMAP_MARGIN;
+        graphics::Rect::new(MINIMAP_MARGIN, viewport.screen.1 - TIMELINE_MARGIN - TIMELINE_HEIGHT, right - MINIMAP_MARGIN, TIMELINE_HEIGHT)
     }
 
-    fn mouse_button_down_event(&mut self, _ctx: &mut Context, _button: MouseButton, x: f32, y: f32) {
-        self.mouse_down = true;
+    /// Which of `frames` generations is under a screen point, if the point
+    /// is on the timeline. Anywhere left or right of it is the first or last.
+    pub fn frame_at(viewport: &Viewport, frames: usize, x: f32) -> usize {
+        let rect = Self::rect(viewport);
+        let along = ((x - rect.x) / rect.w).clamp(0.0, 1.0);
+        (along * (frames - 1) as f32).round() as usize
+    }
+
+    /// The timeline for `frames` generations, with `showing` picked out
+    /// along it, labelled with its generation while it's being dragged.
+    pub fn draw(ctx: &mut Context, viewport: &Viewport, frames: usize, showing: usize, label: Option<u64>, theme: &Theme, color: [f32; 4]) -> GameResult<()> {
+        let rect = Self::rect(viewport);
+        let along = if frames > 1 { showing as f32 / (frames - 1) as f32 } else { 1.0 };
+        let handle_x = rect.x + along * rect.w;
+
+        let mut timeline = graphics::MeshBuilder::new();
+        timeline.rectangle(graphics::DrawMode::fill(), rect, Palette::translucent(theme.hud_background, 0.7));
+        timeline.rectangle(graphics::DrawMode::fill(), graphics::Rect::new(rect.x, rect.y, handle_x - rect.x, rect.h), Palette::translucent(color, 0.5));
+        timeline.rectangle(graphics::DrawMode::stroke(1.0), rect, [0.6, 0.6, 0.6, 1.0].into());
+        timeline.rectangle(graphics::DrawMode::fill(), graphics::Rect::new(handle_x - 2.0, rect.y - 3.0, 4.0, rect.h + 6.0), theme.hud_text.into());
+        let timeline = timeline.build(ctx)?;
+        graphics::draw(ctx, &timeline, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+
+        let generation = match label {
+            Some(generation) => generation,
+            None => return Ok(()),
+        };
+        let label = graphics::Text::new(format!("gen {}", generation));
+        let (width, height) = (label.width(ctx) as f32, label.height(ctx) as f32);
+        let x = (handle_x - width / 2.0).clamp(rect.x, rect.x + rect.w - width);
+        graphics::draw(ctx, &label, (ggez::mint::Point2 { x, y: rect.y - 4.0 - height }, theme.hud_text.into()))
+    }
+}
 
-        Self::toggle_cell(&mut self.board, x, y, false, self.lshift_pressed);
+/// A panel along the top of the board with a button for every count of live
+/// neighbors a cell can be born with and every count it can survive with,
+/// lit up for the ones the rule has and clicked to flip them.
+struct RuleEditor;
+
+impl RuleEditor {
+    /// Screen area the panel takes up for the given viewport: a row of
+    /// buttons for births over one for survival, each after its letter.
+    pub fn rect(viewport: &Viewport) -> graphics::Rect {
+        let w = RULE_BUTTON_GAP + 10.0 * (RULE_BUTTON_SIZE + RULE_BUTTON_GAP);
+        let h = RULE_BUTTON_GAP + 2.0 * (RULE_BUTTON_SIZE + RULE_BUTTON_GAP);
+        graphics::Rect::new((viewport.screen.0 - w) / 2.0, MINIMAP_MARGIN, w, h)
+    }
+
+    /// The button for births, or survival if `alive`, with `neighbors`.
+    fn button_rect(viewport: &Viewport, alive: bool, neighbors: u8) -> graphics::Rect {
+        let rect = Self::rect(viewport);
+        let step = RULE_BUTTON_SIZE + RULE_BUTTON_GAP;
+        let row = if alive { 1.0 } else { 0.0 };
+        // the first column is the row's letter
+        graphics::Rect::new(rect.x + RULE_BUTTON_GAP + (neighbors as f32 + 1.0) * step, rect.y + RULE_BUTTON_GAP + row * step, RULE_BUTTON_SIZE, RULE_BUTTON_SIZE)
+    }
+
+    /// Whether a live cell's button and the count of neighbors it's for is
+    /// under a screen point, if a button is.
+    pub fn button_at(viewport: &Viewport, x: f32, y: f32) -> Option<(bool, u8)> {
+        let point = ggez::mint::Point2 { x, y };
+        [false, true]
+            .iter()
+            .flat_map(|&alive| (0..=8).map(move |neighbors| (alive, neighbors)))
+            .find(|&(alive, neighbors)| Self::button_rect(viewport, alive, neighbors).contains(point))
     }
 
-    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _xrel: f32, _yrel: f32) {
-        if self.mouse_down {
-            Self::toggle_cell(&mut self.board, x, y, true, self.lshift_pressed);
+    pub fn draw(ctx: &mut Context, viewport: &Viewport, rule: Rule, theme: &Theme, color: [f32; 4]) -> GameResult<()> {
+        let mut panel = graphics::MeshBuilder::new();
+        panel.rectangle(graphics::DrawMode::fill(), Self::rect(viewport), Palette::translucent(theme.hud_background, 0.8));
+        let mut labels = Vec::new();
+        for &alive in &[false, true] {
+            let letter = Self::button_rect(viewport, alive, 0);
+            labels.push((if alive { "S" } else { "B" }.to_string(), letter.x - RULE_BUTTON_SIZE - RULE_BUTTON_GAP, letter.y, theme.hud_text));
+            for neighbors in 0..=8 {
+                let button = Self::button_rect(viewport, alive, neighbors);
+                let on = rule.next_state(alive, neighbors);
+                if on {
+                    panel.rectangle(graphics::DrawMode::fill(), button, color.into());
+                }
+                panel.rectangle(graphics::DrawMode::stroke(1.0), button, [0.6, 0.6, 0.6, 1.0].into());
+                labels.push((neighbors.to_string(), button.x, button.y, if on { theme.background } else { theme.hud_text }));
+            }
+        }
+        let panel = panel.build(ctx)?;
+        graphics::draw(ctx, &panel, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+
+        for (label, x, y, color) in labels {
+            let text = graphics::Text::new(label);
+            let (width, height) = (text.width(ctx) as f32, text.height(ctx) as f32);
+            let at = ggez::mint::Point2 { x: x + (RULE_BUTTON_SIZE - width) / 2.0, y: y + (RULE_BUTTON_SIZE - height) / 2.0 };
+            graphics::draw(ctx, &text, (at, color.into()))?;
         }
+        Ok(())
     }
 }
 
-fn main() -> GameResult {
-    let (ctx, events_loop) = &mut ggez::ContextBuilder::new("Life", "Jon Liss")
-        .window_setup(ggez::conf::WindowSetup::default().title("Life"))
-        .window_mode(ggez::conf::WindowMode::default().dimensions(SCREEN_SIZE.0, SCREEN_SIZE.1))
-        .build()?;
+/// The generations on the timeline while it's being dragged along, oldest
+/// first and ending with the one that was showing when the drag started,
+/// which one is showing now, and the generation of the last.
+struct Scrub {
+    frames: Vec<Box<dyn Board>>,
+    showing: usize,
+    generation: u64,
+}
+
+/// Something being typed in, with what's been typed so far.
+enum Entry {
+    /// A label for the cell at the position.
+    Label(GridPosition, String),
+    /// The generation to warp to.
+    Generation(String),
+}
 
-    let state = &mut GameState::new(0);
-    event::run(ctx, events_loop, state)
+/// A run to generation `to` from generation `from` as fast as the board
+/// steps, drawn only once a frame to show how far it's got, and when it started.
+struct Warp {
+    from: u64,
+    to: u64,
+    started: Instant,
+}
+
+/// The session being recorded, and when the recording started.
+struct Recording {
+    recorder: Recorder,
+    started: Instant,
+}
+
+/// A game mode with rules and a score of its own, or none. Only one can be
+/// played at a time, so starting one ends whichever was going on.
+enum GameMode {
+    Free,
+    Puzzle(PuzzleRun),
+    Golf(GolfRun),
+    Attack(AttackRun),
+    Quiz(Quiz),
+    Versus(Match),
+    /// How far through the tutorial the player is.
+    Tutorial(Tutorial),
+}
+
+/// What's drawn over the board, or instead of it, besides the cells.
+struct Overlays {
+    show_help: bool,
+    /// Whether density and entropy are shown.
+    show_stats: bool,
+    /// Whether the box around every live cell is outlined.
+    show_bounds: bool,
+    /// Whether cells that differ from the snapshot are highlighted.
+    show_diff: bool,
+    /// Whether the births and deaths being tracked are shaded in.
+    activity: bool,
+    /// How many previous generations are drawn faintly behind the board.
+    onion_skin: usize,
+    /// Whether the cells the next step will bring to life and kill are
+    /// outlined while the board is paused.
+    ghost: bool,
+    /// Whether the rule editor's buttons are shown.
+    rule_editor: bool,
+    /// Whether hovering over a cell describes it, instead of just giving
+    /// its position.
+    inspect: bool,
+}
+
+impl Default for Overlays {
+    fn default() -> Self {
+        Overlays {
+            show_help: false,
+            show_stats: false,
+            show_bounds: false,
+            show_diff: false,
+            activity: true,
+            onion_skin: 0,
+            ghost: false,
+            rule_editor: false,
+            inspect: false,
+        }
+    }
+}
+
+/// Everything that follows the board as it steps, which the hooks run after
+/// every step are handed to keep up to date.
+struct Followers {
+    /// Births and deaths by region while they're being tracked.
+    activity: Option<Activity>,
+    sound_effects: SoundEffects,
+    /// The game mode being played, whose versus match scores every step.
+    game: GameMode,
+    /// The session being recorded, if it is.
+    recording: Option<Recording>,
+    /// Achievements unlocked so far, over every session, and the ones
+    /// unlocked by the last step, still to be announced.
+    achievements: Progress,
+    unlocked: Vec<&'static Achievement>,
+    gun_watch: GunWatch,
+}
+
+impl Followers {
+    /// The hooks that keep the followers up to date.
+    fn hooks() -> Hooks<Followers> {
+        let mut hooks = Hooks::default();
+        hooks.on_generation(|followers: &mut Followers, _, board| {
+            if let Some(recording) = &mut followers.recording {
+                recording.recorder.stepped(recording.started.elapsed(), board);
+            }
+        });
+        hooks.on_cells_changed(|followers: &mut Followers, born, died| {
+            if let Some(activity) = &mut followers.activity {
+                activity.record(born, died);
+            }
+            if followers.sound_effects.enabled {
+                followers.sound_effects.record(born.len(), died.len());
+            }
+        });
+        hooks.on_step(|followers: &mut Followers, previous, board| {
+            if let GameMode::Versus(versus) = &mut followers.game {
+                versus.step(previous, board);
+            }
+        });
+        hooks.on_generation(|followers: &mut Followers, generation, board| {
+            if let Some(achievement) = followers.achievements.add_generation() {
+                followers.unlocked.push(achievement);
+            }
+            if generation.is_multiple_of(GUN_CHECK_INTERVAL) && !followers.achievements.is_unlocked("gunsmith") {
+                let gliders = census::census(board).iter().find(|(name, _)| name == "glider").map_or(0, |&(_, count)| count);
+                if followers.gun_watch.record(gliders) {
+                    followers.unlocked.extend(followers.achievements.unlock("gunsmith"));
+                }
+            }
+        });
+        hooks
+    }
+}
+
+/// A recorded session being played back, when it started, and the board as
+/// playback last left it, so anything painted over it can be put back.
+struct Playback {
+    player: Player,
+    started: Instant,
+    board: Box<dyn Board>,
+}
+
+/// Which end of a shared board this game is.
+enum Connection {
+    Host(Host),
+    Joined(Peer),
+    /// Watching someone else's broadcast, which can't be changed from here.
+    Watching(Watcher),
+}
+
+/// A board shared over the network, and the board and generation as
+/// everyone else last heard they were.
+struct Network {
+    connection: Connection,
+    shared: Box<dyn Board>,
+    generation: u64,
+}
+
+/// A board open in a tab that isn't showing, with everything that goes with
+/// it. The showing tab's copies of these live in `GameState`.
+struct Tab {
+    board: Box<dyn Board>,
+    viewport: Viewport,
+    rule: Rule,
+    plugin_index: Option<usize>,
+    updates_per_second: f32,
+    seed: u64,
+    rng: StdRng,
+    soups_generated: u64,
+    soup: Option<(u64, u64)>,
+    selection: Option<Selection>,
+    cursor: Option<GridPosition>,
+    history: EditHistory,
+    layers: Vec<Layer>,
+    layer: usize,
+    frozen: Vec<Selection>,
+    walls: Walls,
+    labels: Labels,
+    snapshot: Option<(u64, Box<dyn Board>)>,
+    show_diff: bool,
+    generations: VecDeque<Box<dyn Board>>,
+    generation: u64,
+    recent_steps: VecDeque<Instant>,
+}
+
+struct GameState {
+    board: Box<dyn Board>,
+    viewport: Viewport,
+    /// When the board was last due to step, whether or not it was running.
+    last_update: Instant,
+    /// Frames are only drawn this often, if set.
+    frame_rate: Option<f32>,
+    last_draw: Instant,
+    /// Whether the board runs `turbo_generations` each frame instead of
+    /// stepping at `updates_per_second`.
+    turbo: bool,
+    turbo_generations: u32,
+    mode: Mode,
+    menu_index: usize,
+    rule: Rule,
+    /// Rules from plugins, offered after the presets.
+    plugins: Plugins,
+    /// The plugin rule in use instead of `rule`, if any.
+    plugin_index: Option<usize>,
+    updates_per_second: f32,
+    palette_index: usize,
+    theme_index: usize,
+    density: f32,
+    /// How random fills are mirrored or turned onto themselves.
+    soup_symmetry_index: usize,
+    seed: u64,
+    /// Everything random comes from here, so a given seed always plays out the same way.
+    rng: StdRng,
+    soups_generated: u64,
+    /// The number of the soup on the board and how many changes the edit
+    /// history had when it was made, so a soup that's been touched since
+    /// doesn't make the leaderboard.
+    soup: Option<(u64, u64)>,
+    /// The longest lived soups of the session, in every tab.
+    leaderboard: Leaderboard,
+    mouse_down: bool,
+    panning: bool,
+    lshift_pressed: bool,
+    paint_dead: bool,
+    brush_index: usize,
+    symmetry_index: usize,
+    select_mode: bool,
+    selection: Option<Selection>,
+    clipboard: Option<Pattern>,
+    placement: Option<Placement>,
+    library_index: usize,
+    cursor: Option<GridPosition>,
+    keyboard_cursor: bool,
+    drag_move: Option<DragMove>,
+    history: EditHistory,
+    /// The board and the sketches over it, bottom first, and which is being
+    /// edited. Only the board at the bottom runs.
+    layers: Vec<Layer>,
+    layer: usize,
+    /// Regions of the board whose cells are held as they are while the rest
+    /// of it runs, and whether the cells around them see their live cells.
+    frozen: Vec<Selection>,
+    frozen_counted: bool,
+    /// Cells held alive or dead as the board runs, and which kind painting
+    /// builds, if it's building walls rather than cells.
+    walls: Walls,
+    wall_paint: Option<bool>,
+    generations: VecDeque<Box<dyn Board>>,
+    /// The timeline being dragged along, if it is.
+    scrub: Option<Scrub>,
+    rewind_step: usize,
+    cell_shape_index: usize,
+    /// Made on the first frame, since it needs the graphics context.
+    cell_batch: Option<CellBatch>,
+    /// Whether the board may have changed since its cells were last batched.
+    /// Set by anything that steps or edits it, so a board sitting still
+    /// reuses the same batch frame after frame.
+    cells_dirty: bool,
+    /// Whether cells fade in and out between generations while running.
+    animate: bool,
+    /// Whether running stops once the board settles into a still life or oscillator.
+    auto_pause: bool,
+    cycles: CycleDetector,
+    /// A message shown at the top of the screen, and when it went up.
+    notice: Option<(String, Instant)>,
+    /// Where soup searches record their results, and the search going on, if any.
+    results: PathBuf,
+    search: Option<SoupSearch>,
+    /// Where session reports are saved, and the population over every step
+    /// of the session that goes in them.
+    report: PathBuf,
+    /// Where the board is saved.
+    output: PathBuf,
+    population_curve: PopulationCurve,
+    music: Music,
+    /// Where settings changed in the game are saved, if they are.
+    config_file: Option<PathBuf>,
+    /// The built-in puzzles and any loaded.
+    puzzles: Vec<Puzzle>,
+    /// The best time attack scores.
+    high_scores: HighScores,
+    high_scores_file: Option<PathBuf>,
+    /// The board being shared with other games, if it is.
+    network: Option<Network>,
+    /// Where the board is being broadcast for others to watch, if it is.
+    broadcast: Option<Broadcast>,
+    /// Where sessions are recorded to and played back from, and the session
+    /// being played back, if any.
+    replay_file: PathBuf,
+    playback: Option<Playback>,
+    /// The best score at every golf hole.
+    scorecard: Scorecard,
+    scorecard_file: Option<PathBuf>,
+    /// Where achievements are saved, if anywhere.
+    achievements_file: Option<PathBuf>,
+    /// Notes pinned to cells, saved with the board.
+    labels: Labels,
+    /// What's being typed in, if anything, which the keys go to until it's done with.
+    entry: Option<Entry>,
+    warp: Option<Warp>,
+    /// Achievements just unlocked, and when, shown while they're new.
+    toasts: VecDeque<(&'static Achievement, Instant)>,
+    /// The board as it was when a snapshot was taken, and the generation then.
+    snapshot: Option<(u64, Box<dyn Board>)>,
+    overlays: Overlays,
+    key_bindings: KeyBindings,
+    generation: u64,
+    /// Run after every step, for anything that follows the simulation, and
+    /// what they keep up to date.
+    hooks: Hooks<Followers>,
+    followers: Followers,
+    /// The other tabs, in order, with the showing one left out.
+    tabs: Vec<Tab>,
+    /// Where the showing tab sits among all of them.
+    tab_index: usize,
+    recent_steps: VecDeque<Instant>,
+    left_stick: (f32, f32),
+    right_stick: (f32, f32),
+    last_stick_move: Instant,
+    touches: HashMap<u64, (f32, f32)>,
+    /// Where the only finger on the screen went down, while it could still be a tap.
+    tap: Option<(f32, f32)>,
+}
+
+impl GameState {
+    /// A new game, starting from an empty board unless the options ask for a
+    /// random soup.
+    pub fn new(options: &Options, key_bindings: KeyBindings) -> Self {
+        // the only entropy, and only without a seed; the settings show it so the run can be repeated
+        let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut board: Box<dyn Board> = Box::new(DenseBoard::new(options.grid_size));
+        if let Some(density) = options.density {
+            board::fill_symmetric(board.as_mut(), &Selection::whole_board(options.grid_size), density, options.symmetry, &mut rng);
+        }
+        let population_curve = PopulationCurve::new(board.population());
+        let (width, height) = Self::window_size(options);
+        let achievements_file = options.achievements.clone().or_else(|| Self::data_file(options, ACHIEVEMENTS_FILE));
+        let achievements = achievements_file.as_deref().map_or_else(Progress::default, |path| {
+            Progress::load(path).unwrap_or_else(|err| {
+                eprintln!("{}, starting achievements over", err);
+                Progress::default()
+            })
+        });
+        let scorecard_file = Self::data_file(options, SCORECARD_FILE);
+        let scorecard = scorecard_file.as_deref().map_or_else(Scorecard::default, |path| {
+            Scorecard::load(path).unwrap_or_else(|err| {
+                eprintln!("{}, starting the golf scorecard over", err);
+                Scorecard::default()
+            })
+        });
+        let high_scores_file = Self::data_file(options, HIGH_SCORES_FILE);
+        let high_scores = high_scores_file.as_deref().map_or_else(
+            || HighScores::new(HIGH_SCORES_SIZE),
+            |path| {
+                HighScores::load(path, HIGH_SCORES_SIZE).unwrap_or_else(|err| {
+                    eprintln!("{}, starting the high scores over", err);
+                    HighScores::new(HIGH_SCORES_SIZE)
+                })
+            },
+        );
+        let music = if options.headless {
+            Playlist::default()
+        } else {
+            Playlist::scan(Path::new(MUSIC_DIR)).unwrap_or_else(|err| {
+                eprintln!("couldn't read {}: {}", MUSIC_DIR, err);
+                Playlist::default()
+            })
+        };
+
+        GameState {
+            board,
+            viewport: Viewport::new(options.grid_size, options.cell_size, width, height - STATUS_BAR_HEIGHT),
+            last_update: Instant::now(),
+            frame_rate: options.frame_rate,
+            last_draw: Instant::now(),
+            turbo: false,
+            turbo_generations: options.turbo,
+            // straight to the board when there's already something to look at on it
+            mode: if options.pattern.is_some() || options.density.is_some() { Mode::Editor } else { Mode::Start },
+            menu_index: 0,
+            rule: options.rule,
+            plugins: Plugins::default(),
+            plugin_index: None,
+            updates_per_second: options.speed,
+            palette_index: 0,
+            theme_index: Self::theme_index(options.theme.as_deref()),
+            density: options.density.unwrap_or(DENSITY),
+            soup_symmetry_index: SOUP_SYMMETRIES.iter().position(|&symmetry| symmetry == options.symmetry).unwrap_or(0),
+            seed,
+            rng,
+            soups_generated: 0,
+            // the starting soup is the one reseeding first makes
+            soup: options.density.map(|_| (0, 0)),
+            leaderboard: Leaderboard::new(LEADERBOARD_SIZE),
+            mouse_down: false,
+            panning: false,
+            lshift_pressed: false,
+            paint_dead: false,
+            brush_index: 0,
+            symmetry_index: 0,
+            select_mode: false,
+            selection: None,
+            clipboard: None,
+            placement: None,
+            library_index: 0,
+            cursor: None,
+            keyboard_cursor: false,
+            drag_move: None,
+            history: EditHistory::default(),
+            layers: vec![Layer::new(Box::new(DenseBoard::new(options.grid_size)))],
+            layer: 0,
+            frozen: Vec::new(),
+            frozen_counted: true,
+            walls: Walls::default(),
+            wall_paint: None,
+            generations: VecDeque::new(),
+            scrub: None,
+            rewind_step: 1,
+            cell_shape_index: 0,
+            cell_batch: None,
+            cells_dirty: true,
+            animate: true,
+            auto_pause: true,
+            cycles: CycleDetector::new(AUTO_PAUSE_MAX_PERIOD),
+            notice: None,
+            results: options.results.clone().unwrap_or_else(|| "soups.tsv".into()),
+            report: options.report.clone().unwrap_or_else(|| "report.json".into()),
+            output: options.output.clone().unwrap_or_else(|| "board.rle".into()),
+            population_curve,
+            music: Music::new(music, options.music_volume),
+            config_file: config_file().filter(|_| !options.headless),
+            puzzles: Puzzle::builtin(),
+            network: None,
+            broadcast: None,
+            replay_file: options.record.clone().or_else(|| options.replay.clone()).unwrap_or_else(|| "replay.txt".into()),
+            playback: None,
+            scorecard,
+            scorecard_file,
+            high_scores,
+            high_scores_file,
+            achievements_file,
+            labels: Labels::default(),
+            entry: None,
+            warp: None,
+            toasts: VecDeque::new(),
+            search: None,
+            snapshot: None,
+            overlays: Overlays::default(),
+            key_bindings,
+            generation: 0,
+            hooks: Followers::hooks(),
+            followers: Followers {
+                activity: None,
+                // a headless run has nothing to play them on
+                sound_effects: SoundEffects::new(options.sound && !options.headless, options.volume),
+                game: GameMode::Free,
+                recording: None,
+                achievements,
+                unlocked: Vec::new(),
+                gun_watch: GunWatch::new(GUN_CHECKS),
+            },
+            tabs: Vec::new(),
+            tab_index: 0,
+            recent_steps: VecDeque::new(),
+            left_stick: (0.0, 0.0),
+            right_stick: (0.0, 0.0),
+            last_stick_move: Instant::now(),
+            touches: HashMap::new(),
+            tap: None,
+        }
+    }
+
+    /// Grow or shrink both sides of the board by one step.
+    fn step_board_size(&mut self, grow: bool) {
+        let (width, height) = self.board.size();
+        let factor = if grow { BOARD_RESIZE_STEP } else { 1.0 / BOARD_RESIZE_STEP };
+        let scale = |side: i16| ((side as f32 * factor).round() as i16).clamp(MIN_BOARD_SIZE, MAX_BOARD_SIZE);
+
+        self.resize_board((scale(width), scale(height)));
+    }
+
+    /// Give the board a new size, keeping its middle where it is and dropping
+    /// live cells that no longer fit.
+    fn resize_board(&mut self, size: (i16, i16)) {
+        let (width, height) = self.board.size();
+        if size == (width, height) {
+            return;
+        }
+
+        // put anything being dragged back first so it isn't lost
+        if let Some(drag) = self.drag_move.take() {
+            let source = drag.source;
+            self.drop_drag_move(drag, source);
+        }
+
+        let offset = ((size.0 - width) / 2, (size.1 - height) / 2);
+        let recentered = |old: &dyn Board| {
+            let mut board = old.blank(size);
+            for pos in old.live_cells() {
+                let (x, y) = (pos.x + offset.0, pos.y + offset.1);
+                if x >= 0 && x < size.0 && y >= 0 && y < size.1 {
+                    board.set_alive(GridPosition::new(x, y), true);
+                }
+            }
+            board
+        };
+        self.board = recentered(self.board.as_ref());
+        for layer in &mut self.layers {
+            layer.board = recentered(layer.board.as_ref());
+            layer.history = EditHistory::default();
+        }
+        self.frozen = std::mem::take(&mut self.frozen)
+            .iter()
+            .filter_map(|region| {
+                let (start, end) = region.bounds();
+                let start = GridPosition::new((start.x + offset.0).max(0), (start.y + offset.1).max(0));
+                let end = GridPosition::new((end.x + offset.0).min(size.0 - 1), (end.y + offset.1).min(size.1 - 1));
+                if start.x <= end.x && start.y <= end.y { Some(Selection { start, end }) } else { None }
+            })
+            .collect();
+        self.walls.shift(offset, size);
+        self.labels.shift(offset, size);
+        if let Some(Entry::Label(..)) = self.entry {
+            self.entry = None;
+        }
+        self.notify(format!("resized the board to {}x{}", size.0, size.1));
+
+        // everything remembered about the old board is the wrong size now
+        self.history = EditHistory::default();
+        self.soup = None;
+        if self.followers.activity.is_some() {
+            self.followers.activity = Some(Activity::new(size, ACTIVITY_REGION_SIZE, ACTIVITY_WINDOW));
+        }
+        self.generations.clear();
+        self.selection = None;
+        self.mouse_down = false;
+        self.cursor = None;
+        self.viewport.resize_grid(size, offset);
+    }
+
+    /// Trade everything that belongs to the showing tab for `tab`'s.
+    fn swap_tab(&mut self, tab: &mut Tab) {
+        // put anything being dragged back first so it lands on its own board
+        if let Some(drag) = self.drag_move.take() {
+            let source = drag.source;
+            self.drop_drag_move(drag, source);
+        }
+
+        std::mem::swap(&mut self.board, &mut tab.board);
+        std::mem::swap(&mut self.viewport, &mut tab.viewport);
+        std::mem::swap(&mut self.rule, &mut tab.rule);
+        std::mem::swap(&mut self.plugin_index, &mut tab.plugin_index);
+        std::mem::swap(&mut self.updates_per_second, &mut tab.updates_per_second);
+        std::mem::swap(&mut self.seed, &mut tab.seed);
+        std::mem::swap(&mut self.rng, &mut tab.rng);
+        std::mem::swap(&mut self.soups_generated, &mut tab.soups_generated);
+        std::mem::swap(&mut self.soup, &mut tab.soup);
+        std::mem::swap(&mut self.selection, &mut tab.selection);
+        std::mem::swap(&mut self.cursor, &mut tab.cursor);
+        std::mem::swap(&mut self.history, &mut tab.history);
+        std::mem::swap(&mut self.layers, &mut tab.layers);
+        std::mem::swap(&mut self.layer, &mut tab.layer);
+        std::mem::swap(&mut self.frozen, &mut tab.frozen);
+        std::mem::swap(&mut self.walls, &mut tab.walls);
+        std::mem::swap(&mut self.labels, &mut tab.labels);
+        std::mem::swap(&mut self.snapshot, &mut tab.snapshot);
+        std::mem::swap(&mut self.overlays.show_diff, &mut tab.show_diff);
+        std::mem::swap(&mut self.generations, &mut tab.generations);
+        std::mem::swap(&mut self.generation, &mut tab.generation);
+        std::mem::swap(&mut self.recent_steps, &mut tab.recent_steps);
+        // activity is tracked on whichever board is showing
+        if self.followers.activity.is_some() {
+            self.followers.activity = Some(Activity::new(self.board.size(), ACTIVITY_REGION_SIZE, ACTIVITY_WINDOW));
+        }
+
+        // the window may have changed size while the tab was hidden
+        let (width, height) = tab.viewport.screen;
+        self.viewport.scale_factor = tab.viewport.scale_factor;
+        self.viewport.fit(width, height);
+        self.mouse_down = false;
+        self.last_update = Instant::now();
+    }
+
+    /// Open an empty board the same size as this one, with the same rule and
+    /// speed, in a tab after this one and switch to it.
+    fn new_tab(&mut self) {
+        let mut tab = Tab {
+            board: self.board.blank(self.board.size()),
+            viewport: self.viewport,
+            rule: self.rule,
+            plugin_index: self.plugin_index,
+            updates_per_second: self.updates_per_second,
+            seed: self.seed,
+            rng: StdRng::seed_from_u64(self.seed),
+            soups_generated: 0,
+            soup: None,
+            selection: None,
+            cursor: None,
+            history: EditHistory::default(),
+            layers: vec![Layer::new(self.board.blank(self.board.size()))],
+            layer: 0,
+            frozen: Vec::new(),
+            walls: Walls::default(),
+            labels: Labels::default(),
+            snapshot: None,
+            show_diff: false,
+            generations: VecDeque::new(),
+            generation: 0,
+            recent_steps: VecDeque::new(),
+        };
+        self.swap_tab(&mut tab);
+        self.tabs.insert(self.tab_index, tab);
+        self.tab_index += 1;
+        self.notify(format!("opened tab {}", self.tab_index + 1));
+    }
+
+    /// Show the tab at `index` among all of them.
+    fn switch_tab(&mut self, index: usize) {
+        if index == self.tab_index || index > self.tabs.len() {
+            return;
+        }
+
+        // the showing tab isn't in the list, so anything after it is one earlier there
+        let mut tab = self.tabs.remove(if index > self.tab_index { index - 1 } else { index });
+        self.swap_tab(&mut tab);
+        self.tabs.insert(if self.tab_index < index { self.tab_index } else { self.tab_index - 1 }, tab);
+        self.tab_index = index;
+    }
+
+    /// Close the showing tab and show the one after it, or before it if it
+    /// was the last. The last tab left can't be closed.
+    fn close_tab(&mut self) {
+        if self.tabs.is_empty() {
+            self.notify("can't close the only tab".to_string());
+            return;
+        }
+
+        if self.tab_index == self.tabs.len() {
+            self.tab_index -= 1;
+        }
+        let mut tab = self.tabs.remove(self.tab_index);
+        self.swap_tab(&mut tab);
+        self.notify(format!("closed a tab, {} left", self.tabs.len() + 1));
+    }
+
+    /// Stamp a pattern in the middle of the board.
+    pub fn place_centered(&mut self, pattern: &Pattern) {
+        let (width, height) = self.board.size();
+        let origin = pattern.origin_centered_on(GridPosition::new(width / 2, height / 2));
+        pattern.stamp(self.board.as_mut(), origin);
+    }
+
+    /// Clear the board and start over from the methuselah at `index`, alone
+    /// in the middle so there's room for everything it throws off.
+    fn place_methuselah(&mut self, index: usize) {
+        let (name, generations) = library::METHUSELAHS[index];
+        let (_, cells) = library::PATTERNS.iter().find(|(pattern, _)| *pattern == name).expect("methuselahs are in the library");
+        self.history.record(self.board.as_ref());
+        let whole_board = Selection::whole_board(self.board.size());
+        board::set_region(self.board.as_mut(), &whole_board, true);
+        self.place_centered(&Pattern::from_plaintext(cells));
+        self.generation = 0;
+        self.generations.clear();
+        self.notify(format!("{}: settles down after {} generations, with room to grow", name, generations));
+    }
+
+    /// Index of the named theme, or the first one if there's no such theme.
+    fn theme_index(name: Option<&str>) -> usize {
+        let name = match name {
+            Some(name) => name,
+            None => return 0,
+        };
+
+        THEMES.iter().position(|theme| theme.name.eq_ignore_ascii_case(name)).unwrap_or_else(|| {
+            eprintln!("unknown theme `{}`, using {}", name, THEMES[0].name);
+            0
+        })
+    }
+
+    /// Where the file `name` goes in the user data directory. A headless run
+    /// is usually a script, so it leaves the player's files alone.
+    fn data_file(options: &Options, name: &str) -> Option<PathBuf> {
+        let dirs = directories::ProjectDirs::from("", AUTHOR, GAME_ID).filter(|_| !options.headless)?;
+        Some(dirs.data_dir().join(name))
+    }
+
+    /// Window size that shows the whole board at the requested cell size.
+    pub fn window_size(options: &Options) -> (f32, f32) {
+        (
+            options.grid_size.0 as f32 * options.cell_size,
+            options.grid_size.1 as f32 * options.cell_size + STATUS_BAR_HEIGHT,
+            )
+    }
+
+    /// Pan with the left stick and move the cursor with the right one, for as
+    /// long as they're held.
+    fn apply_sticks(&mut self, elapsed: Duration) {
+        if !self.mode.shows_board() {
+            return;
+        }
+
+        let (x, y) = self.left_stick;
+        if x.abs() > STICK_DEADZONE || y.abs() > STICK_DEADZONE {
+            let distance = STICK_PAN_SPEED * elapsed.as_secs_f32();
+            // sticks report up as positive, the screen counts down
+            self.viewport.pan(-x * distance, y * distance);
+        }
+
+        let (x, y) = self.right_stick;
+        if Instant::now() - self.last_stick_move >= STICK_CURSOR_REPEAT {
+            let step = |value: f32| if value > STICK_DEADZONE { 1 } else if value < -STICK_DEADZONE { -1 } else { 0 };
+            let (dx, dy) = (step(x), -step(y));
+
+            if dx != 0 || dy != 0 {
+                self.move_cursor(dx, dy);
+                self.last_stick_move = Instant::now();
+            }
+        }
+    }
+
+    /// Start over from a fresh soup. Soups are numbered from the seed setting,
+    /// so the same seed always gives the same sequence of soups. The RNG is
+    /// reseeded for each soup, so whatever happens after it replays exactly
+    /// given the seed, the soup number and the same input.
+    fn reseed(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed.wrapping_add(self.soups_generated));
+        let symmetry = SOUP_SYMMETRIES[self.soup_symmetry_index];
+        match symmetry {
+            SoupSymmetry::Asymmetric => self.notify(format!("soup {} from seed {}", self.soups_generated, self.seed)),
+            _ => self.notify(format!("{} soup {} from seed {}", symmetry.name(), self.soups_generated, self.seed)),
+        }
+
+        self.history.record(self.board.as_ref());
+        let whole_board = Selection::whole_board(self.board.size());
+        board::fill_symmetric(self.board.as_mut(), &whole_board, self.density, symmetry, &mut self.rng);
+        self.soup = Some((self.soups_generated, self.history.changes));
+        self.soups_generated += 1;
+        self.generation = 0;
+        self.generations.clear();
+    }
+
+    /// Advance the board by one generation, remembering the previous one so it
+    /// can be stepped back to. While it's running and being watched for it,
+    /// this also says whether the board has settled into a cycle.
+    fn step(&mut self) -> Option<Cycle> {
+        self.cells_dirty = true;
+        self.observe_recording();
+        let next = self.next_board();
+        let previous = std::mem::replace(&mut self.board, next);
+        self.generation += 1;
+        self.hooks.stepped(&mut self.followers, self.generation, previous.as_ref(), self.board.as_ref());
+        for achievement in std::mem::take(&mut self.followers.unlocked) {
+            self.announce(achievement);
+        }
+
+        if self.generations.len() == GENERATION_HISTORY_LIMIT {
+            self.generations.pop_front();
+        }
+        self.generations.push_back(previous);
+        self.population_curve.record(self.population());
+        self.observe_tutorial(tutorial::Event::Stepped);
+
+        let now = Instant::now();
+        self.recent_steps.push_back(now);
+        while self.recent_steps.front().is_some_and(|&step| now - step > Duration::from_secs(1)) {
+            self.recent_steps.pop_front();
+        }
+
+        // only a run left to itself is watched, so stepping by hand never pauses
+        if (self.auto_pause || self.search.is_some()) && self.mode == Mode::Running {
+            self.cycles.observe(self.generation, self.board.as_ref())
+        } else {
+            None
+        }
+    }
+
+    /// Step a running board, pausing once it settles down, or moving on to
+    /// the next soup in a search.
+    fn run_step(&mut self) {
+        if matches!(&self.followers.game, GameMode::Versus(versus) if versus.placing()) {
+            self.mode = Mode::Editor;
+            self.notify("place every cell before running the match".to_string());
+            return;
+        }
+        if !self.start_puzzle_attempt() || !self.start_attack_run() || !self.start_golf_attempt() {
+            return;
+        }
+        let cycle = self.step();
+
+        if matches!(self.followers.game, GameMode::Puzzle(_)) {
+            self.check_puzzle();
+        } else if matches!(self.followers.game, GameMode::Golf(_)) {
+            self.check_golf();
+        } else if matches!(self.followers.game, GameMode::Quiz(_)) {
+            // a still life or oscillator is still worth looking at
+        } else if matches!(self.followers.game, GameMode::Attack(_)) {
+            // an oscillator's population changes, so it has to run the whole way
+            if self.generation >= TIME_ATTACK_GENERATIONS {
+                self.finish_attack_run();
+            }
+        } else if let GameMode::Versus(versus) = &self.followers.game {
+            // a board that's settled down won't change the score
+            if versus.generation == versus.generations || cycle.is_some() {
+                self.mode = Mode::Editor;
+                self.finish_versus();
+            }
+        } else if self.search.is_some() {
+            if cycle.is_some() || self.generation >= SOUP_MAX_GENERATIONS {
+                self.finish_soup(cycle);
+            }
+        } else if let Some(cycle) = cycle {
+            self.mode = Mode::Editor;
+            let report = match self.record_lifespan(cycle) {
+                Some((lifespan, Some(place))) => format!("soup {} stabilized at gen {}, period {}: #{} this session", lifespan.soup, cycle.start, cycle.period, place + 1),
+                Some((lifespan, None)) => format!("soup {} stabilized at gen {}, period {}", lifespan.soup, cycle.start, cycle.period),
+                None => format!("stabilized at gen {}, period {}", cycle.start, cycle.period),
+            };
+            self.notify(report);
+        }
+    }
+
+    /// Put the soup on the board on the leaderboard, if it's been left alone
+    /// since it was made, along with the place it got there, if any.
+    fn record_lifespan(&mut self, cycle: Cycle) -> Option<(Lifespan, Option<usize>)> {
+        let soup = match self.soup.take() {
+            Some((soup, changes)) if changes == self.history.changes => soup,
+            _ => return None,
+        };
+
+        let lifespan = Lifespan { seed: self.seed, soup, generations: cycle.start, population: self.population() };
+        if lifespan.generations > achievements::METHUSELAH_GENERATIONS {
+            self.unlock("methuselah");
+        }
+        Some((lifespan, self.leaderboard.record(lifespan)))
+    }
+
+    /// Write the session report, with a census of the board as it is now.
+    fn save_report(&self) -> std::io::Result<()> {
+        let report = Report {
+            rule: self.rule_label(),
+            seed: self.seed,
+            generation: self.generation,
+            population: &self.population_curve,
+            census: &census::census(self.board.as_ref()),
+            longest_lived: self.leaderboard.lifespans(),
+        };
+        std::fs::write(&self.report, report.to_json())
+    }
+
+    /// Share the board with any game that joins on `address`, which can be
+    /// just a port.
+    fn host(&mut self, address: &str) -> std::io::Result<()> {
+        let host = Host::bind(address)?;
+        let port = host.port()?;
+        self.network = Some(Network { connection: Connection::Host(host), shared: self.board.blank(self.board.size()), generation: self.generation });
+        self.notify(format!("hosting on port {}: others can join with --join <this address>:{}", port, port));
+        Ok(())
+    }
+
+    /// Join the board hosted at `address`, which takes the place of this one
+    /// as soon as the host sends it.
+    fn join(&mut self, address: &str) -> std::io::Result<()> {
+        let peer = Peer::connect(address)?;
+        self.network = Some(Network { connection: Connection::Joined(peer), shared: self.board.box_clone(), generation: self.generation });
+        self.mode = Mode::Editor;
+        self.notify(format!("joined {}: paint away, the host runs the board", address));
+        Ok(())
+    }
+
+    /// Watch the board broadcast from `address`, which takes the place of this
+    /// one as soon as it arrives.
+    fn watch(&mut self, address: &str) -> std::io::Result<()> {
+        let watcher = Watcher::connect(address)?;
+        self.network = Some(Network { connection: Connection::Watching(watcher), shared: self.board.box_clone(), generation: self.generation });
+        self.mode = Mode::Editor;
+        self.notify(format!("watching {}: the board is read only", address));
+        Ok(())
+    }
+
+    /// Let other games and browsers watch the board on `address`, which can
+    /// be just a port.
+    fn start_broadcast(&mut self, address: &str) -> std::io::Result<()> {
+        let broadcast = Broadcast::bind(address)?;
+        let port = broadcast.port()?;
+        self.broadcast = Some(broadcast);
+        self.notify(format!("broadcasting on port {}: watch with --watch <this address>:{} or in a browser", port, port));
+        Ok(())
+    }
+
+    /// Show everyone watching the broadcast how the board's changed.
+    fn sync_broadcast(&mut self) {
+        let (arrived, left) = match &mut self.broadcast {
+            Some(broadcast) => broadcast.send(self.board.as_ref(), self.generation),
+            None => return,
+        };
+        if arrived > 0 || left > 0 {
+            let watching = self.broadcast.as_ref().map_or(0, Broadcast::spectator_count);
+            self.notify(format!("{} arrived, {} left: {} watching the broadcast", arrived, left, watching));
+        }
+    }
+
+    /// Trade changes with the other games sharing the board. The host takes
+    /// in everyone's edits and sends out whatever's changed since it last
+    /// did, and a game that's joined sends its edits and takes in the host's
+    /// changes. A game watching a broadcast only takes them in, putting back
+    /// anything painted over.
+    fn sync_network(&mut self) {
+        let network = match &mut self.network {
+            Some(network) => network,
+            None => return,
+        };
+
+        let mut notices = Vec::new();
+        let mut received = Vec::new();
+        match &mut network.connection {
+            Connection::Host(host) => {
+                let joined = host.accept();
+                let (messages, left) = host.receive();
+                for message in messages {
+                    if let Message::Edits { born, died } = message {
+                        apply_changes(self.board.as_mut(), &born, &died);
+                        self.cells_dirty = true;
+                    }
+                }
+
+                if network.shared.size() != self.board.size() {
+                    host.broadcast(&Message::whole_board(self.board.as_ref(), self.generation));
+                } else {
+                    let (born, died) = board::diff(network.shared.as_ref(), self.board.as_ref());
+                    if !born.is_empty() || !died.is_empty() || network.generation != self.generation {
+                        host.broadcast(&Message::Changes { generation: self.generation, born, died });
+                    }
+                }
+                let peers = host.peers();
+                let everyone = peers.len();
+                for peer in &mut peers[everyone - joined..] {
+                    peer.send(&Message::whole_board(self.board.as_ref(), self.generation));
+                }
+
+                if joined > 0 || left > 0 {
+                    notices.push(format!("{} joined, {} left: sharing the board with {}", joined, left, everyone));
+                }
+            }
+            Connection::Joined(peer) => {
+                let (born, died) = board::diff(network.shared.as_ref(), self.board.as_ref());
+                if !born.is_empty() || !died.is_empty() {
+                    peer.send(&Message::Edits { born, died });
+                }
+
+                received = peer.receive();
+                if peer.is_closed() {
+                    notices.push("lost the connection to the host".to_string());
+                }
+            }
+            Connection::Watching(watcher) => {
+                let (born, died) = board::diff(network.shared.as_ref(), self.board.as_ref());
+                if !born.is_empty() || !died.is_empty() {
+                    self.board = network.shared.box_clone();
+                    self.cells_dirty = true;
+                }
+
+                received = watcher.receive();
+                if watcher.is_closed() {
+                    notices.push("the broadcast has ended".to_string());
+                }
+            }
+        }
+
+        for message in received {
+            match message {
+                Message::Board { size, generation, cells } => {
+                    self.resize_board(size);
+                    let mut board = self.board.blank(size);
+                    apply_changes(board.as_mut(), &cells, &[]);
+                    self.board = board;
+                    self.generation = generation;
+                }
+                Message::Changes { generation, born, died } => {
+                    apply_changes(self.board.as_mut(), &born, &died);
+                    self.generation = generation;
+                }
+                Message::Edits { .. } => {}
+            }
+            self.cells_dirty = true;
+        }
+        if let Some(network) = &mut self.network {
+            network.shared = self.board.box_clone();
+            network.generation = self.generation;
+            let closed = match &network.connection {
+                Connection::Host(_) => false,
+                Connection::Joined(peer) => peer.is_closed(),
+                Connection::Watching(watcher) => watcher.is_closed(),
+            };
+            if closed {
+                self.network = None;
+            }
+        }
+        for notice in notices {
+            self.notify(notice);
+        }
+    }
+
+    /// Start recording the session from the board as it is now, or stop and
+    /// save what's been recorded.
+    fn toggle_recording(&mut self) {
+        if let Some(recording) = self.followers.recording.take() {
+            self.save_recording(recording);
+        } else if self.playback.is_some() {
+            self.notify("can't record while a replay is playing".to_string());
+        } else {
+            self.start_recording();
+            self.notify(format!("recording the session, to save to {} when it stops", self.replay_file.display()));
+        }
+    }
+
+    fn start_recording(&mut self) {
+        let recorder = Recorder::new(self.board.as_ref(), self.generation, self.rule_label(), self.updates_per_second, self.seed);
+        self.followers.recording = Some(Recording { recorder, started: Instant::now() });
+    }
+
+    fn save_recording(&mut self, recording: Recording) {
+        let replay = recording.recorder.finish();
+        match replay.save(&self.replay_file) {
+            Ok(()) => self.notify(format!("saved {:.0}s of the session to {}", replay.duration().as_secs_f32(), self.replay_file.display())),
+            Err(err) => self.notify(format!("couldn't write {}: {}", self.replay_file.display(), err)),
+        }
+    }
+
+    /// Show the session being recorded, if it is, whatever's changed since
+    /// it was last shown.
+    fn observe_recording(&mut self) {
+        if self.followers.recording.is_none() {
+            return;
+        }
+        let rule = self.rule_label();
+        if let Some(recording) = &mut self.followers.recording {
+            recording.recorder.observe(recording.started.elapsed(), self.board.as_ref(), self.generation, &rule, self.updates_per_second);
+        }
+    }
+
+    /// Play back the session recorded in the replay file from the start, or
+    /// stop the one playing.
+    fn toggle_playback(&mut self) {
+        if self.playback.take().is_some() {
+            self.notify("stopped the replay".to_string());
+        } else if self.followers.recording.is_some() {
+            self.notify("stop recording before playing a replay".to_string());
+        } else if self.is_sketching() {
+            self.notify("go back to the board before playing a replay".to_string());
+        } else {
+            let path = self.replay_file.clone();
+            self.play_replay(&path);
+        }
+    }
+
+    /// Play back the session recorded in `path`, with the seed it had.
+    fn play_replay(&mut self, path: &Path) {
+        let replay = match Replay::load(path) {
+            Ok(replay) => replay,
+            Err(err) => return self.notify(err),
+        };
+        self.seed = replay.seed;
+        self.rng = StdRng::seed_from_u64(replay.seed);
+        self.mode = Mode::Editor;
+        self.notify(format!("playing back {}, {:.0}s long", path.display(), replay.duration().as_secs_f32()));
+        self.playback = Some(Playback { player: Player::new(replay), started: Instant::now(), board: self.board.box_clone() });
+    }
+
+    /// Make whatever changes the replay being played back has come to,
+    /// putting back anything painted over it first.
+    fn advance_playback(&mut self) {
+        let playback = match &mut self.playback {
+            Some(playback) => playback,
+            None => return,
+        };
+        if playback.board.size() != self.board.size() || board::diff(playback.board.as_ref(), self.board.as_ref()) != (Vec::new(), Vec::new()) {
+            self.board = playback.board.box_clone();
+            self.cells_dirty = true;
+        }
+
+        let events = playback.player.advance(playback.started.elapsed());
+        let finished = playback.player.is_finished();
+        for event in events {
+            match event {
+                Event::Board { size, cells } => {
+                    self.resize_board(size);
+                    let mut board = self.board.blank(size);
+                    apply_changes(board.as_mut(), &cells, &[]);
+                    self.board = board;
+                    // the generations before aren't this board's
+                    self.generations.clear();
+                    self.scrub = None;
+                }
+                Event::Edits { born, died } => apply_changes(self.board.as_mut(), &born, &died),
+                Event::Steps(count) => {
+                    for _ in 0..count {
+                        self.step();
+                    }
+                }
+                Event::Rule(name) => {
+                    if let Some(index) = self.plugins.find(&name) {
+                        self.plugin_index = Some(index);
+                    } else if let Some(rule) = Rule::from_name_or_rulestring(&name) {
+                        self.rule = rule;
+                        self.plugin_index = None;
+                    } else {
+                        self.notify(format!("the replay runs {}, which isn't loaded", name));
+                    }
+                }
+                Event::Speed(speed) => self.updates_per_second = speed,
+                Event::Generation(generation) => {
+                    self.generation = generation;
+                    self.generations.clear();
+                    self.scrub = None;
+                }
+            }
+            self.cells_dirty = true;
+        }
+
+        if finished {
+            self.playback = None;
+            self.notify("the replay has finished".to_string());
+        } else if let Some(playback) = &mut self.playback {
+            playback.board = self.board.box_clone();
+        }
+    }
+
+    /// Whether this game has joined or is watching a board someone else runs.
+    fn is_joined(&self) -> bool {
+        matches!(self.network, Some(Network { connection: Connection::Joined(_) | Connection::Watching(_), .. }))
+    }
+
+    /// Copy the board and its rule as a share code to the clipboard, or show
+    /// it if the clipboard can't be reached.
+    fn copy_share_code(&mut self) {
+        let code = Setup::capture(self.board.as_ref(), self.rule).to_code();
+        match clipboard::copy(&code) {
+            Ok(()) => self.notify(format!("copied a share code, {} characters long", code.len())),
+            Err(err) => self.notify(format!("couldn't reach the clipboard ({}), so here's the share code:\n{}", err, code)),
+        }
+    }
+
+    /// Replace the board and the rule with the ones in the share code on the clipboard.
+    fn paste_share_code(&mut self) {
+        let setup = match clipboard::paste().map_err(|err| format!("couldn't read the clipboard: {}", err)).and_then(|text| Setup::from_code(&text)) {
+            Ok(setup) => setup,
+            Err(message) => {
+                self.notify(format!("couldn't paste: {}", message));
+                return;
+            }
+        };
+
+        self.resize_board(setup.size);
+        self.history.record(self.board.as_ref());
+        let mut board = self.board.blank(setup.size);
+        setup.stamp(board.as_mut());
+        self.board = board;
+        self.rule = setup.rule;
+        self.plugin_index = None;
+        self.generation = 0;
+        self.generations.clear();
+        self.cells_dirty = true;
+        self.notify(format!("pasted a {}x{} board of {} cells under {}", setup.size.0, setup.size.1, setup.pattern.cells.len(), setup.rule));
+    }
+
+    /// Clear the board, the rule and the generation count for one of the game
+    /// modes with rules of its own, ending whichever was going on.
+    fn start_game_mode(&mut self) {
+        self.history.record(self.board.as_ref());
+        let whole_board = Selection::whole_board(self.board.size());
+        board::set_region(self.board.as_mut(), &whole_board, true);
+        self.rule = Rule::default();
+        self.plugin_index = None;
+        self.mode = Mode::Editor;
+        self.generation = 0;
+        self.generations.clear();
+        self.placement = None;
+        self.select_mode = false;
+        self.followers.game = GameMode::Free;
+        self.frozen.clear();
+        self.walls.clear();
+        self.wall_paint = None;
+    }
+
+    /// Whether a puzzle, golf hole, time attack, versus match or quiz is
+    /// being played, where anything that bends the rule would skew the score.
+    fn in_game_mode(&self) -> bool {
+        !matches!(self.followers.game, GameMode::Free | GameMode::Tutorial(_))
+    }
+
+    /// Clear the board and the rule for the tutorial, and start it from the
+    /// first step.
+    fn start_tutorial(&mut self) {
+        self.start_game_mode();
+        // so the first pattern picked up is the glider the tutorial asks for
+        self.library_index = 0;
+        self.followers.game = GameMode::Tutorial(Tutorial::default());
+        self.notify("tutorial: follow the prompts along the bottom, or press Escape and pick another mode to stop".to_string());
+    }
+
+    /// Let the tutorial know about something the player did, finishing it
+    /// once its last step is done.
+    fn observe_tutorial(&mut self, event: tutorial::Event) {
+        let tutorial = match &mut self.followers.game {
+            GameMode::Tutorial(tutorial) => tutorial,
+            _ => return,
+        };
+        if tutorial.observe(event) && tutorial.finished() {
+            self.followers.game = GameMode::Free;
+            self.notify("tutorial done: that's the basics, the rest is in the help".to_string());
+            self.unlock("graduate");
+        }
+    }
+
+    /// Unlock the achievement `id`, if it isn't already.
+    fn unlock(&mut self, id: &str) {
+        if let Some(achievement) = self.followers.achievements.unlock(id) {
+            self.announce(achievement);
+        }
+    }
+
+    /// Put up a newly unlocked achievement and save it straight away.
+    fn announce(&mut self, achievement: &'static Achievement) {
+        self.toasts.push_back((achievement, Instant::now()));
+        self.save_achievements();
+    }
+
+    fn save_achievements(&self) {
+        if let Some(path) = &self.achievements_file {
+            if let Err(err) = self.followers.achievements.save(path) {
+                eprintln!("couldn't save achievements to {}: {}", path.display(), err);
+            }
+        }
+    }
+
+    /// Start typing a label for the cell under the cursor, from the one it has.
+    fn start_label(&mut self) {
+        match self.cursor {
+            Some(pos) if self.mode.shows_board() => {
+                let text = self.labels.get(pos).unwrap_or_default().to_string();
+                self.entry = Some(Entry::Label(pos, text));
+                self.notify("type the label, then press Return to pin it, or Escape to leave it; a blank label comes off".to_string());
+            }
+            _ => self.notify("point at a cell to label it".to_string()),
+        }
+    }
+
+    /// Pin `text` to the cell at `pos`, or take the cell's label off if it's blank.
+    fn finish_label(&mut self, pos: GridPosition, text: &str) {
+        let had_label = self.labels.get(pos).is_some();
+        self.labels.set(pos, text);
+        match self.labels.get(pos) {
+            Some(label) => self.notify(format!("labelled ({}, {}) \"{}\"", pos.x, pos.y, label)),
+            None if had_label => self.notify(format!("took the label off ({}, {})", pos.x, pos.y)),
+            None => {}
+        }
+    }
+
+    /// Do whatever what's been typed in was for.
+    fn finish_entry(&mut self) {
+        match self.entry.take() {
+            Some(Entry::Label(pos, text)) => self.finish_label(pos, &text),
+            Some(Entry::Generation(text)) => match text.trim().parse() {
+                Ok(generation) => self.warp_to(generation),
+                Err(_) if text.trim().is_empty() => {}
+                Err(_) => self.notify(format!("\"{}\" isn't a generation", text.trim())),
+            },
+            None => {}
+        }
+    }
+
+    /// Start typing in a generation to warp to, or stop the warp that's going.
+    fn toggle_warp(&mut self) {
+        if self.warp.is_some() {
+            self.cancel_warp();
+        } else if self.in_game_mode() || self.search.is_some() {
+            // a warp steps past the checks that end a turn or a soup
+            self.notify("there's no warping while playing or searching soups".to_string());
+        } else {
+            self.entry = Some(Entry::Generation(String::new()));
+            self.notify("type the generation to warp to, then press Return, or Escape to stay put".to_string());
+        }
+    }
+
+    /// Run to `generation` as fast as the board steps, a frame's worth at a time.
+    fn warp_to(&mut self, generation: u64) {
+        if generation <= self.generation {
+            self.notify(format!("generation {} has been and gone: step back or drag the timeline to go back", generation));
+            return;
+        }
+        self.warp = Some(Warp { from: self.generation, to: generation, started: Instant::now() });
+    }
+
+    /// Step towards the warp's generation for a frame's worth of time,
+    /// finishing the warp once it's there.
+    fn advance_warp(&mut self) {
+        let (to, started) = match &self.warp {
+            Some(warp) => (warp.to, warp.started),
+            None => return,
+        };
+        let deadline = Instant::now() + WARP_FRAME_TIME;
+        while self.generation < to && Instant::now() < deadline {
+            self.step();
+        }
+        if self.generation >= to {
+            self.warp = None;
+            self.last_update = Instant::now();
+            self.notify(format!("warped to generation {} in {:.1}s", to, started.elapsed().as_secs_f32()));
+        }
+    }
+
+    fn cancel_warp(&mut self) {
+        if self.warp.take().is_some() {
+            self.last_update = Instant::now();
+            self.notify(format!("stopped warping at generation {}", self.generation));
+        }
+    }
+
+    /// The live part of the board in RLE, with the labels on it in comment lines.
+    fn board_rle(&self) -> String {
+        let origin = self.board.bounds().map_or(GridPosition::new(0, 0), |bounds| bounds.bounds().0);
+        format!("{}{}", self.labels.to_comments(origin), Pattern::capture_live(self.board.as_ref()).to_rle(self.rule))
+    }
+
+    fn save_board(&mut self) {
+        match std::fs::write(&self.output, self.board_rle()) {
+            Ok(()) => self.notify(format!("saved the board to {}", self.output.display())),
+            Err(err) => self.notify(format!("couldn't write {}: {}", self.output.display(), err)),
+        }
+    }
+
+    /// Put the pattern opened from `path` in the middle of the board, with
+    /// the labels saved in the file.
+    fn open_pattern(&mut self, path: &Path, pattern: &Pattern) {
+        let (width, height) = self.board.size();
+        let origin = pattern.origin_centered_on(GridPosition::new(width / 2, height / 2));
+        pattern.stamp(self.board.as_mut(), origin);
+        let text = std::fs::read_to_string(path).unwrap_or_default();
+        self.labels = Labels::from_comments(&text, origin);
+        self.labels.shift((0, 0), (width, height));
+    }
+
+    /// Sum up the achievements in a notice, listing every one, unlocked or not.
+    fn show_achievements(&mut self) {
+        let list: String = achievements::ACHIEVEMENTS
+            .iter()
+            .map(|achievement| {
+                let mark = if self.followers.achievements.is_unlocked(achievement.id) { "x" } else { " " };
+                format!("\n[{}] {}: {}", mark, achievement.name, achievement.description)
+            })
+            .collect();
+        let unlocked: Vec<&str> = self.followers.achievements.unlocked().map(|achievement| achievement.name).collect();
+        let report = match unlocked.len() {
+            0 => format!("no achievements yet, {} to go", achievements::ACHIEVEMENTS.len()),
+            count => format!("{} of {} achievements: {}", count, achievements::ACHIEVEMENTS.len(), unlocked.join(", ")),
+        };
+        self.notify(format!("{}{}", report, list));
+    }
+
+    /// Clear the board for a new versus match, or end the one going on.
+    fn toggle_versus(&mut self) {
+        if matches!(self.followers.game, GameMode::Versus(_)) {
+            self.followers.game = GameMode::Free;
+            self.notify("ended the match".to_string());
+            return;
+        }
+        // Immigration plays out like Conway's Life
+        self.start_game_mode();
+        self.followers.game = GameMode::Versus(Match::new(VERSUS_CELLS, VERSUS_GENERATIONS));
+        self.notify(format!("versus: take turns placing {} cells each, {} first", VERSUS_CELLS, VERSUS_NAMES[0]));
+    }
+
+    /// Clear the board for the puzzle after the one being played, or the first.
+    fn next_puzzle(&mut self) {
+        let index = match &self.followers.game {
+            GameMode::Puzzle(run) => (run.index + 1) % self.puzzles.len(),
+            _ => 0,
+        };
+        let puzzle = &self.puzzles[index];
+        let report = format!(
+            "puzzle {} of {}, {}: make the target from at most {} cells within {} generations",
+            index + 1,
+            self.puzzles.len(),
+            puzzle.name,
+            puzzle.cells,
+            puzzle.generations
+        );
+
+        self.start_game_mode();
+        self.followers.game = GameMode::Puzzle(PuzzleRun { index, start: None, solved: false });
+        self.notify(report);
+    }
+
+    /// Before the first step of an attempt at the puzzle, check the board
+    /// doesn't use too many cells and remember it to go back to. False, and
+    /// paused, if it uses too many.
+    fn start_puzzle_attempt(&mut self) -> bool {
+        let run = match &mut self.followers.game {
+            GameMode::Puzzle(run) if run.start.is_none() => run,
+            _ => return true,
+        };
+
+        let cells = self.puzzles[run.index].cells;
+        if self.board.population() > cells {
+            self.mode = Mode::Editor;
+            self.notify(format!("this puzzle allows only {} cells", cells));
+            return false;
+        }
+        run.start = Some(self.board.box_clone());
+        self.generation = 0;
+        self.generations.clear();
+        true
+    }
+
+    /// End the attempt at the puzzle if the target's been reached or it's run
+    /// out of generations, putting the board back as it started on a miss.
+    fn check_puzzle(&mut self) {
+        let run = match &mut self.followers.game {
+            GameMode::Puzzle(run) => run,
+            _ => return,
+        };
+        let puzzle = &self.puzzles[run.index];
+
+        if puzzle.is_solved_by(self.board.as_ref()) {
+            let report = format!("solved {} in {} generations! press F6 for the next puzzle", puzzle.name, self.generation);
+            run.start = None;
+            run.solved = true;
+            self.mode = Mode::Editor;
+            self.notify(report);
+            self.unlock("puzzler");
+        } else if self.generation >= puzzle.generations {
+            if let Some(start) = run.start.take() {
+                self.board = start;
+            }
+            self.generation = 0;
+            self.generations.clear();
+            self.mode = Mode::Editor;
+            self.notify("not there yet: the board is back how it started, try again".to_string());
+        }
+    }
+
+    /// Clear the board for the golf hole after the one being played, or the first.
+    fn next_hole(&mut self) {
+        let index = match &self.followers.game {
+            GameMode::Golf(run) => (run.hole + 1) % golf::HOLES.len(),
+            _ => 0,
+        };
+        let hole = &golf::HOLES[index];
+        let best = match self.scorecard.best(index) {
+            Some(best) => format!(", best {}", best),
+            None => String::new(),
+        };
+        let report = format!("hole {} of {}, {}: {} from as few cells as you can (par {}{})", index + 1, golf::HOLES.len(), hole.name, hole.description(), hole.par, best);
+
+        self.start_game_mode();
+        self.followers.game = GameMode::Golf(GolfRun { hole: index, start: None });
+        self.notify(report);
+    }
+
+    /// Before the first step of an attempt at the hole, remember the board to
+    /// go back to. False, and paused, if there's nothing on it.
+    fn start_golf_attempt(&mut self) -> bool {
+        let run = match &mut self.followers.game {
+            GameMode::Golf(run) if run.start.is_none() => run,
+            _ => return true,
+        };
+
+        if self.board.population() == 0 {
+            self.mode = Mode::Editor;
+            self.notify("draw some cells to tee off with".to_string());
+            return false;
+        }
+        run.start = Some(self.board.box_clone());
+        self.generation = 0;
+        self.generations.clear();
+        true
+    }
+
+    /// End the attempt at the hole once its goal is reached or it's run out
+    /// of generations, scoring it by the cells it started from on a make and
+    /// putting the board back as it started on a miss.
+    fn check_golf(&mut self) {
+        let run = match &mut self.followers.game {
+            GameMode::Golf(run) => run,
+            _ => return,
+        };
+        let hole = &golf::HOLES[run.hole];
+
+        if hole.is_met(self.board.as_ref(), self.generation) {
+            let cells = run.start.take().map_or(0, |start| start.population());
+            let best = if self.scorecard.record(run.hole, cells) { ", a new best" } else { "" };
+            let report = format!("made {} in {} generations with {} cells, {}{}! press F9 for the next hole", hole.name, self.generation, cells, golf::versus_par(cells, hole.par), best);
+            self.mode = Mode::Editor;
+            if let Some(path) = &self.scorecard_file {
+                if let Err(err) = self.scorecard.save(path) {
+                    eprintln!("couldn't save the golf scorecard to {}: {}", path.display(), err);
+                }
+            }
+            self.notify(report);
+        } else if self.generation >= hole.generations {
+            if let Some(start) = run.start.take() {
+                self.board = start;
+            }
+            self.generation = 0;
+            self.generations.clear();
+            self.mode = Mode::Editor;
+            self.notify("missed: the board is back how it started, try again".to_string());
+        }
+    }
+
+    /// Start a quiz on the library's patterns, or end the one going on.
+    fn toggle_quiz(&mut self) {
+        if let GameMode::Quiz(quiz) = &self.followers.game {
+            let report = format!("quiz over: {} of {} right", quiz.right, quiz.asked);
+            self.followers.game = GameMode::Free;
+            self.notify(report);
+            return;
+        }
+
+        self.start_game_mode();
+        self.followers.game = GameMode::Quiz(Quiz::new(&mut self.rng, QUIZ_CHOICES));
+        self.show_question();
+        self.notify(format!("quiz: what's running on the board? answer with 1 to {}", QUIZ_CHOICES));
+    }
+
+    /// Run the pattern the quiz is asking about, turned a random way, on an
+    /// otherwise empty board.
+    fn show_question(&mut self) {
+        let quiz = match &self.followers.game {
+            GameMode::Quiz(quiz) => quiz,
+            _ => return,
+        };
+
+        let mut pattern = Pattern::from_plaintext(library::PATTERNS[quiz.question.pattern].1);
+        for _ in 0..self.rng.gen_range(0, 4) {
+            pattern = pattern.rotate_clockwise();
+        }
+        if self.rng.gen() {
+            pattern = pattern.flip_horizontal();
+        }
+
+        let whole_board = Selection::whole_board(self.board.size());
+        board::set_region(self.board.as_mut(), &whole_board, true);
+        self.place_centered(&pattern);
+        self.generation = 0;
+        self.generations.clear();
+        self.mode = Mode::Running;
+    }
+
+    /// Answer the quiz with the name at `index`, and go on to the next pattern.
+    fn answer_quiz(&mut self, index: usize) {
+        let quiz = match &mut self.followers.game {
+            GameMode::Quiz(quiz) => quiz,
+            _ => return,
+        };
+        let (question, right) = match quiz.answer(&mut self.rng, index) {
+            Some(answer) => answer,
+            None => return,
+        };
+
+        let score = format!("{} of {} right", quiz.right, quiz.asked);
+        let report = if right {
+            format!("right, that was a {}! {}", question.answer(), score)
+        } else {
+            format!("no, that was a {}. {}", question.answer(), score)
+        };
+        self.show_question();
+        self.notify(report);
+    }
+
+    /// Clear the board for a time attack, or end the one going on.
+    fn toggle_time_attack(&mut self) {
+        if matches!(self.followers.game, GameMode::Attack(_)) {
+            self.followers.game = GameMode::Free;
+            self.notify("ended the time attack".to_string());
+            return;
+        }
+
+        self.start_game_mode();
+        self.followers.game = GameMode::Attack(AttackRun { started: Instant::now(), start: None });
+        self.notify(format!(
+            "time attack: place at most {} cells in {} seconds, then score the population after {} generations",
+            TIME_ATTACK_CELLS, TIME_ATTACK_SECONDS, TIME_ATTACK_GENERATIONS
+        ));
+    }
+
+    /// How long is left of the time attack's clock for placing cells, while
+    /// it's running down.
+    fn attack_time_left(&self) -> Option<Duration> {
+        match &self.followers.game {
+            GameMode::Attack(run) if run.start.is_none() => Some(Duration::from_secs(TIME_ATTACK_SECONDS).saturating_sub(run.started.elapsed())),
+            _ => None,
+        }
+    }
+
+    /// Before the first step of a time attack run, check the board doesn't
+    /// use too many cells and remember it to go back to. False, paused and
+    /// with the clock started over, if it uses too many.
+    fn start_attack_run(&mut self) -> bool {
+        let run = match &mut self.followers.game {
+            GameMode::Attack(run) if run.start.is_none() => run,
+            _ => return true,
+        };
+
+        if self.board.population() > TIME_ATTACK_CELLS {
+            run.started = Instant::now();
+            self.mode = Mode::Editor;
+            self.notify(format!("a time attack allows only {} cells, the clock's started over", TIME_ATTACK_CELLS));
+            return false;
+        }
+        run.start = Some(self.board.box_clone());
+        self.generation = 0;
+        self.generations.clear();
+        true
+    }
+
+    /// Score a time attack run that's gone every generation, and put the board
+    /// back as it started for another go.
+    fn finish_attack_run(&mut self) {
+        let run = match &mut self.followers.game {
+            GameMode::Attack(run) => run,
+            _ => return,
+        };
+        let start = match run.start.take() {
+            Some(start) => start,
+            None => return,
+        };
+        run.started = Instant::now();
+
+        let score = Score { population: self.population(), cells: start.population() };
+        self.board = start;
+        self.generation = 0;
+        self.generations.clear();
+        self.mode = Mode::Editor;
+
+        // nothing left alive isn't worth a place, even on an empty table
+        let place = if score.population > 0 { self.high_scores.record(score) } else { None };
+        if let (Some(path), Some(_)) = (&self.high_scores_file, place) {
+            if let Err(err) = self.high_scores.save(path) {
+                eprintln!("couldn't save the high scores to {}: {}", path.display(), err);
+            }
+        }
+        let report = match place {
+            Some(place) => format!("scored {}: #{} on the high scores! the clock's running again", score, place + 1),
+            None => format!("scored {}, not enough for the high scores. the clock's running again", score),
+        };
+        self.notify(report);
+    }
+
+    /// Place a cell at `pos` for whoever's turn it is in the match, and start
+    /// it running once every cell is placed.
+    fn place_versus_cell(&mut self, pos: GridPosition) {
+        let versus = match &mut self.followers.game {
+            GameMode::Versus(versus) if versus.placing() => versus,
+            _ => return,
+        };
+        if !versus.place(self.board.as_mut(), pos) {
+            return;
+        }
+
+        if !versus.placing() {
+            let generations = versus.generations;
+            self.mode = Mode::Running;
+            self.notify(format!("every cell is placed, running for {} generations", generations));
+        }
+    }
+
+    /// Announce who owns more of the board.
+    fn finish_versus(&mut self) {
+        let versus = match &self.followers.game {
+            GameMode::Versus(versus) => versus,
+            _ => return,
+        };
+
+        let territory = versus.territory();
+        let report = match versus.leader() {
+            Some(player) => {
+                let (winner, loser) = (player.index(), player.other().index());
+                format!("{} wins with {} cells to {} after {} generations", VERSUS_NAMES[winner], territory[winner], territory[loser], versus.generation)
+            }
+            None => format!("a draw at {} cells each after {} generations", territory[0], versus.generation),
+        };
+        self.notify(report);
+        self.unlock("rivals");
+    }
+
+    /// Start tracking births and deaths by region, or stop and sum up what was
+    /// tracked.
+    fn toggle_activity(&mut self) {
+        let activity = match self.followers.activity.take() {
+            Some(activity) => activity,
+            None => {
+                self.followers.activity = Some(Activity::new(self.board.size(), ACTIVITY_REGION_SIZE, ACTIVITY_WINDOW));
+                self.notify(format!("tracking births and deaths in {0}x{0} regions", ACTIVITY_REGION_SIZE));
+                return;
+            }
+        };
+
+        let (columns, rows) = activity.regions();
+        let regions = (0..rows).flat_map(|row| (0..columns).map(move |column| (column, row)));
+        let (births, deaths) = regions.clone().map(|(column, row)| activity.region(column, row)).fold((0, 0), |(births, deaths), (b, d)| (births + b, deaths + d));
+        let busiest = regions.max_by_key(|&(column, row)| {
+            let (births, deaths) = activity.region(column, row);
+            births + deaths
+        });
+        let size = activity.region_size();
+        let mut report = format!("stopped tracking activity: {} births and {} deaths over the last {} generations", births, deaths, activity.generations());
+        if let (Some((column, row)), true) = (busiest, births + deaths > 0) {
+            report.push_str(&format!(", busiest in the {0}x{0} region at ({1}, {2})", size, column as i16 * size, row as i16 * size));
+        }
+        self.notify(report);
+    }
+
+    /// List every soup on the leaderboard in a notice, longest lived first.
+    fn show_leaderboard(&mut self) {
+        let lifespans = self.leaderboard.lifespans();
+        if lifespans.is_empty() {
+            self.notify("no soups have settled yet this session".to_string());
+            return;
+        }
+
+        let mut report = String::from("longest lived soups:");
+        for (place, lifespan) in lifespans.iter().enumerate() {
+            report.push_str(&format!("\n{:>3}. {}", place + 1, lifespan));
+        }
+        self.notify(report);
+    }
+
+    /// Start a soup search from the next soup, or stop the one going on.
+    fn toggle_soup_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            self.notify(format!("searched {} soups, {} with rare finds, results in {}", search.soups, search.rare_finds, search.results.display()));
+            return;
+        }
+
+        match SoupSearch::start(self.results.clone()) {
+            Ok(search) => {
+                self.search = Some(search);
+                self.followers.game = GameMode::Free;
+                self.turbo = true;
+                self.mode = Mode::Running;
+                self.reseed();
+            }
+            Err(err) => self.notify(format!("couldn't start a search in {}: {}", self.results.display(), err)),
+        }
+    }
+
+    /// Record the soup that just settled, or ran too long without settling,
+    /// point out anything rare in it and start the next one.
+    fn finish_soup(&mut self, cycle: Option<Cycle>) {
+        let result = SoupResult {
+            seed: self.seed,
+            soup: self.soups_generated - 1,
+            settled_at: cycle.map(|cycle| cycle.start),
+            census: census::census(self.board.as_ref()),
+        };
+
+        // a rare find is more worth hearing about, so it's shown over this
+        if let Some((lifespan, Some(0))) = cycle.and_then(|cycle| self.record_lifespan(cycle)) {
+            self.notify(format!("longest lived yet: {}", lifespan));
+        }
+
+        let search = match self.search.as_mut() {
+            Some(search) => search,
+            None => return,
+        };
+        if let Err(err) = search.record(&result) {
+            self.search = None;
+            self.notify(format!("stopped searching, couldn't write {}: {}", self.results.display(), err));
+            return;
+        }
+
+        let rare = result.rare_objects();
+        if !rare.is_empty() {
+            search.rare_finds += 1;
+            self.notify(format!("rare find in soup {}: {}", result.soup, census::summary(&rare, CENSUS_NOTICE_KINDS)));
+        }
+        self.reseed();
+    }
+
+    /// Write the population, density and entropy of every generation from now
+    /// on to a CSV file at `path`, replacing whatever was there.
+    fn log_stats(&mut self, path: &std::path::Path) -> std::io::Result<()> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(file, "generation,population,density,entropy")?;
+
+        let path = path.to_path_buf();
+        let mut failed = false;
+        self.hooks.on_generation(move |_, generation, board| {
+            let line = format!("{},{},{:.6},{:.6}", generation, board.population(), stats::density(board), stats::block_entropy(board));
+            // one complaint is enough if the disk fills up
+            if let Err(err) = writeln!(file, "{}", line).and_then(|_| file.flush()) {
+                if !failed {
+                    eprintln!("couldn't write stats to {}: {}", path.display(), err);
+                    failed = true;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Show `message` at the top of the screen for a few seconds.
+    fn notify(&mut self, message: String) {
+        self.notice = Some((message, Instant::now()));
+    }
+
+    /// The generation after the board, with the frozen regions and walls
+    /// held as they are.
+    fn next_board(&self) -> Box<dyn Board> {
+        let mut next = board::step_frozen(self.board.as_ref(), self.transition(), &self.frozen, self.frozen_counted);
+        self.walls.apply(next.as_mut());
+        next
+    }
+
+    /// Go from painting cells to painting live walls, then dead walls, then back.
+    fn cycle_wall_paint(&mut self) {
+        if self.in_game_mode() {
+            self.notify("walls can't be built while playing".to_string());
+            return;
+        }
+        self.wall_paint = match self.wall_paint {
+            None => Some(true),
+            Some(true) => Some(false),
+            Some(false) => None,
+        };
+        self.notify(match self.wall_paint {
+            Some(true) => "painting walls that are always alive; paint over one to knock it down".to_string(),
+            Some(false) => "painting walls that are always dead; paint over one to knock it down".to_string(),
+            None => "painting cells".to_string(),
+        });
+    }
+
+    fn clear_walls(&mut self) {
+        if self.walls.is_empty() {
+            self.notify("there are no walls".to_string());
+        } else {
+            self.notify(format!("knocked down {} wall{}", self.walls.len(), if self.walls.len() == 1 { "" } else { "s" }));
+            self.walls.clear();
+        }
+    }
+
+    /// Freeze the selection, or thaw it if it's already frozen. With
+    /// nothing selected, thaw the frozen region under the cursor.
+    fn toggle_freeze(&mut self) {
+        if self.in_game_mode() {
+            self.notify("nothing can be frozen while playing".to_string());
+        } else if let Some(selection) = self.selection.take() {
+            let (start, end) = selection.bounds();
+            let region = Selection { start, end };
+            let size = format!("{}x{}", end.x - start.x + 1, end.y - start.y + 1);
+            if let Some(index) = self.frozen.iter().position(|frozen| frozen.bounds() == (start, end)) {
+                self.frozen.remove(index);
+                self.notify(format!("thawed the {} region", size));
+            } else {
+                self.frozen.push(region);
+                let counted = if self.frozen_counted { "still counts" } else { "doesn't count" };
+                self.notify(format!("froze a {} region: it won't change, and {} for the cells around it", size, counted));
+            }
+        } else if let Some(index) = self.cursor.and_then(|cursor| self.frozen.iter().rposition(|frozen| frozen.contains(cursor))) {
+            self.frozen.remove(index);
+            self.notify("thawed the region under the cursor".to_string());
+        } else {
+            self.notify("select a region to freeze, or point at a frozen one to thaw it".to_string());
+        }
+        self.cells_dirty = true;
+    }
+
+    fn thaw_all(&mut self) {
+        if self.frozen.is_empty() {
+            self.notify("nothing is frozen".to_string());
+        } else {
+            self.notify(format!("thawed {} frozen region{}", self.frozen.len(), if self.frozen.len() == 1 { "" } else { "s" }));
+            self.frozen.clear();
+        }
+    }
+
+    /// The rule the board steps under.
+    fn transition(&self) -> &dyn Transition {
+        match self.plugin_index.and_then(|index| self.plugins.get(index)) {
+            Some(plugin) => plugin,
+            None => &self.rule,
+        }
+    }
+
+    /// The plugin's name, or the rulestring of a life-like rule.
+    fn rule_label(&self) -> String {
+        match self.plugin_index.and_then(|index| self.plugins.get(index)) {
+            Some(plugin) => plugin.name().to_string(),
+            None => self.rule.to_string(),
+        }
+    }
+
+    /// How far along the wait for the next generation is, from 0 to 1, while
+    /// births and deaths are being animated.
+    fn fade_progress(&self) -> Option<f32> {
+        if !self.animate || self.turbo || self.mode != Mode::Running {
+            return None;
+        }
+
+        let elapsed = (Instant::now() - self.last_update).as_secs_f32();
+        Some((elapsed * self.updates_per_second).min(1.0))
+    }
+
+    /// How long until the next frame should be drawn, which is right away
+    /// without a frame rate.
+    fn until_next_frame(&self) -> Duration {
+        match self.frame_rate {
+            Some(frame_rate) => Duration::from_secs_f32(1.0 / frame_rate).saturating_sub(self.last_draw.elapsed()),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Generations actually computed over the last second.
+    fn actual_updates_per_second(&self) -> usize {
+        let now = Instant::now();
+        self.recent_steps.iter().filter(|&&step| now - step <= Duration::from_secs(1)).count()
+    }
+
+    fn population(&self) -> usize {
+        self.board.population()
+    }
+
+    /// Go back `count` generations, or as far as the history reaches.
+    fn step_back(&mut self, count: usize) {
+        for _ in 0..count {
+            match self.generations.pop_back() {
+                Some(previous) => {
+                    self.board = previous;
+                    self.generation = self.generation.saturating_sub(1);
+                },
+                None => break,
+            }
+        }
+    }
+
+    /// The cell at `pos`, the generations it's been that way, its live
+    /// neighbors and what it'll be next generation.
+    fn inspection(&self, pos: GridPosition) -> String {
+        let neighborhood = board::neighborhood(self.board.as_ref(), pos);
+        let alive = neighborhood.center();
+        let age = board::age(self.board.as_ref(), pos, self.generations.iter().rev().map(Box::as_ref));
+        // all the history there is, so it could be older
+        let more = if age == self.generations.len() { "+" } else { "" };
+        let next = match (alive, self.transition().next_state(neighborhood)) {
+            (true, true) => "survives",
+            (true, false) => "dies",
+            (false, true) => "is born",
+            (false, false) => "stays dead",
+        };
+        format!(
+            "({}, {}) {} for {}{} gens, {} neighbors: {}",
+            pos.x,
+            pos.y,
+            if alive { "alive" } else { "dead" },
+            age,
+            more,
+            neighborhood.live_neighbors(),
+            next,
+        )
+    }
+
+    /// Flip whether the rule has a cell that's `alive` with `neighbors` live
+    /// neighbors alive next generation, leaving any plugin rule for it.
+    fn toggle_rule_count(&mut self, alive: bool, neighbors: u8) {
+        self.rule.toggle(alive, neighbors);
+        self.plugin_index = None;
+        self.cycles.clear();
+        let name = self.rule.name().map_or_else(String::new, |name| format!(" ({})", name));
+        self.notify(format!("rule {}{}", self.rule, name));
+    }
+
+    /// Whether the timeline is showing: on the board, with generations to go back to.
+    fn shows_timeline(&self) -> bool {
+        self.mode.shows_board() && !self.is_sketching() && (self.scrub.is_some() || !self.generations.is_empty())
+    }
+
+    /// Whether a sketch layer is being edited rather than the board that runs.
+    fn is_sketching(&self) -> bool {
+        self.layer > 0
+    }
+
+    /// Put the layer being edited back with the others and edit the one at `index`.
+    fn switch_layer(&mut self, index: usize) {
+        let layer = &mut self.layers[self.layer];
+        std::mem::swap(&mut layer.board, &mut self.board);
+        std::mem::swap(&mut layer.history, &mut self.history);
+        self.layer = index;
+        let layer = &mut self.layers[self.layer];
+        std::mem::swap(&mut layer.board, &mut self.board);
+        std::mem::swap(&mut layer.history, &mut self.history);
+        self.selection = None;
+        self.mouse_down = false;
+        self.cells_dirty = true;
+    }
+
+    fn layer_name(&self) -> String {
+        if self.is_sketching() { format!("sketch layer {}", self.layer) } else { "the board".to_string() }
+    }
+
+    /// Add a blank sketch layer on top and start editing it.
+    fn new_layer(&mut self) {
+        if self.is_joined() || self.playback.is_some() {
+            self.notify("the board can't be sketched on while someone else runs it".to_string());
+            return;
+        }
+        if self.layers.len() == MAX_LAYERS {
+            self.notify(format!("{} layers is as many as there can be: merge one down first", MAX_LAYERS));
+            return;
+        }
+        self.layers.push(Layer::new(self.board.blank(self.board.size())));
+        self.mode = Mode::Editor;
+        self.switch_layer(self.layers.len() - 1);
+        self.notify(format!("editing {}: it won't run until it's merged down onto the board", self.layer_name()));
+    }
+
+    /// Edit the layer above this one, or the board after the top one.
+    fn next_layer(&mut self) {
+        if self.layers.len() == 1 {
+            self.notify("there are no sketch layers yet".to_string());
+            return;
+        }
+        self.switch_layer((self.layer + 1) % self.layers.len());
+        self.notify(format!("editing {}", self.layer_name()));
+    }
+
+    fn toggle_layer_visible(&mut self) {
+        let layer = &mut self.layers[self.layer];
+        layer.visible = !layer.visible;
+        let shown = if layer.visible { "shown" } else { "hidden" };
+        self.notify(format!("{} is {} while other layers are edited", self.layer_name(), shown));
+    }
+
+    /// Copy the live cells of the layer being edited onto the one under it,
+    /// as one edit that can be undone there, and drop the layer.
+    fn merge_layer(&mut self) {
+        if !self.is_sketching() {
+            self.notify("the board is the bottom layer, with nothing under it to merge into".to_string());
+            return;
+        }
+        let name = self.layer_name();
+        let merged = self.layer;
+        self.switch_layer(merged - 1);
+        let sketch = self.layers.remove(merged);
+        self.history.record(self.board.as_ref());
+        let (width, height) = self.board.size();
+        for pos in sketch.board.live_cells().filter(|pos| pos.x < width && pos.y < height) {
+            self.board.set_alive(pos, true);
+        }
+        self.notify(format!("merged {} down onto {}", name, self.layer_name()));
+    }
+
+    /// Pause and start dragging along the timeline, from the generation at `x`.
+    fn start_scrub(&mut self, x: f32) {
+        self.mode = Mode::Editor;
+        let mut frames: Vec<Box<dyn Board>> = self.generations.drain(..).collect();
+        frames.push(self.board.box_clone());
+        let showing = frames.len() - 1;
+        self.scrub = Some(Scrub { frames, showing, generation: self.generation });
+        self.scrub_to(x);
+    }
+
+    /// Show the generation on the timeline under `x`.
+    fn scrub_to(&mut self, x: f32) {
+        let scrub = match &mut self.scrub {
+            Some(scrub) => scrub,
+            None => return,
+        };
+        let showing = Timeline::frame_at(&self.viewport, scrub.frames.len(), x);
+        if showing != scrub.showing {
+            scrub.showing = showing;
+            self.board = scrub.frames[showing].box_clone();
+            self.generation = scrub.generation.saturating_sub((scrub.frames.len() - 1 - showing) as u64);
+            self.cells_dirty = true;
+        }
+    }
+
+    /// Stop dragging along the timeline and carry on from the generation it
+    /// was left on, forgetting the ones after it.
+    fn finish_scrub(&mut self) {
+        if let Some(mut scrub) = self.scrub.take() {
+            scrub.frames.truncate(scrub.showing + 1);
+            self.board = scrub.frames.pop().expect("the showing generation is on the timeline");
+            self.generations = scrub.frames.into();
+            self.cycles.clear();
+            self.cells_dirty = true;
+        }
+    }
+
+    /// Where things along the bottom of the board go, above the status bar
+    /// and the timeline if it's showing.
+    fn hud_bottom(&self, screen: graphics::Rect) -> f32 {
+        let timeline = if self.shows_timeline() { TIMELINE_MARGIN + TIMELINE_HEIGHT } else { 0.0 };
+        screen.y + screen.h - STATUS_BAR_HEIGHT - timeline
+    }
+
+    /// Start stamping the library pattern at `index`, wrapping around the library.
+    fn select_library_pattern(&mut self, index: usize) {
+        self.library_index = index % library::PATTERNS.len();
+        let (name, cells) = library::PATTERNS[self.library_index];
+        self.notify(format!("stamping {}", name));
+
+        self.placement = Some(Placement {
+            pattern: Pattern::from_plaintext(cells),
+            sticky: true,
+            name: Some(name),
+        });
+    }
+
+    /// Apply `transform` to the pattern being placed if there is one, otherwise to
+    /// the contents of the selection, turning them about the selection's center.
+    fn transform_selection(&mut self, transform: fn(&Pattern) -> Pattern) {
+        if let Some(placement) = &mut self.placement {
+            placement.pattern = transform(&placement.pattern);
+        } else if let Some(selection) = self.selection {
+            let (min, _) = selection.bounds();
+            let pattern = Pattern::capture(self.board.as_ref(), &selection);
+            let center = GridPosition::new(min.x + pattern.width / 2, min.y + pattern.height / 2);
+
+            let transformed = transform(&pattern);
+            let origin = transformed.origin_centered_on(center);
+
+            self.history.record(self.board.as_ref());
+            board::set_region(self.board.as_mut(), &selection, true);
+            transformed.stamp(self.board.as_mut(), origin);
+            self.selection = Some(transformed.footprint(origin, self.board.size()));
+        }
+    }
+
+    /// Lines between the cells that are on screen.
+    fn add_grid(mesh: &mut graphics::MeshBuilder, viewport: &Viewport, color: [f32; 4]) -> GameResult<()> {
+        let visible = viewport.visible_grid_rect();
+        let top_left = viewport.cell_rect(GridPosition::new(0, 0));
+        let (right, bottom) = (
+            viewport.snap(viewport.origin.0 + viewport.grid.0 as f32 * viewport.cell_size.0),
+            viewport.snap(viewport.origin.1 + viewport.grid.1 as f32 * viewport.cell_size.1),
+        );
+        for x in visible.x.floor() as i16..=(visible.x + visible.w).ceil() as i16 {
+            let screen_x = viewport.snap(viewport.origin.0 + x as f32 * viewport.cell_size.0);
+            mesh.line(&[ggez::mint::Point2 { x: screen_x, y: top_left.y }, ggez::mint::Point2 { x: screen_x, y: bottom }], 1.0, color.into())?;
+        }
+        for y in visible.y.floor() as i16..=(visible.y + visible.h).ceil() as i16 {
+            let screen_y = viewport.snap(viewport.origin.1 + y as f32 * viewport.cell_size.1);
+            mesh.line(&[ggez::mint::Point2 { x: top_left.x, y: screen_y }, ggez::mint::Point2 { x: right, y: screen_y }], 1.0, color.into())?;
+        }
+        Ok(())
+    }
+
+    fn add_selection(mesh: &mut graphics::MeshBuilder, viewport: &Viewport, palette: &Palette, selection: &Selection) {
+        let rect = viewport.selection_rect(selection);
+        mesh.rectangle(graphics::DrawMode::fill(), rect, Palette::translucent(palette.selection, 0.25))
+            .rectangle(graphics::DrawMode::stroke(1.0), rect, palette.selection.into());
+    }
+
+    /// Draw the walls on screen over the cells under them. False if none are on screen.
+    fn add_walls(mesh: &mut graphics::MeshBuilder, viewport: &Viewport, walls: &Walls) -> GameResult<bool> {
+        let visible = match viewport.visible_cells() {
+            Some(visible) => visible,
+            None => return Ok(false),
+        };
+
+        let mut added = false;
+        for (pos, alive) in walls.iter().filter(|&(pos, _)| visible.contains(pos)) {
+            let rect = viewport.cell_rect(pos);
+            if alive {
+                mesh.rectangle(graphics::DrawMode::fill(), rect, WALL_COLOR.into());
+            } else {
+                let color = Palette::translucent(WALL_COLOR, 0.6);
+                mesh.rectangle(graphics::DrawMode::fill(), rect, Palette::translucent(WALL_COLOR, 0.2));
+                mesh.line(&[ggez::mint::Point2 { x: rect.x, y: rect.y }, ggez::mint::Point2 { x: rect.x + rect.w, y: rect.y + rect.h }], 1.0, color)?;
+                mesh.line(&[ggez::mint::Point2 { x: rect.x + rect.w, y: rect.y }, ggez::mint::Point2 { x: rect.x, y: rect.y + rect.h }], 1.0, color)?;
+            }
+            added = true;
+        }
+        Ok(added)
+    }
+
+    fn add_frozen(mesh: &mut graphics::MeshBuilder, viewport: &Viewport, region: &Selection) {
+        let rect = viewport.selection_rect(region);
+        mesh.rectangle(graphics::DrawMode::fill(), rect, Palette::translucent(FROZEN_COLOR, 0.2))
+            .rectangle(graphics::DrawMode::stroke(2.0), rect, Palette::translucent(FROZEN_COLOR, 0.8));
+    }
+
+    /// A translucent pattern at `origin`, marking cells that would land on an
+    /// already live cell. False if none of it is on the board.
+    fn add_preview(mesh: &mut graphics::MeshBuilder, viewport: &Viewport, palette: &Palette, board: &dyn Board, pattern: &Pattern, origin: GridPosition) -> bool {
+        let mut added = false;
+
+        for pos in pattern.placed_at(origin, board.size()) {
+            let color = if !board.is_alive(pos) {
+                Palette::translucent(palette.cell, 0.5)
+            } else {
+                Palette::translucent(palette.collision, 0.8)
+            };
+            mesh.rectangle(graphics::DrawMode::fill(), viewport.cell_rect(pos), color);
+            added = true;
+        }
+        added
+    }
+
+    fn start_drag_move(&mut self, selection: Selection, grab: GridPosition) {
+        let (min, _) = selection.bounds();
+        let pattern = Pattern::capture(self.board.as_ref(), &selection);
+        self.history.record(self.board.as_ref());
+        board::set_region(self.board.as_mut(), &selection, true);
+
+        self.drag_move = Some(DragMove {
+            pattern,
+            source: min,
+            destination: min,
+            grab_offset: GridPosition::new(grab.x - min.x, grab.y - min.y),
+        });
+    }
+
+    /// Stamp the moved contents at `origin` and select where they landed.
+    fn drop_drag_move(&mut self, drag: DragMove, origin: GridPosition) {
+        drag.pattern.stamp(self.board.as_mut(), origin);
+        self.selection = Some(drag.pattern.footprint(origin, self.board.size()));
+    }
+
+    /// Stamp the pattern being placed centered on `pos`, keeping it around
+    /// afterwards if it's sticky.
+    fn stamp_placement(&mut self, pos: GridPosition) {
+        if let Some(placement) = self.placement.take() {
+            self.history.record(self.board.as_ref());
+            placement.pattern.stamp(self.board.as_mut(), placement.pattern.origin_centered_on(pos));
+
+            let name = placement.name;
+            if placement.sticky {
+                self.placement = Some(placement);
+            }
+            self.observe_tutorial(tutorial::Event::Stamped(name));
+        }
+    }
+
+    /// Move the cursor from the keyboard, starting from the middle of the board.
+    fn move_cursor(&mut self, dx: i16, dy: i16) {
+        let (width, height) = self.board.size();
+        let pos = self.cursor.unwrap_or_else(|| GridPosition::new(width / 2, height / 2));
+
+        self.cursor = Some(GridPosition::new(
+            (pos.x + dx).clamp(0, width - 1),
+            (pos.y + dy).clamp(0, height - 1),
+        ));
+        self.keyboard_cursor = true;
+    }
+
+    /// Tint the cells on screen that differ from the snapshot: newly alive ones
+    /// in the selection color, and ones that have since died in the collision color.
+    fn add_diff(mesh: &mut graphics::MeshBuilder, viewport: &Viewport, palette: &Palette, snapshot: &dyn Board, board: &dyn Board) -> bool {
+        let visible = match viewport.visible_cells() {
+            Some(visible) => visible,
+            None => return false,
+        };
+        let (born, died) = board::diff(snapshot, board);
+
+        let mut added = false;
+        for (cells, color) in &[(born, palette.selection), (died, palette.collision)] {
+            for &pos in cells.iter().filter(|&&pos| visible.contains(pos)) {
+                mesh.rectangle(graphics::DrawMode::fill(), viewport.cell_rect(pos), Palette::translucent(*color, 0.6));
+                added = true;
+            }
+        }
+        added
+    }
+
+    /// Outline the cells on screen that `next` has alive and `board` doesn't,
+    /// and the other way around, like the diff but without covering them up.
+    fn add_ghost(mesh: &mut graphics::MeshBuilder, viewport: &Viewport, palette: &Palette, board: &dyn Board, next: &dyn Board) -> bool {
+        let visible = match viewport.visible_cells() {
+            Some(visible) => visible,
+            None => return false,
+        };
+        let (born, died) = board::diff(board, next);
+
+        let mut added = false;
+        for (cells, color) in &[(born, palette.selection), (died, palette.collision)] {
+            for &pos in cells.iter().filter(|&&pos| visible.contains(pos)) {
+                mesh.rectangle(graphics::DrawMode::stroke(2.0), viewport.cell_rect(pos), (*color).into());
+                added = true;
+            }
+        }
+        added
+    }
+
+    /// Shade the live cells on screen of a layer that isn't being edited.
+    fn add_layer(mesh: &mut graphics::MeshBuilder, viewport: &Viewport, board: &dyn Board, color: [f32; 4]) -> bool {
+        let visible = match viewport.visible_cells() {
+            Some(visible) => visible,
+            None => return false,
+        };
+
+        let mut added = false;
+        for pos in board.live_cells_in(&visible) {
+            mesh.rectangle(graphics::DrawMode::fill(), viewport.cell_rect(pos), Palette::translucent(color, 0.45));
+            added = true;
+        }
+        added
+    }
+
+    /// Color the live cells on screen by the player who owns them.
+    fn add_versus(mesh: &mut graphics::MeshBuilder, viewport: &Viewport, board: &dyn Board, versus: &Match) -> bool {
+        let visible = match viewport.visible_cells() {
+            Some(visible) => visible,
+            None => return false,
+        };
+
+        let mut added = false;
+        for pos in board.live_cells_in(&visible) {
+            if let Some(owner) = versus.owners.get(&pos) {
+                mesh.rectangle(graphics::DrawMode::fill(), viewport.cell_rect(pos), VERSUS_COLORS[owner.index()].into());
+                added = true;
+            }
+        }
+        added
+    }
+
+    /// Shade each region in proportion to how much has been born and died in it.
+    fn add_activity(mesh: &mut graphics::MeshBuilder, viewport: &Viewport, palette: &Palette, activity: &Activity) -> bool {
+        let (columns, rows) = activity.regions();
+        let regions = || (0..rows).flat_map(move |row| (0..columns).map(move |column| (column, row)));
+        let busiest = regions().map(|(column, row)| activity.region(column, row)).map(|(births, deaths)| births + deaths).max().unwrap_or(0);
+        if busiest == 0 {
+            return false;
+        }
+
+        let size = activity.region_size();
+        for (column, row) in regions() {
+            let (births, deaths) = activity.region(column, row);
+            if births + deaths == 0 {
+                continue;
+            }
+            let start = GridPosition::new(column as i16 * size, row as i16 * size);
+            let end = GridPosition::new((start.x + size - 1).min(viewport.grid.0 - 1), (start.y + size - 1).min(viewport.grid.1 - 1));
+            let alpha = 0.5 * (births + deaths) as f32 / busiest as f32;
+            mesh.rectangle(graphics::DrawMode::fill(), viewport.selection_rect(&Selection { start, end }), Palette::translucent(palette.collision, alpha));
+        }
+        true
+    }
+
+    /// Outline the smallest box holding every live cell, in the collision color
+    /// once it reaches the edge of the board.
+    fn add_bounds(mesh: &mut graphics::MeshBuilder, viewport: &Viewport, palette: &Palette, theme: &Theme, bounds: &Selection) {
+        let (min, max) = bounds.bounds();
+        let at_edge = min.x == 0 || min.y == 0 || max.x == viewport.grid.0 - 1 || max.y == viewport.grid.1 - 1;
+        let color = if at_edge { palette.collision.into() } else { Palette::translucent(theme.hud_text, 0.7) };
+        mesh.rectangle(graphics::DrawMode::stroke(1.0), viewport.selection_rect(bounds), color);
+    }
+
+    /// Outline the cell under the cursor: boldly for the keyboard cursor and
+    /// subtly for the mouse, just to show which cell a click will hit.
+    fn add_cursor(mesh: &mut graphics::MeshBuilder, viewport: &Viewport, theme: &Theme, pos: GridPosition, keyboard: bool) {
+        let (width, alpha) = if keyboard { (2.0, 1.0) } else { (1.0, 0.5) };
+        mesh.rectangle(graphics::DrawMode::stroke(width), viewport.cell_rect(pos), Palette::translucent(theme.hud_text, alpha));
+    }
+
+    /// The cells, then everything laid over them, in two draws however much
+    /// is on the board: every cell from the cell batch, then the grid,
+    /// players' cells, selection, activity, diff, bounding box, cursor and
+    /// previews in one mesh.
+    fn draw_board(&mut self, ctx: &mut Context) -> GameResult<()> {
+        if self.cell_batch.is_none() {
+            self.cell_batch = Some(CellBatch::new(ctx)?);
+        }
+        let fade_progress = self.fade_progress();
+        // cells that grow and shrink, or have a shape, need a sprite each
+        let squares = CELL_SHAPES[self.cell_shape_index] == CellShape::Square || self.viewport.cell_size.0 < MIN_SHAPED_CELL_SIZE;
+        let look = CellLook {
+            viewport: if squares && fade_progress.is_none() { None } else { Some(self.viewport) },
+            shape_index: self.cell_shape_index,
+            palette_index: self.palette_index,
+            onion_skin: self.overlays.onion_skin,
+            fade_progress,
+        };
+        let cells = self.cell_batch.as_mut().expect("cell batch was just made");
+
+        if look.viewport.is_none() && (self.cells_dirty || cells.look != Some(look)) {
+            // oldest first, so each generation sits on top of the one before it
+            let [r, g, b, _] = PALETTES[self.palette_index].cell;
+            let generations = &self.generations;
+            let mut layers: Vec<(&dyn Board, [f32; 4])> = (1..=self.overlays.onion_skin.min(generations.len()))
+                .rev()
+                .map(|age| (generations[generations.len() - age].as_ref(), [r, g, b, ONION_SKIN_ALPHA / age as f32]))
+                .collect();
+            layers.push((self.board.as_ref(), PALETTES[self.palette_index].cell));
+            cells.paint(ctx, self.board.size(), &layers)?;
+
+            cells.look = Some(look);
+            self.cells_dirty = false;
+        } else if self.cells_dirty || cells.look != Some(look) {
+            cells.begin(CELL_SHAPES[self.cell_shape_index], &self.viewport);
+
+            // only what's on screen, however big the board is
+            if let Some(visible) = self.viewport.visible_cells() {
+                // oldest first, so each generation sits on top of the one before it
+                let [r, g, b, _] = PALETTES[self.palette_index].cell;
+                for age in (1..=self.overlays.onion_skin.min(self.generations.len())).rev() {
+                    let color = [r, g, b, ONION_SKIN_ALPHA / age as f32];
+                    for pos in self.generations[self.generations.len() - age].live_cells_in(&visible) {
+                        cells.add(&self.viewport, pos, color, 1.0);
+                    }
+                }
+
+                let color = PALETTES[self.palette_index].cell;
+                match (look.fade_progress, self.generations.back()) {
+                    (Some(progress), Some(previous)) => {
+                        // births grow in as survivors stay put, then deaths shrink away
+                        for pos in self.board.live_cells_in(&visible) {
+                            let amount = if previous.is_alive(pos) { 1.0 } else { progress };
+                            cells.add(&self.viewport, pos, color, amount);
+                        }
+                        let board = self.board.as_ref();
+                        for pos in previous.live_cells_in(&visible).filter(|&pos| !board.is_alive(pos)) {
+                            cells.add(&self.viewport, pos, color, 1.0 - progress);
+                        }
+                    }
+                    _ => {
+                        for pos in self.board.live_cells_in(&visible) {
+                            cells.add(&self.viewport, pos, color, 1.0);
+                        }
+                    }
+                }
+            }
+
+            cells.look = Some(look);
+            self.cells_dirty = false;
+        }
+        cells.draw(ctx, &self.viewport)?;
+
+        let (viewport, palette, theme) = (&self.viewport, &PALETTES[self.palette_index], &THEMES[self.theme_index]);
+        let mut overlay = graphics::MeshBuilder::new();
+        // ggez refuses to build a mesh without any vertices
+        let mut empty = true;
+
+        if viewport.cell_size.0 >= MIN_GRID_CELL_SIZE {
+            Self::add_grid(&mut overlay, viewport, theme.grid)?;
+            empty = false;
+        }
+
+        if let Some(drag) = &self.drag_move {
+            Self::add_selection(&mut overlay, viewport, palette, &drag.pattern.footprint(drag.destination, self.board.size()));
+            empty = false;
+        } else if let Some(selection) = &self.selection {
+            Self::add_selection(&mut overlay, viewport, palette, selection);
+            empty = false;
+        }
+
+        if let (Some(placement), Some(cursor)) = (&self.placement, self.cursor) {
+            let origin = placement.pattern.origin_centered_on(cursor);
+            empty &= !Self::add_preview(&mut overlay, viewport, palette, self.board.as_ref(), &placement.pattern, origin);
+        }
+
+        if let GameMode::Versus(versus) = &self.followers.game {
+            empty &= !Self::add_versus(&mut overlay, viewport, self.board.as_ref(), versus);
+        }
+
+        if let (true, Some(activity)) = (self.overlays.activity, &self.followers.activity) {
+            empty &= !Self::add_activity(&mut overlay, viewport, palette, activity);
+        }
+
+        for region in &self.frozen {
+            Self::add_frozen(&mut overlay, viewport, region);
+            empty = false;
+        }
+
+        if !self.walls.is_empty() {
+            empty &= !Self::add_walls(&mut overlay, viewport, &self.walls)?;
+        }
+
+        for (index, layer) in self.layers.iter().enumerate().filter(|&(index, layer)| index != self.layer && layer.visible) {
+            let color = if index == 0 { palette.cell } else { LAYER_COLORS[(index - 1) % LAYER_COLORS.len()] };
+            empty &= !Self::add_layer(&mut overlay, viewport, layer.board.as_ref(), color);
+        }
+
+        if let (true, Some((_, snapshot))) = (self.overlays.show_diff, &self.snapshot) {
+            empty &= !Self::add_diff(&mut overlay, viewport, palette, snapshot.as_ref(), self.board.as_ref());
+        }
+
+        if self.overlays.ghost && self.mode == Mode::Editor {
+            let next = self.next_board();
+            empty &= !Self::add_ghost(&mut overlay, viewport, palette, self.board.as_ref(), next.as_ref());
+        }
+
+        if let (true, Some(bounds)) = (self.overlays.show_bounds, self.board.bounds()) {
+            Self::add_bounds(&mut overlay, viewport, palette, theme, &bounds);
+            empty = false;
+        }
+
+        if let (true, Some(cursor)) = (self.mode.shows_board(), self.cursor) {
+            Self::add_cursor(&mut overlay, viewport, theme, cursor, self.keyboard_cursor);
+            empty = false;
+        }
+
+        if let Some(drag) = &self.drag_move {
+            empty &= !Self::add_preview(&mut overlay, viewport, palette, self.board.as_ref(), &drag.pattern, drag.destination);
+        }
+
+        if empty {
+            return Ok(());
+        }
+        let overlay = overlay.build(ctx)?;
+        graphics::draw(ctx, &overlay, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))
+    }
+
+    /// A strip along the bottom of the window with the rule, speed, generation and population.
+    fn draw_status_bar(&self, ctx: &mut Context) -> GameResult<()> {
+        let screen = graphics::screen_coordinates(ctx);
+        let bar = graphics::Rect::new(screen.x, screen.y + screen.h - STATUS_BAR_HEIGHT, screen.w, STATUS_BAR_HEIGHT);
+        let theme = THEMES[self.theme_index];
+        let backdrop = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), bar, theme.hud_background.into())?;
+        graphics::draw(ctx, &backdrop, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+
+        let tab = if self.tabs.is_empty() { String::new() } else { format!("tab {} of {}    ", self.tab_index + 1, self.tabs.len() + 1) };
+        let layer = if self.is_sketching() { format!("{} of {}    ", self.layer_name(), self.layers.len() - 1) } else { String::new() };
+        let puzzle = match &self.followers.game {
+            GameMode::Puzzle(run) => {
+                let puzzle = &self.puzzles[run.index];
+                let status = if run.solved { "solved" } else { "unsolved" };
+                format!("puzzle {}: {} of {} cells, {} generations, {}    ", puzzle.name, self.population(), puzzle.cells, puzzle.generations, status)
+            }
+            _ => String::new(),
+        };
+        let attack = match (&self.followers.game, self.attack_time_left()) {
+            (GameMode::Attack(_), Some(left)) => format!("time attack: {} of {} cells, {}s left    ", self.population(), TIME_ATTACK_CELLS, left.as_secs()),
+            (GameMode::Attack(_), None) => format!("time attack: gen {} of {}, {} cells    ", self.generation, TIME_ATTACK_GENERATIONS, self.population()),
+            _ => String::new(),
+        };
+        let network = match &self.network {
+            Some(Network { connection: Connection::Host(host), .. }) => format!("hosting for {}    ", host.peer_count()),
+            Some(Network { connection: Connection::Joined(_), .. }) => "joined    ".to_string(),
+            Some(Network { connection: Connection::Watching(_), .. }) => "watching    ".to_string(),
+            None => String::new(),
+        };
+        let network = match &self.broadcast {
+            Some(broadcast) => format!("{}broadcasting to {}    ", network, broadcast.spectator_count()),
+            None => network,
+        };
+        let golf = match &self.followers.game {
+            GameMode::Golf(run) => {
+                let hole = &golf::HOLES[run.hole];
+                let best = self.scorecard.best(run.hole).map_or("none".to_string(), |best| best.to_string());
+                format!("golf {}: {}, par {}, best {}    ", hole.name, hole.description(), hole.par, best)
+            }
+            _ => String::new(),
+        };
+        let quiz = match &self.followers.game {
+            GameMode::Quiz(quiz) => format!("quiz: {} of {} right    ", quiz.right, quiz.asked),
+            _ => String::new(),
+        };
+        let versus = match &self.followers.game {
+            GameMode::Versus(versus) if versus.placing() => {
+                let turn = versus.turn;
+                format!("versus: {} to place, {} cells left    ", VERSUS_NAMES[turn.index()], versus.remaining(turn))
+            }
+            GameMode::Versus(versus) => {
+                let territory = versus.territory();
+                format!("versus: {} {} - {} {}, gen {} of {}    ", VERSUS_NAMES[0], territory[0], territory[1], VERSUS_NAMES[1], versus.generation, versus.generations)
+            }
+            _ => String::new(),
+        };
+        let search = match &self.search {
+            Some(search) => format!("searching: {} soups, {} rare    ", search.soups, search.rare_finds),
+            None => String::new(),
+        };
+        let bounds = match (self.overlays.show_bounds, self.board.bounds()) {
+            (true, Some(bounds)) => {
+                let (min, max) = bounds.bounds();
+                format!("    bounds {}x{}", max.x - min.x + 1, max.y - min.y + 1)
+            }
+            (true, None) => "    bounds none".to_string(),
+            (false, _) => String::new(),
+        };
+        let activity = match self.followers.activity.as_ref().map(|activity| (activity, activity.busiest())) {
+            Some((activity, Some((column, row)))) => {
+                let (births, deaths) = activity.region(column, row);
+                let size = activity.region_size() as usize;
+                format!("    busiest region ({}, {}): {} births, {} deaths", column * size, row * size, births, deaths)
+            }
+            Some((_, None)) => "    no activity yet".to_string(),
+            None => String::new(),
+        };
+        let diff = match (self.overlays.show_diff, &self.snapshot) {
+            (true, Some((_, snapshot))) => {
+                let (born, died) = board::diff(snapshot.as_ref(), self.board.as_ref());
+                format!("    diff +{} -{}", born.len(), died.len())
+            }
+            _ => String::new(),
+        };
+        let target = if self.turbo {
+            format!("turbo {} gen/frame", self.turbo_generations)
+        } else {
+            format!("{} gen/s target", self.updates_per_second)
+        };
+        let status = graphics::Text::new(format!(
+            "{}{}{}{}{}{}{}{}{}{}    {}    {} gen/s actual    generation {}    population {}{}{}{}",
+            tab,
+            layer,
+            network,
+            puzzle,
+            golf,
+            attack,
+            quiz,
+            versus,
+            search,
+            self.rule_label(),
+            target,
+            self.actual_updates_per_second(),
+            self.generation,
+            self.population(),
+            bounds,
+            diff,
+            activity,
+        ));
+        let y = bar.y + (STATUS_BAR_HEIGHT - status.height(ctx) as f32) / 2.0;
+        graphics::draw(ctx, &status, (ggez::mint::Point2 { x: bar.x + 8.0, y }, theme.hud_text.into()))?;
+
+        if let Some(cursor) = self.cursor {
+            let coordinates = graphics::Text::new(if self.overlays.inspect { self.inspection(cursor) } else { format!("({}, {})", cursor.x, cursor.y) });
+            let x = bar.x + bar.w - 8.0 - coordinates.width(ctx) as f32;
+            graphics::draw(ctx, &coordinates, (ggez::mint::Point2 { x, y }, theme.hud_text.into()))?;
+        }
+
+        Ok(())
+    }
+
+    /// A play or pause symbol with a label in the top left corner, so a stable
+    /// board can be told apart from a paused one.
+    fn draw_run_indicator(&self, ctx: &mut Context) -> GameResult<()> {
+        let running = self.mode == Mode::Running;
+        let (x, y, size) = (MINIMAP_MARGIN, MINIMAP_MARGIN, 16.0);
+        let color = if running { [0.3, 0.9, 0.3, 1.0] } else { [1.0, 0.8, 0.2, 1.0] };
+
+        let mut indicator = graphics::MeshBuilder::new();
+        indicator.rectangle(
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(x - 4.0, y - 4.0, 110.0, size + 8.0),
+            Palette::translucent(THEMES[self.theme_index].hud_background, 0.6),
+        );
+
+        if running {
+            indicator.polygon(
+                graphics::DrawMode::fill(),
+                &[
+                    ggez::mint::Point2 { x, y },
+                    ggez::mint::Point2 { x: x + size, y: y + size / 2.0 },
+                    ggez::mint::Point2 { x, y: y + size },
+                ],
+                color.into(),
+            )?;
+        } else {
+            for bar in &[x, x + size * 0.6] {
+                indicator.rectangle(
+                    graphics::DrawMode::fill(),
+                    graphics::Rect::new(*bar, y, size * 0.4, size),
+                    color.into(),
+                );
+            }
+        }
+
+        let indicator = indicator.build(ctx)?;
+        graphics::draw(ctx, &indicator, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+
+        let label = graphics::Text::new(if running { "RUNNING" } else { "PAUSED" });
+        graphics::draw(ctx, &label, (ggez::mint::Point2 { x: x + size + 8.0, y }, color.into()))
+    }
+
+    /// Density and entropy of the board, under the run indicator.
+    fn draw_stats(&self, ctx: &mut Context) -> GameResult<()> {
+        let theme = THEMES[self.theme_index];
+        let text = graphics::Text::new(format!(
+            "density {:.2}%\nentropy {:.3} bits",
+            stats::density(self.board.as_ref()) * 100.0,
+            stats::block_entropy(self.board.as_ref()),
+        ));
+        let (width, height) = (text.width(ctx) as f32, text.height(ctx) as f32);
+        let (x, y) = (MINIMAP_MARGIN, MINIMAP_MARGIN * 2.0 + 24.0);
+
+        let backdrop = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(x - 4.0, y - 4.0, width + 8.0, height + 8.0),
+            Palette::translucent(theme.hud_background, 0.6),
+        )?;
+        graphics::draw(ctx, &backdrop, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+        graphics::draw(ctx, &text, (ggez::mint::Point2 { x, y }, theme.hud_text.into()))
+    }
+
+    /// A marker on every labelled cell on screen, with its label to the
+    /// right of it, and the label being typed with a caret at its end.
+    fn draw_labels(&self, ctx: &mut Context) -> GameResult<()> {
+        let visible = match self.viewport.visible_cells() {
+            Some(visible) => visible,
+            None => return Ok(()),
+        };
+        let typing = match &self.entry {
+            Some(Entry::Label(pos, text)) => Some((*pos, format!("{}_", text))),
+            _ => None,
+        };
+        let typed = typing.as_ref().map(|&(pos, _)| pos);
+        let labels: Vec<(GridPosition, String)> = self
+            .labels
+            .iter()
+            .filter(|&(pos, _)| typed != Some(pos))
+            .map(|(pos, text)| (pos, text.to_string()))
+            .chain(typing)
+            .filter(|(pos, _)| visible.contains(*pos))
+            .collect();
+        if labels.is_empty() {
+            return Ok(());
+        }
+
+        let theme = THEMES[self.theme_index];
+        let marker = PALETTES[self.palette_index].selection;
+        let mut overlay = graphics::MeshBuilder::new();
+        let mut texts = Vec::new();
+        for (pos, label) in labels {
+            let cell = self.viewport.cell_rect(pos);
+            overlay.rectangle(graphics::DrawMode::stroke(2.0), cell, marker.into());
+            let text = graphics::Text::new(label);
+            let (width, height) = (text.width(ctx) as f32, text.height(ctx) as f32);
+            let (x, y) = (cell.x + cell.w + 6.0, cell.y + (cell.h - height) / 2.0);
+            overlay.rectangle(
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(x - 3.0, y - 2.0, width + 6.0, height + 4.0),
+                Palette::translucent(theme.hud_background, 0.8),
+            );
+            texts.push((text, x, y));
+        }
+
+        let overlay = overlay.build(ctx)?;
+        graphics::draw(ctx, &overlay, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+        for (text, x, y) in texts {
+            graphics::draw(ctx, &text, (ggez::mint::Point2 { x, y }, theme.hud_text.into()))?;
+        }
+        Ok(())
+    }
+
+    /// The generation being typed in to warp to, or how far the warp has
+    /// got, on a bar in the middle of the screen.
+    fn draw_warp(&self, ctx: &mut Context) -> GameResult<()> {
+        let (label, progress) = match (&self.entry, &self.warp) {
+            (Some(Entry::Generation(text)), _) => (format!("warp to generation: {}_", text), None),
+            (_, Some(warp)) => {
+                let progress = self.generation.saturating_sub(warp.from) as f32 / (warp.to - warp.from) as f32;
+                (format!("warping to generation {}: {} so far, Escape to stop", warp.to, self.generation), Some(progress))
+            }
+            _ => return Ok(()),
+        };
+
+        let theme = THEMES[self.theme_index];
+        let screen = graphics::screen_coordinates(ctx);
+        let text = graphics::Text::new(label);
+        let (width, height) = (text.width(ctx) as f32, text.height(ctx) as f32);
+        let panel_width = width.max(WARP_BAR_WIDTH);
+        let (x, y) = (screen.x + (screen.w - panel_width) / 2.0, screen.y + (screen.h - height) / 2.0);
+
+        let mut panel = graphics::MeshBuilder::new();
+        let bar_height = if progress.is_some() { TIMELINE_HEIGHT + 6.0 } else { 0.0 };
+        panel.rectangle(
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(x - 8.0, y - 6.0, panel_width + 16.0, height + bar_height + 12.0),
+            Palette::translucent(theme.hud_background, 0.85),
+        );
+        if let Some(progress) = progress {
+            let bar = graphics::Rect::new(x, y + height + 6.0, panel_width, TIMELINE_HEIGHT);
+            let done = graphics::Rect::new(bar.x, bar.y, bar.w * progress.clamp(0.0, 1.0), bar.h);
+            panel.rectangle(graphics::DrawMode::fill(), done, Palette::translucent(PALETTES[self.palette_index].cell, 0.7));
+            panel.rectangle(graphics::DrawMode::stroke(1.0), bar, [0.6, 0.6, 0.6, 1.0].into());
+        }
+        let panel = panel.build(ctx)?;
+        graphics::draw(ctx, &panel, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+        graphics::draw(ctx, &text, (ggez::mint::Point2 { x: x + (panel_width - width) / 2.0, y }, theme.hud_text.into()))
+    }
+
+    /// The puzzle's target, small, in the bottom left corner above the status bar.
+    fn draw_puzzle_target(&self, ctx: &mut Context, puzzle: &Puzzle) -> GameResult<()> {
+        let theme = THEMES[self.theme_index];
+        let screen = graphics::screen_coordinates(ctx);
+        let label = graphics::Text::new("target");
+        let (label_width, label_height) = (label.width(ctx) as f32, label.height(ctx) as f32 + 4.0);
+        let (width, height) = (puzzle.target.width as f32 * PUZZLE_TARGET_CELL_SIZE, puzzle.target.height as f32 * PUZZLE_TARGET_CELL_SIZE);
+        let (x, y) = (screen.x + MINIMAP_MARGIN, self.hud_bottom(screen) - MINIMAP_MARGIN - height);
+
+        let mut target = graphics::MeshBuilder::new();
+        target.rectangle(
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(x - 4.0, y - label_height - 4.0, width.max(label_width) + 8.0, height + label_height + 8.0),
+            Palette::translucent(theme.hud_background, 0.8),
+        );
+        for pos in &puzzle.target.cells {
+            let cell = graphics::Rect::new(
+                x + pos.x as f32 * PUZZLE_TARGET_CELL_SIZE,
+                y + pos.y as f32 * PUZZLE_TARGET_CELL_SIZE,
+                PUZZLE_TARGET_CELL_SIZE,
+                PUZZLE_TARGET_CELL_SIZE,
+            );
+            target.rectangle(graphics::DrawMode::fill(), cell, PALETTES[self.palette_index].cell.into());
+        }
+
+        let target = target.build(ctx)?;
+        graphics::draw(ctx, &target, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+        graphics::draw(ctx, &label, (ggez::mint::Point2 { x, y: y - label_height }, theme.hud_text.into()))
+    }
+
+    /// The time attack high scores in the bottom left corner.
+    fn draw_high_scores(&self, ctx: &mut Context) -> GameResult<()> {
+        let mut table = String::from("high scores");
+        for (place, score) in self.high_scores.scores().iter().enumerate() {
+            table.push_str(&format!("\n{:>2}. {}", place + 1, score));
+        }
+        if self.high_scores.scores().is_empty() {
+            table.push_str("\nnone yet");
+        }
+        self.draw_corner_panel(ctx, table)
+    }
+
+    /// The names to pick from for the quiz question, by their keys, in the bottom left corner.
+    fn draw_quiz_choices(&self, ctx: &mut Context, quiz: &Quiz) -> GameResult<()> {
+        let actions = [Action::Answer1, Action::Answer2, Action::Answer3, Action::Answer4];
+        let mut choices = String::from("what is it?");
+        for (name, &action) in quiz.question.names().zip(&actions) {
+            let key = self.key_bindings.chords(action).into_iter().next().unwrap_or_else(|| "unbound".to_string());
+            choices.push_str(&format!("\n{}: {}", key, name));
+        }
+        self.draw_corner_panel(ctx, choices)
+    }
+
+    /// `text` on a backdrop in the bottom left corner above the status bar.
+    fn draw_corner_panel(&self, ctx: &mut Context, text: String) -> GameResult<()> {
+        let theme = THEMES[self.theme_index];
+        let screen = graphics::screen_coordinates(ctx);
+        let text = graphics::Text::new(text);
+        let (width, height) = (text.width(ctx) as f32, text.height(ctx) as f32);
+        let (x, y) = (screen.x + MINIMAP_MARGIN, self.hud_bottom(screen) - MINIMAP_MARGIN - height);
+
+        let backdrop = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(x - 4.0, y - 4.0, width + 8.0, height + 8.0),
+            Palette::translucent(theme.hud_background, 0.8),
+        )?;
+        graphics::draw(ctx, &backdrop, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+        graphics::draw(ctx, &text, (ggez::mint::Point2 { x, y }, theme.hud_text.into()))
+    }
+
+    /// The tutorial's current prompt, centered along the bottom above the status bar.
+    fn draw_tutorial_prompt(&self, ctx: &mut Context, prompt: &str) -> GameResult<()> {
+        let theme = THEMES[self.theme_index];
+        let screen = graphics::screen_coordinates(ctx);
+        let text = graphics::Text::new(prompt);
+        let (width, height) = (text.width(ctx) as f32, text.height(ctx) as f32);
+        let x = screen.x + (screen.w - width) / 2.0;
+        let y = self.hud_bottom(screen) - MINIMAP_MARGIN - height;
+
+        let backdrop = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(x - 8.0, y - 4.0, width + 16.0, height + 8.0),
+            Palette::translucent(theme.hud_background, 0.8),
+        )?;
+        graphics::draw(ctx, &backdrop, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+        graphics::draw(ctx, &text, (ggez::mint::Point2 { x, y }, theme.hud_text.into()))
+    }
+
+    /// Achievements unlocked in the last few seconds, stacked down the top
+    /// right corner, dropping the ones that have been up long enough.
+    fn draw_toasts(&mut self, ctx: &mut Context) -> GameResult<()> {
+        while self.toasts.front().is_some_and(|(_, shown)| shown.elapsed() >= TOAST_DURATION) {
+            self.toasts.pop_front();
+        }
+
+        let theme = THEMES[self.theme_index];
+        let screen = graphics::screen_coordinates(ctx);
+        let mut y = screen.y + MINIMAP_MARGIN;
+        for (achievement, _) in &self.toasts {
+            let text = graphics::Text::new(format!("achievement unlocked: {}\n{}", achievement.name, achievement.description));
+            let (width, height) = (text.width(ctx) as f32, text.height(ctx) as f32);
+            let x = screen.x + screen.w - MINIMAP_MARGIN - width;
+
+            let backdrop = graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(x - 8.0, y - 4.0, width + 16.0, height + 8.0),
+                Palette::translucent(theme.hud_background, 0.8),
+            )?;
+            graphics::draw(ctx, &backdrop, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+            graphics::draw(ctx, &text, (ggez::mint::Point2 { x, y }, theme.hud_text.into()))?;
+            y += height + 16.0;
+        }
+        Ok(())
+    }
+
+    /// The notice, if there is one and it hasn't been up too long, centered
+    /// along the top of the screen.
+    fn draw_notice(&self, ctx: &mut Context) -> GameResult<()> {
+        let message = match &self.notice {
+            Some((message, shown)) if shown.elapsed() < NOTICE_DURATION => message,
+            _ => return Ok(()),
+        };
+
+        let theme = THEMES[self.theme_index];
+        let text = graphics::Text::new(message.as_str());
+        let (width, height) = (text.width(ctx) as f32, text.height(ctx) as f32);
+        let x = (graphics::screen_coordinates(ctx).w - width) / 2.0;
+
+        let backdrop = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(x - 8.0, MINIMAP_MARGIN - 4.0, width + 16.0, height + 8.0),
+            Palette::translucent(theme.hud_background, 0.8),
+        )?;
+        graphics::draw(ctx, &backdrop, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+        graphics::draw(ctx, &text, (ggez::mint::Point2 { x, y: MINIMAP_MARGIN }, theme.hud_text.into()))
+    }
+
+    /// Dim the board and list every control along with the current rule and speed.
+    fn draw_help(&self, ctx: &mut Context) -> GameResult<()> {
+        let theme = THEMES[self.theme_index];
+        let screen = graphics::screen_coordinates(ctx);
+        let backdrop = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            screen,
+            Palette::translucent(theme.hud_background, 0.9),
+            )?;
+        graphics::draw(ctx, &backdrop, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+
+        let status = format!("Rule {}, {} generations per second", self.rule_label(), self.updates_per_second);
+        let mut keys = String::new();
+        let mut descriptions = String::new();
+
+        for &action in Action::ALL.iter() {
+            let chords = self.key_bindings.chords(action);
+            if !chords.is_empty() {
+                keys.push_str(&format!("{}\n", chords.join(" / ")));
+                descriptions.push_str(&format!("{}\n", action.description()));
+            }
+        }
+        keys.push_str("Mouse");
+        descriptions.push_str("click or drag to paint, hold LShift to erase");
+
+        // the default font isn't monospaced, so the two columns are laid out separately
+        let status = graphics::Text::new(status);
+        let keys = graphics::Text::new(keys);
+        let descriptions = graphics::Text::new(descriptions);
+        let top = 30.0 + status.height(ctx) as f32;
+        let column = 40.0 + keys.width(ctx) as f32;
+
+        graphics::draw(ctx, &status, (ggez::mint::Point2 { x: 20.0, y: 20.0 }, theme.hud_text.into()))?;
+        graphics::draw(ctx, &keys, (ggez::mint::Point2 { x: 20.0, y: top }, theme.hud_text.into()))?;
+        graphics::draw(ctx, &descriptions, (ggez::mint::Point2 { x: column, y: top }, theme.hud_text.into()))
+    }
+
+    /// Paint the brush at `pos`. Starting a stroke decides whether it brings
+    /// cells to life or kills them (by toggling the cell under the cursor, or
+    /// always killing while shift is held) and continuing it carries on with
+    /// the same choice.
+    fn toggle_cell(&mut self, pos: GridPosition, mouse_motion: bool) {
+        self.cells_dirty = true;
+        self.cycles.clear();
+        if !mouse_motion {
+            self.paint_dead = match self.wall_paint {
+                // painting walls knocks down the ones it starts on
+                Some(alive) => self.lshift_pressed || self.walls.get(pos) == Some(alive),
+                None => self.lshift_pressed || self.board.is_alive(pos),
+            };
+        }
+
+        let grid_size = self.board.size();
+        for cell in BRUSHES[self.brush_index].cells(pos, grid_size) {
+            for image in SYMMETRIES[self.symmetry_index].images(cell, grid_size) {
+                match self.wall_paint {
+                    Some(_) if self.paint_dead => self.walls.set(image, None),
+                    Some(alive) => {
+                        self.walls.set(image, Some(alive));
+                        self.board.set_alive(image, alive);
+                    }
+                    None => self.board.set_alive(image, !self.paint_dead),
+                }
+            }
+        }
+        self.observe_tutorial(tutorial::Event::Painted);
+    }
+
+    fn perform(&mut self, ctx: &mut Context, action: Action) {
+        // most actions can change the board one way or another
+        self.cells_dirty = true;
+        self.cycles.clear();
+        self.finish_scrub();
+
+        if action == Action::ToggleHelp {
+            self.overlays.show_help = !self.overlays.show_help;
+        } else if matches!(action, Action::ToggleMusic | Action::NextTrack) {
+            let notice = if action == Action::ToggleMusic { self.music.toggle(ctx) } else { self.music.next(ctx) };
+            self.notify(notice);
+        } else if self.is_joined() && matches!(action, Action::ToggleRun | Action::Step | Action::WarpToGeneration) && self.mode.shows_board() {
+            self.notify("the host runs the board".to_string());
+        } else if self.playback.is_some() && matches!(action, Action::ToggleRun | Action::Step | Action::StepBack | Action::WarpToGeneration) && self.mode.shows_board() {
+            self.notify("the replay is running the board".to_string());
+        } else if self.is_sketching() && matches!(action, Action::ToggleRun | Action::Step | Action::StepBack | Action::WarpToGeneration) && self.mode.shows_board() {
+            self.notify("sketch layers don't run: merge this one down, or go back to the board".to_string());
+        } else if self.mode.shows_board() {
+            self.perform_board_action(action);
+        } else {
+            self.perform_menu_action(ctx, action);
+        }
+        self.observe_tutorial(tutorial::Event::Performed(action));
+    }
+
+    fn open_menu(&mut self, mode: Mode) {
+        self.mode = mode;
+        self.menu_index = 0;
+        self.mouse_down = false;
+    }
+
+    /// Entries of the current menu screen.
+    fn menu_items(&self) -> Vec<String> {
+        match self.mode {
+            Mode::Start => [
+                "Sandbox: draw and run anything",
+                "Tutorial: learn the basics step by step",
+                "Puzzle: reach a target from a few cells",
+                "Time attack: the most cells after 100 generations",
+                "Quiz: name the pattern on the board",
+                "Life golf: make something from as few cells as you can",
+                "Versus: two players fight for the board",
+                "Soup search: run random soups and log what they leave",
+                "Quit",
+            ]
+            .iter()
+            .map(|item| item.to_string())
+            .collect(),
+            Mode::MainMenu => ["Resume", "Pattern browser", "Settings", "Quit"].iter().map(|item| item.to_string()).collect(),
+            Mode::PatternBrowser => library::PATTERNS.iter().map(|(name, _)| name.to_string()).collect(),
+            Mode::Settings => SETTINGS.iter().map(|&setting| self.setting_label(setting)).collect(),
+            Mode::Editor | Mode::Running => Vec::new(),
+        }
+    }
+
+    /// Step a setting forwards or backwards through its choices.
+    fn adjust_setting(&mut self, forward: bool) {
+        let cycle = |index: usize, len: usize| if forward { (index + 1) % len } else { (index + len - 1) % len };
+
+        match SETTINGS[self.menu_index] {
+            Setting::Rule => {
+                // the presets come first, then the plugins
+                let presets = rule::PRESETS.len();
+                let current = match self.plugin_index {
+                    Some(index) => Some(presets + index),
+                    None => rule::PRESETS.iter().position(|(_, rulestring)| Rule::parse(rulestring) == Some(self.rule)),
+                };
+                // a custom rule steps onto the first preset
+                let index = current.map_or(0, |index| cycle(index, presets + self.plugins.len()));
+
+                if index < presets {
+                    self.rule = Rule::parse(rule::PRESETS[index].1).expect("invalid preset rule");
+                    self.plugin_index = None;
+                } else {
+                    self.plugin_index = Some(index - presets);
+                }
+            },
+            Setting::Speed => self.updates_per_second = Self::next_speed(self.updates_per_second, forward),
+            Setting::Colors => self.palette_index = cycle(self.palette_index, PALETTES.len()),
+            Setting::Theme => self.theme_index = cycle(self.theme_index, THEMES.len()),
+            Setting::Density => self.adjust_density(forward),
+            Setting::SoupSymmetry => self.soup_symmetry_index = cycle(self.soup_symmetry_index, SOUP_SYMMETRIES.len()),
+            Setting::Seed => {
+                self.seed = if forward { self.seed.wrapping_add(1) } else { self.seed.wrapping_sub(1) };
+                self.rng = StdRng::seed_from_u64(self.seed);
+                self.soups_generated = 0;
+            },
+            Setting::Brush => self.brush_index = cycle(self.brush_index, BRUSHES.len()),
+            Setting::Symmetry => self.symmetry_index = cycle(self.symmetry_index, SYMMETRIES.len()),
+            Setting::OnionSkin => self.overlays.onion_skin = cycle(self.overlays.onion_skin, ONION_SKIN_LAYERS + 1),
+            Setting::CellShape => self.cell_shape_index = cycle(self.cell_shape_index, CELL_SHAPES.len()),
+            Setting::Animation => self.animate = !self.animate,
+            Setting::AutoPause => self.auto_pause = !self.auto_pause,
+            Setting::FrozenCounted => self.frozen_counted = !self.frozen_counted,
+            Setting::ActivityOverlay => self.overlays.activity = !self.overlays.activity,
+            Setting::BoardSize => self.step_board_size(forward),
+            Setting::Sound => self.followers.sound_effects.enabled = !self.followers.sound_effects.enabled,
+            Setting::BoardMusic => self.followers.sound_effects.music = !self.followers.sound_effects.music,
+            Setting::Volume => {
+                let step = if forward { VOLUME_STEP } else { -VOLUME_STEP };
+                self.followers.sound_effects.volume = (self.followers.sound_effects.volume + step).clamp(0.0, 1.0);
+            }
+            Setting::MusicVolume => {
+                let step = if forward { VOLUME_STEP } else { -VOLUME_STEP };
+                self.music.set_volume((self.music.volume + step).clamp(0.0, 1.0));
+                self.save_setting("music_volume", &format!("{:.1}", self.music.volume));
+            }
+        }
+    }
+
+    fn setting_label(&self, setting: Setting) -> String {
+        match setting {
+            Setting::Rule if self.plugin_index.is_some() => format!("Rule: {} (plugin)", self.rule_label()),
+            Setting::Rule => format!("Rule: {} {}", self.rule.name().unwrap_or("custom"), self.rule),
+            Setting::Speed => format!("Speed: {} generations per second", self.updates_per_second),
+            Setting::Colors => format!("Colors: {}", PALETTES[self.palette_index].name),
+            Setting::Theme => format!("Theme: {}", THEMES[self.theme_index].name),
+            Setting::Density => format!("Random fill density: {:.0}%", self.density * 100.0),
+            Setting::SoupSymmetry => format!("Soup symmetry: {}", SOUP_SYMMETRIES[self.soup_symmetry_index].name()),
+            Setting::Seed => format!("Soup seed: {}", self.seed),
+            Setting::Brush => format!("Brush: {}", BRUSHES[self.brush_index].name()),
+            Setting::Symmetry => format!("Symmetry: {:?}", SYMMETRIES[self.symmetry_index]),
+            Setting::OnionSkin => match self.overlays.onion_skin {
+                0 => "Onion skin: off".to_string(),
+                layers => format!("Onion skin: {} previous generations", layers),
+            },
+            Setting::CellShape => format!("Cells: {}", CELL_SHAPES[self.cell_shape_index].name()),
+            Setting::Animation => format!("Birth and death animation: {}", if self.animate { "on" } else { "off" }),
+            Setting::AutoPause => format!("Pause once stable: {}", if self.auto_pause { "on" } else { "off" }),
+            Setting::FrozenCounted => format!("Frozen cells count as neighbors: {}", if self.frozen_counted { "on" } else { "off" }),
+            Setting::ActivityOverlay => format!("Activity overlay: {}", if self.overlays.activity { "on" } else { "off" }),
+            Setting::BoardSize => {
+                let (width, height) = self.board.size();
+                format!("Board size: {}x{}", width, height)
+            }
+            Setting::Sound => format!("Sound effects: {}", if self.followers.sound_effects.enabled { "on" } else { "off" }),
+            Setting::BoardMusic => format!("Play the board as music: {}", if self.followers.sound_effects.music { "on" } else { "off" }),
+            Setting::Volume => format!("Volume: {:.0}%", self.followers.sound_effects.volume * 100.0),
+            Setting::MusicVolume => format!("Music volume: {:.0}%", self.music.volume * 100.0),
+        }
+    }
+
+    /// Keep a setting changed in the game for next time, in the config file.
+    fn save_setting(&mut self, key: &str, value: &str) {
+        if let Some(path) = &self.config_file {
+            if let Err(err) = options::save_setting(path, key, value) {
+                self.notify(format!("couldn't save {}: {}", path.display(), err));
+            }
+        }
+    }
+
+    fn adjust_density(&mut self, increase: bool) {
+        let step = if increase { DENSITY_STEP } else { -DENSITY_STEP };
+        self.density = (self.density + step).clamp(MIN_DENSITY, MAX_DENSITY);
+    }
+
+    /// The next faster or slower of the preset speeds.
+    fn next_speed(current: f32, faster: bool) -> f32 {
+        if faster {
+            SPEEDS.iter().copied().find(|&speed| speed > current).unwrap_or(current)
+        } else {
+            SPEEDS.iter().rev().copied().find(|&speed| speed < current).unwrap_or(current)
+        }
+    }
+
+    /// Work out whether the selection, or the whole board without one, is an
+    /// oscillator or a spaceship, and report it.
+    fn find_period(&mut self) {
+        let pattern = match &self.selection {
+            Some(selection) => Pattern::capture(self.board.as_ref(), selection),
+            None => Pattern::capture_live(self.board.as_ref()),
+        };
+        if pattern.cells.is_empty() {
+            self.notify("nothing alive to find the period of".to_string());
+            return;
+        }
+
+        let after = |start: u64| if start == 0 { String::new() } else { format!(" after {} generations", start) };
+        let report = match cycle::classify(&pattern, self.transition(), PERIOD_SEARCH_GENERATIONS) {
+            Some(Behavior::Dies { generation }) => format!("dies out after {} generations", generation),
+            Some(Behavior::Oscillates { start, period: 1 }) => format!("still life{}", after(start)),
+            Some(Behavior::Oscillates { start, period }) => format!("period {} oscillator{}", period, after(start)),
+            Some(Behavior::Travels { start, period, displacement: (dx, dy) }) => {
+                format!("spaceship moving ({}, {}) every {} generations{}", dx, dy, period, after(start))
+            }
+            None => format!("no period within {} generations", PERIOD_SEARCH_GENERATIONS),
+        };
+        self.notify(report);
+    }
+
+    /// Start or stop highlighting the cells that differ from the snapshot.
+    fn toggle_diff(&mut self) {
+        let (generation, snapshot) = match &self.snapshot {
+            Some(snapshot) => snapshot,
+            None => {
+                self.notify("no snapshot to compare with yet".to_string());
+                return;
+            }
+        };
+
+        self.overlays.show_diff = !self.overlays.show_diff;
+        if self.overlays.show_diff {
+            let (born, died) = board::diff(snapshot.as_ref(), self.board.as_ref());
+            let report = format!("{} cells born and {} died since the snapshot at generation {}", born.len(), died.len(), generation);
+            self.notify(report);
+        }
+    }
+
+    /// Search for cells that turn into the selection in a generation, and pick
+    /// them up to be placed if there are any.
+    fn find_predecessor(&mut self) {
+        let target = match &self.selection {
+            Some(selection) => Pattern::capture(self.board.as_ref(), selection),
+            None => {
+                self.notify("select a region to find a predecessor of".to_string());
+                return;
+            }
+        };
+        if target.width > PREDECESSOR_MAX_SIZE || target.height > PREDECESSOR_MAX_SIZE {
+            self.notify(format!("select at most {0}x{0} cells to find a predecessor of", PREDECESSOR_MAX_SIZE));
+            return;
+        }
+
+        match predecessor::find_predecessor(&target, self.transition(), PREDECESSOR_SEARCH_STEPS) {
+            Search::Found(pattern) => {
+                self.notify(format!("found a {}-cell predecessor; click to place it", pattern.cells.len()));
+                self.placement = Some(Placement { pattern, sticky: false, name: None });
+            }
+            Search::NoPredecessor => {
+                self.notify("no predecessor within bounds: nothing becomes the selection".to_string());
+                self.unlock("eden");
+            }
+            Search::GaveUp => self.notify(format!("no predecessor found in {} steps", PREDECESSOR_SEARCH_STEPS)),
+        }
+    }
+
+    /// Run `factor` times as many generations per turbo frame, turning turbo
+    /// on if it wasn't.
+    fn scale_turbo(&mut self, factor: f32) {
+        let generations = (self.turbo_generations as f32 * factor).round() as u32;
+        self.turbo_generations = generations.clamp(1, MAX_TURBO_GENERATIONS);
+        self.turbo = true;
+    }
+
+    fn perform_menu_action(&mut self, ctx: &mut Context, action: Action) {
+        let items = self.menu_items().len();
+
+        match action {
+            Action::CursorUp => self.menu_index = (self.menu_index + items - 1) % items,
+            Action::CursorDown => self.menu_index = (self.menu_index + 1) % items,
+
+            Action::CursorLeft if self.mode == Mode::Settings => self.adjust_setting(false),
+            Action::CursorRight if self.mode == Mode::Settings => self.adjust_setting(true),
+
+            Action::CursorToggle => match (self.mode, self.menu_index) {
+                (Mode::Start, 0) => self.mode = Mode::Editor,
+                (Mode::Start, 1) => self.start_tutorial(),
+                (Mode::Start, 2) => self.next_puzzle(),
+                (Mode::Start, 3) => self.toggle_time_attack(),
+                (Mode::Start, 4) => self.toggle_quiz(),
+                (Mode::Start, 5) => self.next_hole(),
+                (Mode::Start, 6) => self.toggle_versus(),
+                (Mode::Start, 7) => self.toggle_soup_search(),
+                (Mode::Start, _) => event::quit(ctx),
+                (Mode::MainMenu, 0) => self.mode = Mode::Editor,
+                (Mode::MainMenu, 1) => self.open_menu(Mode::PatternBrowser),
+                (Mode::MainMenu, 2) => self.open_menu(Mode::Settings),
+                (Mode::MainMenu, _) => event::quit(ctx),
+                (Mode::PatternBrowser, index) => {
+                    self.mode = Mode::Editor;
+                    self.select_library_pattern(index);
+                },
+                (Mode::Settings, _) => self.adjust_setting(true),
+                (Mode::Editor, _) | (Mode::Running, _) => {},
+            },
+
+            Action::Cancel => match self.mode {
+                Mode::Start | Mode::MainMenu => self.mode = Mode::Editor,
+                _ => self.open_menu(Mode::MainMenu),
+            },
+
+            _ => {},
+        }
+    }
+
+    /// Dim the board and list the current menu's entries, highlighting the selected one.
+    fn draw_menu(&self, ctx: &mut Context) -> GameResult<()> {
+        let theme = THEMES[self.theme_index];
+        let screen = graphics::screen_coordinates(ctx);
+        let backdrop = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            screen,
+            Palette::translucent(theme.hud_background, 0.8),
+            )?;
+        graphics::draw(ctx, &backdrop, (ggez::mint::Point2 { x: 0.0, y: 0.0 },))?;
+
+        let title = match self.mode {
+            Mode::PatternBrowser => "Patterns",
+            Mode::Settings => "Settings",
+            _ => "Life",
+        };
+
+        let mut menu = graphics::Text::new(
+            graphics::TextFragment::new(format!("{}\n\n", title))
+                .color(theme.hud_text.into())
+                .scale(graphics::Scale::uniform(32.0)),
+        );
+        for (index, item) in self.menu_items().into_iter().enumerate() {
+            let color = if index == self.menu_index { PALETTES[self.palette_index].cell } else { theme.hud_text };
+            menu.add(graphics::TextFragment::new(format!("{}\n", item)).color(color.into()).scale(graphics::Scale::uniform(24.0)));
+        }
+
+        let dest = ggez::mint::Point2 {
+            x: screen.x + (screen.w - menu.width(ctx) as f32) / 2.0,
+            y: screen.y + (screen.h - menu.height(ctx) as f32) / 2.0,
+        };
+        graphics::draw(ctx, &menu, (dest,))
+    }
+
+    fn perform_board_action(&mut self, action: Action) {
+        match action {
+            Action::Copy => {
+                if let Some(selection) = &self.selection {
+                    self.clipboard = Some(Pattern::capture(self.board.as_ref(), selection));
+                }
+            },
+
+            Action::Cut => {
+                if let Some(selection) = &self.selection {
+                    self.clipboard = Some(Pattern::capture(self.board.as_ref(), selection));
+                    self.history.record(self.board.as_ref());
+                    board::set_region(self.board.as_mut(), selection, true);
+                }
+            },
+
+            Action::Paste => {
+                self.placement = self.clipboard.clone().map(|pattern| Placement { pattern, sticky: false, name: None });
+            },
+
+            Action::StampMode => {
+                self.select_library_pattern(self.library_index);
+            },
+
+            Action::StampSelection => {
+                if let Some(selection) = &self.selection {
+                    self.placement = Some(Placement {
+                        pattern: Pattern::capture(self.board.as_ref(), selection),
+                        sticky: true,
+                        name: None,
+                    });
+                }
+            },
+
+            Action::NextPattern => {
+                if self.placement.is_some() {
+                    self.select_library_pattern(self.library_index + 1);
+                }
+            },
+
+            Action::PreviousPattern => {
+                if self.placement.is_some() {
+                    self.select_library_pattern(self.library_index + library::PATTERNS.len() - 1);
+                }
+            },
+
+            Action::NextBrush => {
+                self.brush_index = (self.brush_index + 1) % BRUSHES.len();
+                self.notify(format!("painting with a {} brush", BRUSHES[self.brush_index].name()));
+            },
+
+            Action::PreviousBrush => {
+                self.brush_index = (self.brush_index + BRUSHES.len() - 1) % BRUSHES.len();
+                self.notify(format!("painting with a {} brush", BRUSHES[self.brush_index].name()));
+            },
+
+            Action::NextSymmetry => {
+                self.symmetry_index = (self.symmetry_index + 1) % SYMMETRIES.len();
+                self.notify(format!("painting with {:?} symmetry", SYMMETRIES[self.symmetry_index]));
+            },
+
+            Action::CursorLeft => self.move_cursor(-1, 0),
+            Action::CursorRight => self.move_cursor(1, 0),
+            Action::CursorUp => self.move_cursor(0, -1),
+            Action::CursorDown => self.move_cursor(0, 1),
+
+            Action::CursorToggle => {
+                if let Some(pos) = self.cursor {
+                    if matches!(self.followers.game, GameMode::Versus(_)) {
+                        self.place_versus_cell(pos);
+                    } else if self.placement.is_some() {
+                        self.stamp_placement(pos);
+                    } else {
+                        self.history.record(self.board.as_ref());
+                        self.toggle_cell(pos, false);
+                    }
+                }
+            },
+
+            Action::ToggleHelp => {},
+
+            Action::Randomize => {
+                self.history.record(self.board.as_ref());
+                let whole_board = Selection::whole_board(self.board.size());
+                board::fill_symmetric(self.board.as_mut(), &whole_board, self.density, SOUP_SYMMETRIES[self.soup_symmetry_index], &mut self.rng);
+            },
+
+            Action::RandomizeSelection => {
+                if let Some(selection) = &self.selection {
+                    self.history.record(self.board.as_ref());
+                    board::fill_symmetric(self.board.as_mut(), selection, self.density, SOUP_SYMMETRIES[self.soup_symmetry_index], &mut self.rng);
+                }
+            },
+
+            Action::Reseed => self.reseed(),
+
+            Action::ToggleTheme => {
+                self.theme_index = if self.theme_index == LIGHT_THEME { DARK_THEME } else { LIGHT_THEME };
+            },
+
+            Action::Faster => self.updates_per_second = Self::next_speed(self.updates_per_second, true),
+            Action::Slower => self.updates_per_second = Self::next_speed(self.updates_per_second, false),
+
+            Action::FindPeriod => self.find_period(),
+            Action::FindPredecessor => self.find_predecessor(),
+            Action::ShowLeaderboard => self.show_leaderboard(),
+            Action::ToggleActivity => self.toggle_activity(),
+            Action::ToggleVersus => self.toggle_versus(),
+            Action::NextPuzzle => self.next_puzzle(),
+            Action::QuitPuzzle => {
+                if matches!(self.followers.game, GameMode::Puzzle(_)) {
+                    self.followers.game = GameMode::Free;
+                    self.notify("left the puzzle".to_string());
+                }
+            }
+            Action::NextHole => self.next_hole(),
+            Action::QuitGolf => {
+                if matches!(self.followers.game, GameMode::Golf(_)) {
+                    self.followers.game = GameMode::Free;
+                    self.notify("left life golf".to_string());
+                }
+            }
+            Action::ToggleSound => {
+                self.followers.sound_effects.enabled = !self.followers.sound_effects.enabled;
+                self.notify(format!("sound effects {}", if self.followers.sound_effects.enabled { "on" } else { "off" }));
+            }
+            Action::ToggleBoardMusic => {
+                self.followers.sound_effects.music = !self.followers.sound_effects.music;
+                self.notify(format!("playing the board as music {}", if self.followers.sound_effects.music { "on" } else { "off" }));
+            }
+            // the music plays on over the menus, so these come before them
+            Action::ToggleMusic | Action::NextTrack => {}
+            Action::ToggleRuleEditor => self.overlays.rule_editor = !self.overlays.rule_editor,
+            Action::NewLayer => self.new_layer(),
+            Action::NextLayer => self.next_layer(),
+            Action::ToggleLayerVisible => self.toggle_layer_visible(),
+            Action::MergeLayer => self.merge_layer(),
+            Action::LabelCell => self.start_label(),
+            Action::ToggleFreeze => self.toggle_freeze(),
+            Action::ThawAll => self.thaw_all(),
+            Action::CycleWallPaint => self.cycle_wall_paint(),
+            Action::ClearWalls => self.clear_walls(),
+            Action::WarpToGeneration => self.toggle_warp(),
+            Action::SaveBoard => self.save_board(),
+            Action::PlaceRPentomino => self.place_methuselah(0),
+            Action::PlaceAcorn => self.place_methuselah(1),
+            Action::PlaceDiehard => self.place_methuselah(2),
+            Action::PlaceBHeptomino => self.place_methuselah(3),
+            Action::ToggleGhost => {
+                self.overlays.ghost = !self.overlays.ghost;
+                self.notify(format!("previewing the next step while paused {}", if self.overlays.ghost { "on" } else { "off" }));
+            }
+            Action::ToggleInspect => {
+                self.overlays.inspect = !self.overlays.inspect;
+                self.notify(if self.overlays.inspect { "inspecting cells: hover over one, or click to show its neighborhood" } else { "stopped inspecting cells" }.to_string());
+            }
+            Action::ToggleRecording => self.toggle_recording(),
+            Action::PlayReplay => self.toggle_playback(),
+            Action::CopyShareCode => self.copy_share_code(),
+            Action::PasteShareCode => self.paste_share_code(),
+            Action::ShowAchievements => self.show_achievements(),
+            Action::ToggleTimeAttack => self.toggle_time_attack(),
+            Action::ToggleQuiz => self.toggle_quiz(),
+            Action::Answer1 => self.answer_quiz(0),
+            Action::Answer2 => self.answer_quiz(1),
+            Action::Answer3 => self.answer_quiz(2),
+            Action::Answer4 => self.answer_quiz(3),
+            Action::ExportReport => match self.save_report() {
+                Ok(()) => self.notify(format!("saved a report to {}", self.report.display())),
+                Err(err) => self.notify(format!("couldn't write {}: {}", self.report.display(), err)),
+            },
+            Action::ToggleSoupSearch => self.toggle_soup_search(),
+            Action::ToggleStats => self.overlays.show_stats = !self.overlays.show_stats,
+            Action::ToggleBounds => self.overlays.show_bounds = !self.overlays.show_bounds,
+            Action::TakeSnapshot => {
+                self.snapshot = Some((self.generation, self.board.box_clone()));
+                self.notify(format!("took a snapshot at generation {}", self.generation));
+            }
+            Action::ToggleDiff => self.toggle_diff(),
+            Action::Census => {
+                let census = census::census(self.board.as_ref());
+                self.notify(census::summary(&census, CENSUS_NOTICE_KINDS));
+            }
+
+            Action::ToggleTurbo => self.turbo = !self.turbo,
+            Action::TurboFaster => self.scale_turbo(2.0),
+            Action::TurboMuchFaster => self.scale_turbo(10.0),
+            Action::TurboSlower => self.scale_turbo(0.5),
+            Action::TurboMuchSlower => self.scale_turbo(0.1),
+
+            Action::Invert => {
+                self.history.record(self.board.as_ref());
+                for pos in Selection::whole_board(self.board.size()).positions() {
+                    let alive = self.board.is_alive(pos);
+                    self.board.set_alive(pos, !alive);
+                }
+            },
+
+            Action::IncreaseDensity => {
+                self.adjust_density(true);
+                self.notify(format!("random fill density {:.0}%", self.density * 100.0));
+            },
+
+            Action::DecreaseDensity => {
+                self.adjust_density(false);
+                self.notify(format!("random fill density {:.0}%", self.density * 100.0));
+            },
+
+            Action::ToggleRun => {
+                self.mode = if self.mode == Mode::Running { Mode::Editor } else { Mode::Running };
+            },
+
+            Action::Redo => {
+                self.history.redo(&mut self.board);
+            },
+
+            Action::Undo => {
+                self.history.undo(&mut self.board);
+            },
+
+            Action::Step => {
+                self.mode = Mode::Editor;
+                self.step();
+            },
+
+            Action::StepBack => {
+                self.mode = Mode::Editor;
+                self.step_back(self.rewind_step);
+            },
+
+            Action::IncreaseRewind => {
+                self.rewind_step = (self.rewind_step * 2).min(GENERATION_HISTORY_LIMIT);
+                self.notify(format!("stepping back {} generations at a time", self.rewind_step));
+            },
+
+            Action::DecreaseRewind => {
+                self.rewind_step = (self.rewind_step / 2).max(1);
+                self.notify(format!("stepping back {} generations at a time", self.rewind_step));
+            },
+
+            Action::Reset => {
+                self.history.record(self.board.as_ref());
+                let whole_board = Selection::whole_board(self.board.size());
+                board::set_region(self.board.as_mut(), &whole_board, true);
+                self.generation = 0;
+                self.generations.clear();
+            },
+
+            Action::RotateCounterclockwise => {
+                self.transform_selection(Pattern::rotate_counterclockwise);
+            },
+
+            Action::RotateClockwise => {
+                self.transform_selection(Pattern::rotate_clockwise);
+            },
+
+            Action::FlipHorizontal => {
+                self.transform_selection(Pattern::flip_horizontal);
+            },
+
+            Action::FlipVertical => {
+                self.transform_selection(Pattern::flip_vertical);
+            },
+
+            Action::ToggleSelectMode => {
+                self.select_mode = !self.select_mode;
+            },
+
+            Action::Cancel => {
+                if let Some(drag) = self.drag_move.take() {
+                    let source = drag.source;
+                    self.drop_drag_move(drag, source);
+                    self.mouse_down = false;
+                } else if self.placement.is_some() {
+                    self.placement = None;
+                } else if self.selection.is_some() {
+                    self.selection = None;
+                } else {
+                    self.open_menu(Mode::MainMenu);
+                }
+            },
+
+            Action::ClearSelection => {
+                if let Some(selection) = &self.selection {
+                    self.history.record(self.board.as_ref());
+                    board::set_region(self.board.as_mut(), selection, true);
+                }
+            },
+
+            Action::FillSelection => {
+                if let Some(selection) = &self.selection {
+                    self.history.record(self.board.as_ref());
+                    board::set_region(self.board.as_mut(), selection, false);
+                }
+            },
+
+            Action::CycleOnionSkin => {
+                self.overlays.onion_skin = (self.overlays.onion_skin + 1) % (ONION_SKIN_LAYERS + 1);
+            },
+
+            Action::GrowBoard => self.step_board_size(true),
+
+            Action::ShrinkBoard => self.step_board_size(false),
+
+            Action::NewTab => self.new_tab(),
+            Action::CloseTab => self.close_tab(),
+            Action::NextTab => self.switch_tab((self.tab_index + 1) % (self.tabs.len() + 1)),
+            Action::PreviousTab => self.switch_tab((self.tab_index + self.tabs.len()) % (self.tabs.len() + 1)),
+        }
+    }
+}
+
+impl EventHandler for GameState {
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        self.apply_sticks(ggez::timer::delta(ctx));
+        let (width, height) = self.board.size();
+        self.followers.sound_effects.play(ctx, width as usize * height as usize);
+        // a chord for every generation when they're slow enough to hear, and
+        // for every frame's worth when they aren't
+        let generation_time = if self.turbo { ggez::timer::delta(ctx).as_secs_f32() } else { 1.0 / self.updates_per_second };
+        self.followers.sound_effects.play_board(ctx, self.board.as_ref(), self.generation, generation_time);
+        self.music.update(ctx);
+        // a sketch is the board for now, but not for anyone else
+        if !self.is_sketching() {
+            self.advance_playback();
+            self.observe_recording();
+            self.sync_network();
+            self.sync_broadcast();
+        }
+
+        // a warp steps as fast as it can, leaving the run to wait until it's done
+        if self.warp.is_some() {
+            self.advance_warp();
+            return Ok(());
+        }
+
+        // the clock running out starts the run with whatever's been placed
+        if self.attack_time_left().is_some_and(|left| left.is_zero()) && self.mode == Mode::Editor {
+            self.mode = Mode::Running;
+        }
+
+        if self.turbo {
+            if self.mode == Mode::Running && self.until_next_frame().is_zero() {
+                // stop short if the run pauses itself
+                for _ in 0..self.turbo_generations {
+                    if self.mode != Mode::Running {
+                        break;
+                    }
+                    self.run_step();
+                }
+            }
+            self.last_update = Instant::now();
+            return Ok(());
+        }
+
+        // a fixed timestep: the board steps on its own schedule however often
+        // frames come, with a slow frame made up for by stepping more next time
+        let step_time = Duration::from_secs_f32(1.0 / self.updates_per_second);
+        let mut steps = 0;
+        while Instant::now() - self.last_update >= step_time {
+            if steps == MAX_CATCH_UP_STEPS {
+                self.last_update = Instant::now();
+                break;
+            }
+            if self.mode == Mode::Running {
+                self.run_step();
+                steps += 1;
+            }
+            self.last_update += step_time;
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        // between frames, wait for whichever comes first of the next frame and the next step
+        let until_frame = self.until_next_frame();
+        if !until_frame.is_zero() {
+            let until_step = Duration::from_secs_f32(1.0 / self.updates_per_second).saturating_sub(self.last_update.elapsed());
+            ggez::timer::sleep(if self.turbo { until_frame } else { until_frame.min(until_step) });
+            return Ok(());
+        }
+        self.last_draw = Instant::now();
+
+        graphics::clear(ctx, THEMES[self.theme_index].background.into());
+
+        self.draw_board(ctx)?;
+
+        if self.mode.shows_board() {
+            self.draw_labels(ctx)?;
+            self.draw_run_indicator(ctx)?;
+            if self.overlays.show_stats {
+                self.draw_stats(ctx)?;
+            }
+            if let GameMode::Puzzle(run) = &self.followers.game {
+                self.draw_puzzle_target(ctx, &self.puzzles[run.index])?;
+            }
+            if matches!(self.followers.game, GameMode::Attack(_)) {
+                self.draw_high_scores(ctx)?;
+            }
+            if let GameMode::Quiz(quiz) = &self.followers.game {
+                self.draw_quiz_choices(ctx, quiz)?;
+            }
+            if self.overlays.rule_editor {
+                RuleEditor::draw(ctx, &self.viewport, self.rule, &THEMES[self.theme_index], PALETTES[self.palette_index].cell)?;
+            }
+            if let Some(prompt) = match &self.followers.game {
+                GameMode::Tutorial(tutorial) => tutorial.prompt(&self.key_bindings),
+                _ => None,
+            } {
+                self.draw_tutorial_prompt(ctx, &prompt)?;
+            }
+        }
+        self.draw_status_bar(ctx)?;
+        self.draw_warp(ctx)?;
+        self.draw_notice(ctx)?;
+        self.draw_toasts(ctx)?;
+
+        if self.shows_timeline() {
+            let (frames, showing) = match &self.scrub {
+                Some(scrub) => (scrub.frames.len(), scrub.showing),
+                None => (self.generations.len() + 1, self.generations.len()),
+            };
+            let label = self.scrub.as_ref().map(|_| self.generation);
+            Timeline::draw(ctx, &self.viewport, frames, showing, label, &THEMES[self.theme_index], PALETTES[self.palette_index].cell)?;
+        }
+
+        if self.viewport.is_cropped() {
+            Minimap::draw(ctx, &self.viewport, self.board.as_ref(), &THEMES[self.theme_index], PALETTES[self.palette_index].cell)?;
+        }
+
+        if !self.mode.shows_board() {
+            self.draw_menu(ctx)?;
+        }
+
+        if self.overlays.show_help {
+            self.draw_help(ctx)?;
+        }
+
+        graphics::present(ctx)?;
+        ggez::timer::yield_now();
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, ctx: &mut Context, keycode: KeyCode, keymod: KeyMods, _repeat: bool) {
+        // shift is held rather than pressed, switching painting over to erasing
+        if keycode == KeyCode::LShift {
+            self.lshift_pressed = true;
+        } else if let Some(entry) = &mut self.entry {
+            match keycode {
+                KeyCode::Return | KeyCode::NumpadEnter => self.finish_entry(),
+                KeyCode::Escape => self.entry = None,
+                KeyCode::Back => {
+                    let (Entry::Label(_, text) | Entry::Generation(text)) = entry;
+                    text.pop();
+                }
+                _ => {}
+            }
+        } else if keycode == KeyCode::Escape && self.warp.is_some() {
+            self.cancel_warp();
+        } else if let Some(action) = self.key_bindings.action(keycode, keymod) {
+            self.perform(ctx, action);
+        }
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) {
+        match &mut self.entry {
+            Some(Entry::Label(_, text)) if !character.is_control() => text.push(character),
+            Some(Entry::Generation(text)) if character.is_ascii_digit() => text.push(character),
+            _ => {}
+        }
+    }
+
+    fn key_up_event(&mut self, _ctx: &mut Context, keycode: KeyCode, _keymod: KeyMods) {
+        if keycode == KeyCode::LShift {
+            self.lshift_pressed = false;
+        }
+    }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, _x: f32, _y: f32) {
+        self.cells_dirty = true;
+        if button != MouseButton::Left {
+            self.panning = false;
+            return;
+        }
+
+        self.mouse_down = false;
+        self.finish_scrub();
+
+        if let Some(drag) = self.drag_move.take() {
+            let destination = drag.destination;
+            self.drop_drag_move(drag, destination);
+        }
+    }
+
+    fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        self.cells_dirty = true;
+        self.cycles.clear();
+        if !self.mode.shows_board() {
+            return;
+        }
+
+        // other buttons drag the view around
+        if button != MouseButton::Left {
+            self.panning = true;
+            return;
+        }
+
+        if let Some((grid_x, grid_y)) = Minimap::grid_point(&self.viewport, x, y) {
+            self.viewport.center_on(grid_x, grid_y);
+            return;
+        }
+
+        if self.overlays.rule_editor && RuleEditor::rect(&self.viewport).contains(ggez::mint::Point2 { x, y }) {
+            if let Some((alive, neighbors)) = RuleEditor::button_at(&self.viewport, x, y) {
+                self.toggle_rule_count(alive, neighbors);
+            }
+            return;
+        }
+
+        if self.shows_timeline() && Timeline::rect(&self.viewport).contains(ggez::mint::Point2 { x, y }) {
+            if self.is_joined() || self.playback.is_some() {
+                self.notify("the board can't be taken back while someone else runs it".to_string());
+            } else {
+                self.start_scrub(x);
+            }
+            return;
+        }
+
+        if self.overlays.inspect {
+            if let Some(pos) = self.viewport.grid_position(x, y) {
+                let inspection = self.inspection(pos);
+                self.notify(inspection);
+            }
+            return;
+        }
+
+        self.mouse_down = true;
+
+        if matches!(self.followers.game, GameMode::Versus(_)) {
+            if let Some(pos) = self.viewport.grid_position(x, y) {
+                self.place_versus_cell(pos);
+            }
+            // one cell a turn, however far the mouse goes
+            self.mouse_down = false;
+        } else if self.placement.is_some() {
+            if let Some(pos) = self.viewport.grid_position(x, y) {
+                self.stamp_placement(pos);
+            }
+            // don't let the rest of this drag paint over the stamped cells
+            self.mouse_down = false;
+        } else if self.select_mode {
+            let pos = self.viewport.grid_position(x, y);
+
+            match (self.selection, pos) {
+                (Some(selection), Some(pos)) if selection.contains(pos) => self.start_drag_move(selection, pos),
+                _ => self.selection = pos.map(Selection::new),
+            }
+        } else if let Some(pos) = self.viewport.grid_position(x, y) {
+            // one undo step covers the whole brush stroke
+            self.history.record(self.board.as_ref());
+            self.toggle_cell(pos, false);
+        }
+    }
+
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, xrel: f32, yrel: f32) {
+        if self.panning {
+            self.viewport.pan(xrel, yrel);
+        }
+
+        self.cursor = self.viewport.grid_position(x, y);
+        self.keyboard_cursor = false;
+
+        if self.scrub.is_some() {
+            self.scrub_to(x);
+        } else if self.mouse_down {
+            if let Some(drag) = &mut self.drag_move {
+                if let Some(pos) = self.cursor {
+                    drag.follow(pos);
+                }
+            } else if self.select_mode {
+                if let (Some(selection), Some(pos)) = (&mut self.selection, self.cursor) {
+                    selection.end = pos;
+                }
+            } else if let Some(pos) = self.cursor {
+                self.toggle_cell(pos, true);
+            }
+        }
+    }
+
+    fn mouse_wheel_event(&mut self, ctx: &mut Context, _x: f32, y: f32) {
+        if self.mode.shows_board() && y != 0.0 {
+            let pos = ggez::input::mouse::position(ctx);
+            let factor = if y > 0.0 { ZOOM_STEP } else { 1.0 / ZOOM_STEP };
+            self.viewport.zoom_at(pos.x, pos.y, factor);
+        }
+    }
+
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        // keep one screen unit per pixel so the grid isn't stretched with the window
+        graphics::set_screen_coordinates(ctx, graphics::Rect::new(0.0, 0.0, width, height))
+            .expect("failed to update screen coordinates");
+        self.viewport.scale_factor = graphics::window(ctx).get_hidpi_factor() as f32;
+        self.viewport.fit(width, height - STATUS_BAR_HEIGHT);
+    }
+}
+
+impl InputHandler for GameState {
+    /// A tap toggles a cell like a click, dragging one finger pans and
+    /// pinching with two zooms about the point between them.
+    fn touch_event(&mut self, ctx: &mut Context, id: u64, phase: TouchPhase, x: f32, y: f32) {
+        match phase {
+            TouchPhase::Started => {
+                self.touches.insert(id, (x, y));
+                // a second finger means a pinch, never a tap
+                self.tap = if self.touches.len() == 1 { Some((x, y)) } else { None };
+            }
+            TouchPhase::Moved => {
+                let previous = match self.touches.insert(id, (x, y)) {
+                    Some(previous) => previous,
+                    None => return,
+                };
+
+                if !self.mode.shows_board() {
+                    return;
+                }
+
+                if let Some((start_x, start_y)) = self.tap {
+                    if (x - start_x).hypot(y - start_y) < TAP_SLOP {
+                        return;
+                    }
+                    self.tap = None;
+                    self.viewport.pan(x - start_x, y - start_y);
+                    return;
+                }
+
+                let other = self.touches.iter().find(|(other_id, _)| **other_id != id).map(|(_, position)| *position);
+                match other {
+                    None => self.viewport.pan(x - previous.0, y - previous.1),
+                    Some((other_x, other_y)) => {
+                        let before = (previous.0 - other_x).hypot(previous.1 - other_y);
+                        let after = (x - other_x).hypot(y - other_y);
+                        let midpoint = ((previous.0 + other_x) / 2.0, (previous.1 + other_y) / 2.0);
+
+                        if before > 0.0 {
+                            self.viewport.zoom_at(midpoint.0, midpoint.1, after / before);
+                        }
+                        self.viewport.pan((x - previous.0) / 2.0, (y - previous.1) / 2.0);
+                    }
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.touches.remove(&id);
+
+                if let Some((tap_x, tap_y)) = self.tap.take() {
+                    if phase == TouchPhase::Ended {
+                        self.mouse_button_down_event(ctx, MouseButton::Left, tap_x, tap_y);
+                        self.mouse_button_up_event(ctx, MouseButton::Left, tap_x, tap_y);
+                    }
+                }
+            }
+        }
+    }
+
+    fn gamepad_button(&mut self, ctx: &mut Context, btn: Button) {
+        if let Some(action) = gamepad_action(btn) {
+            self.perform(ctx, action);
+        }
+    }
+
+    fn gamepad_axis(&mut self, _ctx: &mut Context, axis: Axis, value: f32) {
+        match axis {
+            Axis::LeftStickX => self.left_stick.0 = value,
+            Axis::LeftStickY => self.left_stick.1 = value,
+            Axis::RightStickX => self.right_stick.0 = value,
+            Axis::RightStickY => self.right_stick.1 = value,
+            _ => {},
+        }
+    }
+}
+
+/// Bring the cells in `born` to life and kill the ones in `died`, leaving
+/// out any that don't fit on `board`.
+fn apply_changes(board: &mut dyn Board, born: &[GridPosition], died: &[GridPosition]) {
+    let (width, height) = board.size();
+    for (cells, alive) in &[(born, true), (died, false)] {
+        for &pos in cells.iter() {
+            if pos.x >= 0 && pos.x < width && pos.y >= 0 && pos.y < height {
+                board.set_alive(pos, *alive);
+            }
+        }
+    }
+}
+
+/// Run the simulation without a window, then save the live part of the board
+/// as RLE and print some stats.
+fn run_headless(options: &Options, pattern: Option<&Pattern>, generations: u64) -> Result<(), String> {
+    let mut state = GameState::new(options, KeyBindings::default());
+    if let Some(path) = &options.stats {
+        state.log_stats(path).map_err(|err| format!("couldn't write {}: {}", path.display(), err))?;
+    }
+    if let (Some(pattern), Some(path)) = (pattern, &options.pattern) {
+        state.open_pattern(path, pattern);
+    }
+
+    if let Some(address) = &options.broadcast {
+        state.start_broadcast(address).map_err(|err| format!("couldn't broadcast on {}: {}", address, err))?;
+    }
+
+    let started = Instant::now();
+    let mut broadcast_at = started;
+    for _ in 0..generations {
+        state.step();
+        if state.broadcast.is_some() && broadcast_at.elapsed() >= HEADLESS_BROADCAST_INTERVAL {
+            state.sync_broadcast();
+            broadcast_at = Instant::now();
+        }
+    }
+    state.sync_broadcast();
+    let elapsed = started.elapsed().as_secs_f32();
+
+    let pattern = Pattern::capture_live(state.board.as_ref());
+    let output = options.output.clone().unwrap_or_else(|| "final.rle".into());
+    std::fs::write(&output, state.board_rle()).map_err(|err| format!("couldn't write {}: {}", output.display(), err))?;
+
+    println!("seed: {}", state.seed);
+    println!("rule: {}", state.rule);
+    println!("generations: {}", state.generation);
+    println!("population: {}", state.population());
+    println!("bounding box: {}x{}", pattern.width, pattern.height);
+    println!("census: {}", census::summary(&census::census(state.board.as_ref()), usize::MAX));
+    println!("time: {:.2}s ({:.0} generations per second)", elapsed, generations as f32 / elapsed.max(f32::EPSILON));
+    println!("saved the board to {}", output.display());
+
+    if options.report.is_some() {
+        state.save_report().map_err(|err| format!("couldn't write {}: {}", state.report.display(), err))?;
+        println!("saved a report to {}", state.report.display());
+    }
+    Ok(())
+}
+
+/// Run the script at `path` against its own universes instead of starting the game.
+#[cfg(feature = "scripting")]
+fn run_script(options: &Options, path: &std::path::Path) -> Result<(), String> {
+    let source = std::fs::read_to_string(path).map_err(|err| format!("couldn't read {}: {}", path.display(), err))?;
+    let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    println!("seed {} (start with --seed {} to repeat this run)", seed, seed);
+
+    life::script::run(&source, seed).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+#[cfg(not(feature = "scripting"))]
+fn run_script(_options: &Options, _path: &std::path::Path) -> Result<(), String> {
+    Err("this build can't run scripts, rebuild it with `--features scripting`".to_string())
+}
+
+/// A universe set up from the options, for the frontends that don't use
+/// `GameState`, along with the RNG for later soups and its seed.
+#[cfg(any(feature = "tui", feature = "pixel-buffer"))]
+fn start_universe(options: &Options, pattern: Option<&Pattern>) -> (life::universe::Universe, StdRng, u64) {
+    let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut universe = life::universe::Universe::new(options.grid_size);
+    universe.set_rule(options.rule);
+    if let Some(density) = options.density {
+        board::fill_symmetric(universe.board_mut(), &Selection::whole_board(options.grid_size), density, options.symmetry, &mut rng);
+    }
+    if let Some(pattern) = pattern {
+        universe.place_centered(pattern);
+    }
+
+    (universe, rng, seed)
+}
+
+#[cfg(feature = "tui")]
+fn run_tui(options: &Options, pattern: Option<&Pattern>) -> Result<(), String> {
+    tui::run(options, pattern)
+}
+
+#[cfg(not(feature = "tui"))]
+fn run_tui(_options: &Options, _pattern: Option<&Pattern>) -> Result<(), String> {
+    Err("this build has no terminal frontend, rebuild it with `--features tui`".to_string())
+}
+
+#[cfg(feature = "pixel-buffer")]
+fn run_pixel_buffer(options: &Options, pattern: Option<&Pattern>) -> Result<(), String> {
+    pixel_buffer::run(options, pattern)
+}
+
+#[cfg(not(feature = "pixel-buffer"))]
+fn run_pixel_buffer(_options: &Options, _pattern: Option<&Pattern>) -> Result<(), String> {
+    Err("this build has no pixel buffer frontend, rebuild it with `--features pixel-buffer`".to_string())
+}
+
+/// Where the config file goes, in the user config directory.
+fn config_file() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", AUTHOR, GAME_ID).map(|dirs| dirs.config_dir().join(options::CONFIG_FILE))
+}
+
+fn main() -> GameResult {
+    let config = config_file().map_or_else(Options::default, |path| Options::load(&path));
+
+    let options = match config.parse(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", options::USAGE);
+            return Ok(());
+        }
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, options::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let pattern = options.pattern.as_ref().map(|path| Pattern::load(path)).transpose().unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        std::process::exit(1);
+    });
+    let puzzles = options.puzzles.as_ref().map_or(Ok(Vec::new()), |path| Puzzle::load(path)).unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        std::process::exit(1);
+    });
+
+    if let Some(path) = &options.script {
+        if let Err(message) = run_script(&options, path) {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if options.tui || options.pixel_buffer {
+        let run = if options.tui { run_tui } else { run_pixel_buffer };
+        if let Err(message) = run(&options, pattern.as_ref()) {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if options.headless {
+        let generations = options.generations.unwrap_or_else(|| {
+            eprintln!("--headless needs --generations\n\n{}", options::USAGE);
+            std::process::exit(2);
+        });
+
+        if let Err(message) = run_headless(&options, pattern.as_ref(), generations) {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let (width, height) = GameState::window_size(&options);
+    let (ctx, events_loop) = &mut ggez::ContextBuilder::new(GAME_ID, AUTHOR)
+        .window_setup(ggez::conf::WindowSetup::default().title("Life"))
+        .window_mode(
+            ggez::conf::WindowMode::default()
+                .dimensions(width, height)
+                .resizable(true),
+            )
+        .build()?;
+
+    let key_bindings = KeyBindings::load(ctx, &options.key_bindings);
+    let state = &mut GameState::new(&options, key_bindings);
+    state.puzzles.extend(puzzles);
+    let network = match (&options.host, &options.join, &options.watch) {
+        (Some(address), _, _) => state.host(address).map_err(|err| format!("couldn't host on {}: {}", address, err)),
+        (None, Some(address), _) => state.join(address).map_err(|err| format!("couldn't join {}: {}", address, err)),
+        (None, None, Some(address)) => state.watch(address).map_err(|err| format!("couldn't watch {}: {}", address, err)),
+        (None, None, None) => Ok(()),
+    };
+    if let Err(message) = network {
+        eprintln!("{}", message);
+        std::process::exit(1);
+    }
+    if let Some(address) = &options.broadcast {
+        if let Err(err) = state.start_broadcast(address) {
+            eprintln!("couldn't broadcast on {}: {}", address, err);
+            std::process::exit(1);
+        }
+    }
+    if let Some(path) = &options.stats {
+        if let Err(err) = state.log_stats(path) {
+            eprintln!("couldn't write {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+    }
+    if let (Some(pattern), Some(path)) = (&pattern, &options.pattern) {
+        state.open_pattern(path, pattern);
+    }
+    if let Some(path) = &options.replay {
+        state.play_replay(path);
+    } else if options.record.is_some() {
+        state.start_recording();
+    }
+
+    // the window can come up at a different size or scale than asked for
+    let (width, height) = graphics::drawable_size(ctx);
+    state.resize_event(ctx, width, height);
+
+    let result = event_loop::run(ctx, events_loop, state);
+    // for the generations run since the last achievement
+    state.save_achievements();
+    if let Some(recording) = state.followers.recording.take() {
+        state.save_recording(recording);
+    }
+    result
 }
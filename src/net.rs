@@ -0,0 +1,339 @@
+//! Sharing a board over TCP: one game hosts it and steps it, and everyone
+//! who joins sees it change and can paint on it. Messages go a line at a
+//! time, as text:
+//!
+//! ```text
+//! board 200 150 12 4,5 5,5 6,5
+//! changes 13 +5,4 +5,6 -4,5 -6,5
+//! edits +10,10 -4,5
+//! ```
+//!
+//! The host sends the whole board to each game that joins, then the changes
+//! to it every frame, whether from a step or from painting. Games that
+//! joined send the host their edits, which come back to all of them as
+//! changes. Sockets never block, so all of it can go on in the update loop.
+
+use crate::board::{Board, GridPosition};
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+
+/// The port games host on and join unless told otherwise.
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// The longest side a board sent in a message can have, so a bad host
+/// can't make a game allocate more than it would make for itself.
+pub const MAX_BOARD_SIZE: i16 = 2000;
+/// The shortest side a game makes a board with itself, whether resizing it
+/// or setting it up from a share code.
+pub const MIN_BOARD_SIZE: i16 = 16;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    /// The whole board: its size, the generation it's at and its live cells.
+    Board { size: (i16, i16), generation: u64, cells: Vec<GridPosition> },
+    /// How the host's board has changed since it last said, now at `generation`.
+    Changes { generation: u64, born: Vec<GridPosition>, died: Vec<GridPosition> },
+    /// Cells brought to life or killed on a board that joined, for the host to make.
+    Edits { born: Vec<GridPosition>, died: Vec<GridPosition> },
+}
+
+impl Message {
+    /// The message as a line, without the newline.
+    pub fn to_line(&self) -> String {
+        match self {
+            Message::Board { size, generation, cells: live } => format!("board {} {} {}{}", size.0, size.1, generation, cells("", live)),
+            Message::Changes { generation, born, died } => format!("changes {}{}{}", generation, cells("+", born), cells("-", died)),
+            Message::Edits { born, died } => format!("edits{}{}", cells("+", born), cells("-", died)),
+        }
+    }
+
+    /// The whole of `board`, at `generation`.
+    pub fn whole_board(board: &dyn Board, generation: u64) -> Message {
+        Message::Board { size: board.size(), generation, cells: board.live_cells().collect() }
+    }
+
+    pub fn parse(line: &str) -> Result<Message, String> {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("board") => {
+                let size: (i16, i16) = (number(words.next(), line)?, number(words.next(), line)?);
+                if size.0 <= 0 || size.1 <= 0 || size.0 > MAX_BOARD_SIZE || size.1 > MAX_BOARD_SIZE {
+                    return Err(format!("bad board size in {:?}", line));
+                }
+                let generation = number(words.next(), line)?;
+                let cells = words.map(|word| cell(word).ok_or_else(|| format!("bad cell {:?}", word))).collect::<Result<_, _>>()?;
+                Ok(Message::Board { size, generation, cells })
+            }
+            Some("changes") => {
+                let generation = number(words.next(), line)?;
+                let (born, died) = changes(words)?;
+                Ok(Message::Changes { generation, born, died })
+            }
+            Some("edits") => {
+                let (born, died) = changes(words)?;
+                Ok(Message::Edits { born, died })
+            }
+            _ => Err(format!("unknown message {:?}", line)),
+        }
+    }
+}
+
+/// `cells` written as ` x,y` each, with `prefix` before each `x`.
+pub(crate) fn cells(prefix: &str, cells: &[GridPosition]) -> String {
+    cells.iter().map(|pos| format!(" {}{},{}", prefix, pos.x, pos.y)).collect()
+}
+
+/// The number in `word`, which is part of `line`.
+pub(crate) fn number<T: std::str::FromStr>(word: Option<&str>, line: &str) -> Result<T, String> {
+    word.and_then(|word| word.parse().ok()).ok_or_else(|| format!("bad message {:?}", line))
+}
+
+/// A cell written as `x,y`.
+pub(crate) fn cell(word: &str) -> Option<GridPosition> {
+    let (x, y) = word.split_once(',')?;
+    Some(GridPosition::new(x.parse().ok()?, y.parse().ok()?))
+}
+
+/// Cells written as `+x,y` for born and `-x,y` for died.
+pub(crate) fn changes<'a>(words: impl Iterator<Item = &'a str>) -> Result<(Vec<GridPosition>, Vec<GridPosition>), String> {
+    let (mut born, mut died) = (Vec::new(), Vec::new());
+    for word in words {
+        let (cells, pos) = match (word.strip_prefix('+'), word.strip_prefix('-')) {
+            (Some(pos), _) => (&mut born, pos),
+            (_, Some(pos)) => (&mut died, pos),
+            _ => return Err(format!("bad change {:?}", word)),
+        };
+        cells.push(cell(pos).ok_or_else(|| format!("bad change {:?}", word))?);
+    }
+    Ok((born, died))
+}
+
+/// A socket that never blocks, with whatever's been read and not yet used
+/// and whatever's waiting to be written.
+#[derive(Debug)]
+pub(crate) struct Socket {
+    stream: TcpStream,
+    pub incoming: Vec<u8>,
+    outgoing: Vec<u8>,
+    closed: bool,
+}
+
+impl Socket {
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        Ok(Socket { stream, incoming: Vec::new(), outgoing: Vec::new(), closed: false })
+    }
+
+    /// Write `bytes` as soon as the connection will take them.
+    pub fn write(&mut self, bytes: &[u8]) {
+        self.outgoing.extend_from_slice(bytes);
+        self.flush();
+    }
+
+    /// Write as much of what's waiting as the connection will take now.
+    pub fn flush(&mut self) {
+        while !self.outgoing.is_empty() && !self.closed {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => self.closed = true,
+                Ok(written) => {
+                    self.outgoing.drain(..written);
+                }
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => self.closed = true,
+            }
+        }
+    }
+
+    /// Add everything that's arrived to `incoming`, after writing what it can.
+    pub fn read(&mut self) {
+        self.flush();
+        let mut buffer = [0; 4096];
+        while !self.closed {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => self.closed = true,
+                Ok(read) => self.incoming.extend_from_slice(&buffer[..read]),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => self.closed = true,
+            }
+        }
+    }
+
+    /// Whether everything waiting has been written.
+    pub fn is_flushed(&self) -> bool {
+        self.outgoing.is_empty()
+    }
+
+    pub fn close(&mut self) {
+        self.closed = true;
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+/// The address in `address`, like `example.com:7878`, with `port` if it doesn't give one.
+pub(crate) fn resolve(address: &str, port: u16) -> io::Result<SocketAddr> {
+    let address = if address.contains(':') { address.to_string() } else { format!("{}:{}", address, port) };
+    address.to_socket_addrs()?.next().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such address"))
+}
+
+/// A socket listening on `address`, which can be just a port to listen on
+/// every interface, that never blocks.
+pub(crate) fn listen(address: &str) -> io::Result<TcpListener> {
+    let address = if address.contains(':') { address.to_string() } else { format!("0.0.0.0:{}", address) };
+    let listener = TcpListener::bind(address)?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// One end of a connection.
+#[derive(Debug)]
+pub struct Peer {
+    socket: Socket,
+}
+
+impl Peer {
+    /// An address, like `example.com:7878`, with the default port if it doesn't give one.
+    pub fn connect(address: &str) -> io::Result<Self> {
+        Ok(Peer { socket: Socket::new(TcpStream::connect(resolve(address, DEFAULT_PORT)?)?)? })
+    }
+
+    /// Send `message` as soon as the connection will take it.
+    pub fn send(&mut self, message: &Message) {
+        self.socket.write(format!("{}\n", message.to_line()).as_bytes());
+    }
+
+    /// Every message that's arrived whole since last time. Lines that aren't
+    /// messages are left out.
+    pub fn receive(&mut self) -> Vec<Message> {
+        self.socket.read();
+        let mut messages = Vec::new();
+        while let Some(end) = self.socket.incoming.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.socket.incoming.drain(..=end).collect();
+            if let Ok(message) = Message::parse(&String::from_utf8_lossy(&line)) {
+                messages.push(message);
+            }
+        }
+        messages
+    }
+
+    /// Whether the other end has gone.
+    pub fn is_closed(&self) -> bool {
+        self.socket.is_closed()
+    }
+}
+
+/// The hosting end: everyone who's joined, and the socket more can join on.
+#[derive(Debug)]
+pub struct Host {
+    listener: TcpListener,
+    peers: Vec<Peer>,
+}
+
+impl Host {
+    /// Host on `address`, which can be just a port to host on every interface.
+    pub fn bind(address: &str) -> io::Result<Self> {
+        Ok(Host { listener: listen(address)?, peers: Vec::new() })
+    }
+
+    /// The port it's hosting on, which is handy when asked for port 0.
+    pub fn port(&self) -> io::Result<u16> {
+        Ok(self.listener.local_addr()?.port())
+    }
+
+    /// Let in everyone waiting to join, returning how many there were. They
+    /// go on the end of `peers`, so the last that many are the new ones.
+    pub fn accept(&mut self) -> usize {
+        let mut joined = 0;
+        while let Ok((stream, _)) = self.listener.accept() {
+            if let Ok(socket) = Socket::new(stream) {
+                self.peers.push(Peer { socket });
+                joined += 1;
+            }
+        }
+        joined
+    }
+
+    /// How many games have joined and are still there.
+    pub fn peer_count(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn peers(&mut self) -> &mut [Peer] {
+        &mut self.peers
+    }
+
+    /// Send `message` to everyone who's joined.
+    pub fn broadcast(&mut self, message: &Message) {
+        for peer in &mut self.peers {
+            peer.send(message);
+        }
+    }
+
+    /// Every message from everyone, dropping anyone who's gone, and how many that was.
+    pub fn receive(&mut self) -> (Vec<Message>, usize) {
+        let messages = self.peers.iter_mut().flat_map(Peer::receive).collect();
+        let before = self.peers.len();
+        self.peers.retain(|peer| !peer.is_closed());
+        (messages, before - self.peers.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn messages_round_trip_through_lines() {
+        let pos = GridPosition::new;
+        let messages = [
+            Message::Board { size: (20, 10), generation: 12, cells: vec![pos(4, 5), pos(5, 5)] },
+            Message::Changes { generation: 13, born: vec![pos(5, 4)], died: vec![pos(4, 5)] },
+            Message::Edits { born: Vec::new(), died: vec![pos(0, 9)] },
+        ];
+        for message in &messages {
+            assert_eq!(Message::parse(&message.to_line()), Ok(message.clone()));
+        }
+        assert_eq!(messages[1].to_line(), "changes 13 +5,4 -4,5");
+        assert!(Message::parse("changes 13 5,4").is_err());
+        assert!(Message::parse("board 20").is_err());
+        for line in &["board -5 0 0", "board 0 0 0", "board 20 2001 0"] {
+            assert!(Message::parse(line).is_err(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn hosts_and_joiners_talk() {
+        let mut host = Host::bind("127.0.0.1:0").unwrap();
+        let mut peer = Peer::connect(&format!("127.0.0.1:{}", host.port().unwrap())).unwrap();
+        let edits = Message::Edits { born: vec![GridPosition::new(1, 2)], died: Vec::new() };
+        peer.send(&edits);
+
+        // the sockets don't block, so give everything a moment to arrive
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut joined = 0;
+        let mut received = Vec::new();
+        while received.is_empty() && Instant::now() < deadline {
+            joined += host.accept();
+            received = host.receive().0;
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!((joined, received), (1, vec![edits]));
+
+        let board = Message::Board { size: (3, 3), generation: 0, cells: Vec::new() };
+        host.broadcast(&board);
+        let mut received = Vec::new();
+        while received.is_empty() && Instant::now() < deadline {
+            received = peer.receive();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(received, vec![board]);
+    }
+}
@@ -0,0 +1,421 @@
+//! Startup options, from the config file and then the command line, e.g.
+//! `life --grid 300x200 --cell 4 glider.rle`.
+
+use life::board::SoupSymmetry;
+use life::rule::Rule;
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Lives in the user's config directory, next to the key bindings file.
+pub const CONFIG_FILE: &str = "config.toml";
+
+pub const USAGE: &str = "\
+usage: life [options] [pattern file]
+
+options:
+    --grid <width>x<height>    size of the board in cells (default 200x150)
+    --cell <pixels>            starting size of each cell on screen (default 8)
+    --speed <generations>      generations per second (default 20)
+    --fps <frames>             frames drawn per second (default as often as the display refreshes)
+    --turbo <generations>      generations run each frame in turbo mode (default 100)
+    --rule <rule>              rulestring like B36/S23, or a preset name like HighLife
+    --seed <number>            seed for random soups, to make them reproducible
+    --density <fraction>       start from a random soup with this share of live cells
+    --symmetry <symmetry>      make random soups C2, C4, D2 or D4 symmetric
+    --open <file>              start with an .rle or plaintext pattern in the middle
+    --headless                 run without a window, then save the board and print stats
+    --generations <count>      how many generations to run headless
+    --output <file>            where the board is saved, with its labels (default final.rle headless, board.rle otherwise)
+    --results <file>           where soup searches add their results (default soups.tsv)
+    --stats <file>             log population, density and entropy every generation as CSV
+    --report <file>            where to save a JSON report of the session (default report.json)
+    --puzzles <file>           add the puzzles in a puzzle file to the built-in ones
+    --host <port>              share the board with other games joining on this port
+    --join <address>           join a board shared by a game at this address, like 10.0.0.5:7878
+    --broadcast <port>         let other games and browsers watch the board, read only, on this port
+    --watch <address>          watch a board broadcast by a game at this address, like 10.0.0.5:7879
+    --record <file>            record the session from the start, to play back later (default replay.txt)
+    --replay <file>            play back a recorded session
+    --sound                    play ticks for births and deaths
+    --achievements <file>      where achievements are kept (default achievements.txt in the user data directory)
+    --script <file>            run a Rhai script instead of the game (needs the scripting feature)
+    --tui                      play in the terminal instead of a window (needs the tui feature)
+    --pixel-buffer             play in a plain pixel buffer window (needs the pixel-buffer feature)
+    -h, --help                 show this message
+
+Defaults for the grid, cell size, speed, frame rate, turbo, theme, rule,
+seed, density, sound, volume, music volume and key bindings can be set in config.toml in the user config
+directory, e.g.
+
+    grid = \"300x200\"
+    cell = 4
+    speed = 30
+    fps = 30
+    turbo = 1000
+    theme = \"Dark\"
+    rule = \"HighLife\"
+    sound = true
+    volume = 0.3
+    music_volume = 0.5
+
+    [keybindings]
+    toggle_run = \"P\"";
+
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub grid_size: (i16, i16),
+    pub cell_size: f32,
+    pub speed: f32,
+    /// Draw at most this many frames a second, however fast the board steps.
+    pub frame_rate: Option<f32>,
+    /// How many generations turbo mode runs each frame.
+    pub turbo: u32,
+    /// A theme name, checked against the themes when the game starts.
+    pub theme: Option<String>,
+    pub rule: Rule,
+    pub seed: Option<u64>,
+    /// Start from a random soup this dense, instead of an empty board.
+    pub density: Option<f32>,
+    /// How random soups are mirrored or turned onto themselves.
+    pub symmetry: SoupSymmetry,
+    /// Whether births and deaths tick, and how loud, from 0 to 1.
+    pub sound: bool,
+    pub volume: f32,
+    /// How loud the tracks in the music directory play, from 0 to 1.
+    pub music_volume: f32,
+    pub pattern: Option<PathBuf>,
+    pub headless: bool,
+    pub generations: Option<u64>,
+    pub output: Option<PathBuf>,
+    pub results: Option<PathBuf>,
+    pub stats: Option<PathBuf>,
+    pub report: Option<PathBuf>,
+    pub puzzles: Option<PathBuf>,
+    pub achievements: Option<PathBuf>,
+    /// Where to host a shared board, or the address of one to join.
+    pub host: Option<String>,
+    pub join: Option<String>,
+    /// Where to broadcast the board, and the address of a broadcast to watch.
+    pub broadcast: Option<String>,
+    pub watch: Option<String>,
+    /// Where to record the session from the start, and a recorded session to play back.
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub script: Option<PathBuf>,
+    pub tui: bool,
+    pub pixel_buffer: bool,
+    /// Overrides on top of the default key bindings, as in the key bindings file.
+    pub key_bindings: toml::value::Table,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            grid_size: (200, 150),
+            cell_size: 8.0,
+            speed: crate::UPDATES_PER_SECOND,
+            frame_rate: None,
+            turbo: crate::TURBO_GENERATIONS,
+            theme: None,
+            rule: Rule::default(),
+            seed: None,
+            density: None,
+            symmetry: SoupSymmetry::Asymmetric,
+            sound: false,
+            volume: crate::VOLUME,
+            music_volume: crate::VOLUME,
+            pattern: None,
+            headless: false,
+            generations: None,
+            output: None,
+            results: None,
+            stats: None,
+            report: None,
+            puzzles: None,
+            achievements: None,
+            host: None,
+            join: None,
+            broadcast: None,
+            watch: None,
+            record: None,
+            replay: None,
+            script: None,
+            tui: false,
+            pixel_buffer: false,
+            key_bindings: toml::value::Table::new(),
+        }
+    }
+}
+
+impl Options {
+    /// The built-in defaults with anything set in the config file at `path`
+    /// on top. A broken config file is reported and ignored.
+    pub fn load(path: &Path) -> Options {
+        let read = match fs::read_to_string(path) {
+            Ok(contents) => Options::from_config(&contents),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Options::default(),
+            Err(e) => Err(e.to_string()),
+        };
+
+        read.unwrap_or_else(|e| {
+            eprintln!("ignoring {}: {}", path.display(), e);
+            Options::default()
+        })
+    }
+
+    /// Parse the TOML config file.
+    pub fn from_config(contents: &str) -> Result<Options, String> {
+        let table: toml::value::Table = toml::from_str(contents).map_err(|e| e.to_string())?;
+        let mut options = Options::default();
+
+        for (key, value) in table {
+            match key.as_str() {
+                "grid" => {
+                    options.grid_size = value.as_str().and_then(parse_size).ok_or_else(|| format!("`grid` must be a size like \"300x200\", {}", size_range()))?;
+                }
+                "cell" => {
+                    options.cell_size = number(&value).filter(|&size| size >= 1.0).ok_or("`cell` must be a number of pixels")?;
+                }
+                "speed" => {
+                    options.speed = number(&value).filter(|&speed| speed > 0.0).ok_or("`speed` must be a number of generations per second")?;
+                }
+                "fps" => {
+                    options.frame_rate = Some(number(&value).filter(|&fps| fps > 0.0).ok_or("`fps` must be a number of frames per second")?);
+                }
+                "turbo" => {
+                    let turbo = value.as_integer().filter(|turbo| (1..=crate::MAX_TURBO_GENERATIONS as i64).contains(turbo));
+                    options.turbo = turbo.ok_or("`turbo` must be a number of generations per frame")? as u32;
+                }
+                "theme" => options.theme = Some(value.as_str().ok_or("`theme` must be a theme name")?.to_string()),
+                "rule" => {
+                    let rule = value.as_str().ok_or("`rule` must be a rulestring or preset name")?;
+                    options.rule = Rule::from_name_or_rulestring(rule).ok_or(format!("unknown rule `{}`", rule))?;
+                }
+                "seed" => {
+                    options.seed = Some(value.as_integer().filter(|&seed| seed >= 0).ok_or("`seed` must be a whole number")? as u64);
+                }
+                "density" => options.density = Some(number(&value).filter(is_fraction).ok_or("`density` must be a fraction between 0 and 1")?),
+                "sound" => options.sound = value.as_bool().ok_or("`sound` must be true or false")?,
+                "volume" => options.volume = number(&value).filter(is_fraction).ok_or("`volume` must be a fraction between 0 and 1")?,
+                "music_volume" => {
+                    options.music_volume = number(&value).filter(is_fraction).ok_or("`music_volume` must be a fraction between 0 and 1")?;
+                }
+                "keybindings" => match value {
+                    toml::Value::Table(table) => options.key_bindings = table,
+                    _ => return Err("`keybindings` must be a table".to_string()),
+                },
+                _ => return Err(format!("unknown setting `{}`", key)),
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Apply the arguments after the program name. `Ok(None)` means help was asked for.
+    pub fn parse<I: IntoIterator<Item = String>>(mut self, args: I) -> Result<Option<Options>, String> {
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--grid" => {
+                    let value = value()?;
+                    self.grid_size = parse_size(&value).ok_or_else(|| format!("invalid grid size {:?}, expected e.g. 300x200, {}", value, size_range()))?;
+                }
+                "--cell" => {
+                    let value = value()?;
+                    self.cell_size = match value.parse::<f32>() {
+                        Ok(size) if size >= 1.0 => size,
+                        _ => return Err(format!("invalid cell size {:?}, expected a number of pixels", value)),
+                    };
+                }
+                "--speed" => {
+                    let value = value()?;
+                    self.speed = match value.parse::<f32>() {
+                        Ok(speed) if speed > 0.0 => speed,
+                        _ => return Err(format!("invalid speed {:?}, expected a number of generations per second", value)),
+                    };
+                }
+                "--fps" => {
+                    let value = value()?;
+                    self.frame_rate = match value.parse::<f32>() {
+                        Ok(fps) if fps > 0.0 => Some(fps),
+                        _ => return Err(format!("invalid frame rate {:?}, expected a number of frames per second", value)),
+                    };
+                }
+                "--turbo" => {
+                    let value = value()?;
+                    self.turbo = match value.parse::<u32>() {
+                        Ok(turbo) if (1..=crate::MAX_TURBO_GENERATIONS).contains(&turbo) => turbo,
+                        _ => return Err(format!("invalid turbo {:?}, expected a number of generations per frame", value)),
+                    };
+                }
+                "--rule" => {
+                    let value = value()?;
+                    self.rule = Rule::from_name_or_rulestring(&value).ok_or_else(|| format!("unknown rule {:?}", value))?;
+                }
+                "--seed" => {
+                    let value = value()?;
+                    self.seed = Some(value.parse().map_err(|_| format!("invalid seed {:?}, expected a whole number", value))?);
+                }
+                "--density" => {
+                    let value = value()?;
+                    let density = value.parse().ok().filter(is_fraction);
+                    self.density = Some(density.ok_or_else(|| format!("invalid density {:?}, expected a fraction between 0 and 1", value))?);
+                }
+                "--symmetry" => {
+                    let value = value()?;
+                    self.symmetry = SoupSymmetry::from_name(&value).ok_or_else(|| format!("invalid symmetry {:?}, expected C2, C4, D2 or D4", value))?;
+                }
+                "--open" => self.pattern = Some(value()?.into()),
+                "--headless" => self.headless = true,
+                "--generations" => {
+                    let value = value()?;
+                    self.generations = Some(value.parse().map_err(|_| format!("invalid generation count {:?}", value))?);
+                }
+                "--output" => self.output = Some(value()?.into()),
+                "--results" => self.results = Some(value()?.into()),
+                "--stats" => self.stats = Some(value()?.into()),
+                "--report" => self.report = Some(value()?.into()),
+                "--puzzles" => self.puzzles = Some(value()?.into()),
+                "--achievements" => self.achievements = Some(value()?.into()),
+                "--host" => self.host = Some(value()?),
+                "--join" => self.join = Some(value()?),
+                "--broadcast" => self.broadcast = Some(value()?),
+                "--watch" => self.watch = Some(value()?),
+                "--record" => self.record = Some(value()?.into()),
+                "--replay" => self.replay = Some(value()?.into()),
+                "--script" => self.script = Some(value()?.into()),
+                "--sound" => self.sound = true,
+                "--tui" => self.tui = true,
+                "--pixel-buffer" => self.pixel_buffer = true,
+                _ if !arg.starts_with('-') && self.pattern.is_none() => self.pattern = Some(arg.into()),
+                _ => return Err(format!("unknown option {:?}", arg)),
+            }
+        }
+
+        Ok(Some(self))
+    }
+}
+
+/// Set `key` to `value`, which is already TOML, in the config file at
+/// `path`, changing the line that sets it if there is one and leaving the
+/// rest of the file, comments and all, alone.
+pub fn save_setting(path: &Path, key: &str, value: &str) -> std::io::Result<()> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+
+    let setting = format!("{} = {}", key, value);
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    let sets_key = |line: &str| line.split_once('=').is_some_and(|(name, _)| name.trim() == key);
+    // settings go before the first table, or they'd be read as part of it
+    let tables = lines.iter().position(|line| line.trim_start().starts_with('[')).unwrap_or(lines.len());
+    match lines[..tables].iter().position(|line| sets_key(line)) {
+        Some(index) => lines[index] = setting,
+        None => lines.insert(tables, setting),
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, lines.join("\n") + "\n")
+}
+
+/// What a grid size has to be, for its error messages.
+fn size_range() -> String {
+    format!("with each side from {} to {} cells", crate::MIN_BOARD_SIZE, crate::MAX_BOARD_SIZE)
+}
+
+fn is_fraction(fraction: &f32) -> bool {
+    (0.0..=1.0).contains(fraction)
+}
+
+/// A TOML float or integer.
+fn number(value: &toml::Value) -> Option<f32> {
+    value.as_float().or_else(|| value.as_integer().map(|n| n as f64)).map(|n| n as f32)
+}
+
+/// Parse `<width>x<height>`, both sides as long as a board can be resized to.
+fn parse_size(text: &str) -> Option<(i16, i16)> {
+    let (width, height) = text.split_once(['x', 'X'])?;
+    let (width, height) = (width.trim().parse().ok()?, height.trim().parse().ok()?);
+
+    let sides = crate::MIN_BOARD_SIZE..=crate::MAX_BOARD_SIZE;
+    if sides.contains(&width) && sides.contains(&height) {
+        Some((width, height))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_win_over_the_config_file() {
+        let config = Options::from_config("grid = \"300x200\"\nspeed = 30\nrule = \"HighLife\"\nsound = true").unwrap();
+        let options = config.parse(args(&["--speed", "5", "--rule", "B3/S23", "glider.rle"])).unwrap().unwrap();
+        assert_eq!(options.speed, 5.0);
+        assert_eq!(options.rule, Rule::default());
+        assert_eq!(options.pattern, Some(PathBuf::from("glider.rle")));
+        // the rest still come from the config file
+        assert_eq!(options.grid_size, (300, 200));
+        assert!(options.sound);
+    }
+
+    #[test]
+    fn the_config_file_is_used_without_flags() {
+        let config = "grid = \"64x48\"\ncell = 4\nfps = 30\nturbo = 500\nseed = 7\ndensity = 0.25\nvolume = 1\n\n[keybindings]\ntoggle_run = \"P\"";
+        let options = Options::from_config(config).unwrap().parse(Vec::new()).unwrap().unwrap();
+        assert_eq!(options.grid_size, (64, 48));
+        assert_eq!(options.cell_size, 4.0);
+        assert_eq!(options.frame_rate, Some(30.0));
+        assert_eq!(options.turbo, 500);
+        assert_eq!(options.seed, Some(7));
+        assert_eq!(options.density, Some(0.25));
+        assert_eq!(options.volume, 1.0);
+        assert_eq!(options.key_bindings.get("toggle_run").and_then(toml::Value::as_str), Some("P"));
+        assert_eq!(options.speed, Options::default().speed);
+    }
+
+    #[test]
+    fn bad_config_values_are_refused() {
+        assert_eq!(Options::from_config("colour = \"red\"").unwrap_err(), "unknown setting `colour`");
+        assert_eq!(Options::from_config("rule = \"B9/S23\"").unwrap_err(), "unknown rule `B9/S23`");
+        for config in ["grid = \"300\"", "grid = \"30000x30000\"", "cell = 0", "speed = \"fast\"", "turbo = 0", "density = 1.5", "seed = -1", "keybindings = 3", "grid ="] {
+            assert!(Options::from_config(config).is_err(), "{:?}", config);
+        }
+
+        // a broken file is left out altogether
+        let path = std::env::temp_dir().join(format!("life-options-test-{}.toml", std::process::id()));
+        fs::write(&path, "speed = 30\ndensity = 2").unwrap();
+        let options = Options::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(options.speed, Options::default().speed);
+        assert_eq!(options.density, None);
+    }
+
+    #[test]
+    fn bad_flags_are_refused() {
+        let parse = |list: &[&str]| Options::default().parse(args(list));
+        assert_eq!(parse(&["--grid", "10x10"]).unwrap_err(), "invalid grid size \"10x10\", expected e.g. 300x200, with each side from 16 to 2000 cells");
+        assert_eq!(parse(&["--speed", "-1"]).unwrap_err(), "invalid speed \"-1\", expected a number of generations per second");
+        assert_eq!(parse(&["--seed"]).unwrap_err(), "--seed needs a value");
+        assert_eq!(parse(&["--bogus"]).unwrap_err(), "unknown option \"--bogus\"");
+        assert_eq!(parse(&["a.rle", "b.rle"]).unwrap_err(), "unknown option \"b.rle\"");
+        assert!(parse(&["--symmetry", "C3"]).is_err());
+        assert!(parse(&["--rule", "HighLife", "--help"]).unwrap().is_none());
+    }
+}
@@ -0,0 +1,273 @@
+//! Patterns: blocks of cells that can be read from files, turned around and
+//! stamped onto a board.
+
+use crate::board::{Board, GridPosition, Selection};
+use crate::library;
+use crate::rule::Rule;
+
+/// A block of cells lifted off the board, with its live cells stored relative
+/// to the block's top-left corner.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    pub width: i16,
+    pub height: i16,
+    pub cells: Vec<GridPosition>,
+}
+
+impl Pattern {
+    /// Parse a pattern in plaintext format (`O` alive, anything else dead).
+    pub fn from_plaintext(text: &str) -> Self {
+        let mut cells = Vec::new();
+        let mut width = 0;
+        let mut height = 0;
+
+        for (y, line) in text.lines().filter(|line| !line.starts_with('!')).enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                if ch == 'O' {
+                    cells.push(GridPosition::new(x as i16, y as i16));
+                }
+            }
+            width = width.max(line.chars().count() as i16);
+            height = y as i16 + 1;
+        }
+
+        Pattern { width, height, cells }
+    }
+
+    /// Parse a pattern in RLE format: an `x = 3, y = 3` header followed by runs
+    /// like `bo$2bo$3o!`, where `b` is dead, `o` alive and `$` ends a row.
+    pub fn from_rle(text: &str) -> Option<Self> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+        let header = lines.next()?;
+        let dimension = |name: &str| {
+            header.split(',').find_map(|field| {
+                let (key, value) = field.split_once('=')?;
+                if key.trim() == name { value.trim().parse::<i16>().ok() } else { None }
+            })
+        };
+        let (mut width, mut height) = (dimension("x")?, dimension("y")?);
+
+        let mut cells = Vec::new();
+        let (mut x, mut y, mut run) = (0i16, 0i16, 0i16);
+        'body: for line in lines {
+            for ch in line.chars() {
+                if let Some(digit) = ch.to_digit(10) {
+                    run = run.checked_mul(10)?.checked_add(digit as i16)?;
+                    continue;
+                }
+
+                let count = run.max(1);
+                run = 0;
+                match ch {
+                    'b' | '.' => x = x.checked_add(count)?,
+                    '$' => {
+                        y = y.checked_add(count)?;
+                        x = 0;
+                    }
+                    '!' => break 'body,
+                    _ if ch.is_whitespace() => {},
+                    // any other letter is a live cell in some multi-state rule
+                    _ => {
+                        for _ in 0..count {
+                            cells.push(GridPosition::new(x, y));
+                            x = x.checked_add(1)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        for cell in &cells {
+            width = width.max(cell.x + 1);
+            height = height.max(cell.y + 1);
+        }
+        Some(Pattern { width, height, cells })
+    }
+
+    /// Look up a built-in pattern by name, ignoring case, or else parse the
+    /// text as RLE, or failing that as plaintext.
+    pub fn from_name_or_text(text: &str) -> Self {
+        match library::PATTERNS.iter().find(|(name, _)| name.eq_ignore_ascii_case(text.trim())) {
+            Some((_, cells)) => Pattern::from_plaintext(cells),
+            None => Pattern::from_rle(text).unwrap_or_else(|| Pattern::from_plaintext(text)),
+        }
+    }
+
+    /// Read a pattern file, in RLE if it ends in `.rle` and plaintext otherwise.
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|err| format!("couldn't read {}: {}", path.display(), err))?;
+
+        if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("rle")) {
+            Self::from_rle(&text).ok_or_else(|| format!("{} isn't a valid RLE pattern", path.display()))
+        } else {
+            Ok(Self::from_plaintext(&text))
+        }
+    }
+
+    /// Write the pattern in RLE format, noting the rule it runs under in the header.
+    pub fn to_rle(&self, rule: Rule) -> String {
+        const LINE_LENGTH: usize = 70;
+
+        let mut rows = vec![vec![false; self.width as usize]; self.height as usize];
+        for cell in &self.cells {
+            rows[cell.y as usize][cell.x as usize] = true;
+        }
+
+        let mut runs: Vec<(usize, char)> = Vec::new();
+        let mut push = |tag: char| match runs.last_mut() {
+            Some((count, last)) if *last == tag => *count += 1,
+            _ => runs.push((1, tag)),
+        };
+        for (y, row) in rows.iter().enumerate() {
+            if y > 0 {
+                push('$');
+            }
+            // dead cells at the end of a row go without saying
+            let end = row.iter().rposition(|&alive| alive).map_or(0, |x| x + 1);
+            for &alive in &row[..end] {
+                push(if alive { 'o' } else { 'b' });
+            }
+        }
+        push('!');
+
+        let mut text = format!("x = {}, y = {}, rule = {}\n", self.width, self.height, rule);
+        let mut line = String::new();
+        for (count, tag) in runs {
+            let run = if count > 1 { format!("{}{}", count, tag) } else { tag.to_string() };
+            if line.len() + run.len() > LINE_LENGTH {
+                text.push_str(&line);
+                text.push('\n');
+                line.clear();
+            }
+            line.push_str(&run);
+        }
+        text.push_str(&line);
+        text.push('\n');
+        text
+    }
+
+    pub fn capture(board: &dyn Board, selection: &Selection) -> Self {
+        let (min, max) = selection.bounds();
+        let cells = selection
+            .positions()
+            .filter(|&pos| board.is_alive(pos))
+            .map(|pos| GridPosition::new(pos.x - min.x, pos.y - min.y))
+            .collect();
+
+        Pattern {
+            width: max.x - min.x + 1,
+            height: max.y - min.y + 1,
+            cells,
+        }
+    }
+
+    fn remap<F: Fn(&GridPosition) -> GridPosition>(&self, width: i16, height: i16, f: F) -> Self {
+        Pattern {
+            width,
+            height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        self.remap(self.height, self.width, |c| GridPosition::new(self.height - 1 - c.y, c.x))
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self {
+        self.remap(self.height, self.width, |c| GridPosition::new(c.y, self.width - 1 - c.x))
+    }
+
+    pub fn flip_horizontal(&self) -> Self {
+        self.remap(self.width, self.height, |c| GridPosition::new(self.width - 1 - c.x, c.y))
+    }
+
+    pub fn flip_vertical(&self) -> Self {
+        self.remap(self.width, self.height, |c| GridPosition::new(c.x, self.height - 1 - c.y))
+    }
+
+    /// The board area the pattern covers when placed at `origin`, clipped to the board.
+    pub fn footprint(&self, origin: GridPosition, grid_size: (i16, i16)) -> Selection {
+        let clamp = |x: i16, y: i16| GridPosition::new(x.clamp(0, grid_size.0 - 1), y.clamp(0, grid_size.1 - 1));
+
+        Selection {
+            start: clamp(origin.x, origin.y),
+            end: clamp(origin.x + self.width - 1, origin.y + self.height - 1),
+        }
+    }
+
+    /// Top-left corner that puts the middle of the pattern on `pos`.
+    pub fn origin_centered_on(&self, pos: GridPosition) -> GridPosition {
+        GridPosition::new(pos.x - self.width / 2, pos.y - self.height / 2)
+    }
+
+    /// Board positions of the live cells when the pattern's top-left corner sits
+    /// at `origin`, skipping any that would fall off the board.
+    pub fn placed_at(&self, origin: GridPosition, grid_size: (i16, i16)) -> impl Iterator<Item = GridPosition> + '_ {
+        self.cells
+            .iter()
+            .map(move |cell| GridPosition::new(origin.x + cell.x, origin.y + cell.y))
+            .filter(move |pos| pos.x >= 0 && pos.x < grid_size.0 && pos.y >= 0 && pos.y < grid_size.1)
+    }
+
+    /// Everything in the smallest rectangle holding every live cell on the
+    /// board. Empty if nothing is alive.
+    pub fn capture_live(board: &dyn Board) -> Self {
+        board.bounds().map_or(Pattern { width: 0, height: 0, cells: Vec::new() }, |bounds| Pattern::capture(board, &bounds))
+    }
+
+    pub fn stamp(&self, board: &mut dyn Board, origin: GridPosition) {
+        for pos in self.placed_at(origin, board.size()) {
+            board.set_alive(pos, true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(pattern: &Pattern) -> Vec<(i16, i16)> {
+        pattern.cells.iter().map(|pos| (pos.x, pos.y)).collect()
+    }
+
+    #[test]
+    fn the_header_gives_the_size() {
+        let glider = Pattern::from_rle("#N Glider\n#C a comment\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+        assert_eq!((glider.width, glider.height), (3, 3));
+        assert_eq!(cells(&glider), vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+
+        // a header smaller than the cells grows to fit them
+        let padded = Pattern::from_rle("x=10,y=4\no!").unwrap();
+        assert_eq!((padded.width, padded.height), (10, 4));
+        let grown = Pattern::from_rle("x = 1, y = 1\n3o$o!").unwrap();
+        assert_eq!((grown.width, grown.height), (3, 2));
+    }
+
+    #[test]
+    fn runs_are_counted_across_lines() {
+        let pattern = Pattern::from_rle("x = 14, y = 1\n3o2b\n12o!").unwrap();
+        assert_eq!(pattern.cells.len(), 15);
+        assert_eq!(cells(&pattern)[3], (5, 0));
+        assert_eq!(cells(&pattern)[14], (16, 0));
+    }
+
+    #[test]
+    fn dollars_end_rows_and_counts_skip_blank_ones() {
+        let pattern = Pattern::from_rle("x = 2, y = 5\no$bo3$2o!").unwrap();
+        assert_eq!(cells(&pattern), vec![(0, 0), (1, 1), (0, 4), (1, 4)]);
+        assert_eq!((pattern.width, pattern.height), (2, 5));
+    }
+
+    #[test]
+    fn nothing_after_the_bang_is_read() {
+        let pattern = Pattern::from_rle("x = 3, y = 1\n3o!\n5o$5o").unwrap();
+        assert_eq!(cells(&pattern), vec![(0, 0), (1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn bad_rle_is_refused() {
+        for text in ["", "#C only comments", "bo$2bo$3o!", "x = 3\nbo$2bo$3o!", "x = 3, y = three\n3o!", "x = 1, y = 1\n99999o!", "x = 1, y = 1\n32767b2o!"] {
+            assert_eq!(Pattern::from_rle(text), None, "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! A second window frontend that writes cells straight into a pixel buffer
+//! instead of building ggez meshes, so a frame costs one pass over the pixels
+//! however many cells are alive.
+
+use crate::options::Options;
+
+use life::board::{self, GridPosition, Selection};
+use life::pattern::Pattern;
+use minifb::{Key, KeyRepeat, MouseButton, MouseMode, Window, WindowOptions};
+
+use std::time::Instant;
+
+/// Pack a color into the buffer's 0RGB format.
+fn pixel(color: [f32; 4]) -> u32 {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u32;
+    channel(color[0]) << 16 | channel(color[1]) << 8 | channel(color[2])
+}
+
+/// Run the game in a pixel buffer window until it's closed.
+pub fn run(options: &Options, pattern: Option<&Pattern>) -> Result<(), String> {
+    let (mut universe, mut rng, seed) = crate::start_universe(options, pattern);
+    println!("seed {} (start with --seed {} to repeat this run)", seed, seed);
+
+    let (grid_w, grid_h) = options.grid_size;
+    let cell = options.cell_size.round().max(1.0) as usize;
+    let (width, height) = (grid_w as usize * cell, grid_h as usize * cell);
+    let mut window = Window::new("Life", width, height, WindowOptions::default()).map_err(|err| err.to_string())?;
+    window.set_target_fps(60);
+
+    let background = pixel(crate::THEMES[0].background);
+    let live = pixel(crate::PALETTES[0].cell);
+    let mut buffer = vec![background; width * height];
+    let (mut running, mut speed, mut last_step) = (false, options.speed, Instant::now());
+    let mut painting = None;
+
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        let whole_board = Selection::whole_board(options.grid_size);
+        if window.is_key_pressed(Key::Space, KeyRepeat::No) {
+            running = !running;
+        }
+        if window.is_key_pressed(Key::N, KeyRepeat::Yes) {
+            universe.step();
+        }
+        if window.is_key_pressed(Key::Equal, KeyRepeat::No) {
+            speed = (speed * 2.0).min(240.0);
+        }
+        if window.is_key_pressed(Key::Minus, KeyRepeat::No) {
+            speed = (speed / 2.0).max(1.0);
+        }
+        if window.is_key_pressed(Key::R, KeyRepeat::No) {
+            board::fill_random(universe.board_mut(), &whole_board, options.density.unwrap_or(crate::DENSITY), &mut rng);
+        }
+        if window.is_key_pressed(Key::C, KeyRepeat::No) {
+            board::set_region(universe.board_mut(), &whole_board, true);
+        }
+
+        // dragging paints whatever the first cell clicked was flipped to
+        match window.get_mouse_pos(MouseMode::Discard).filter(|_| window.get_mouse_down(MouseButton::Left)) {
+            Some((x, y)) => {
+                let pos = GridPosition::new((x as i16 / cell as i16).min(grid_w - 1), (y as i16 / cell as i16).min(grid_h - 1));
+                let alive = *painting.get_or_insert(!universe.is_alive(pos));
+                universe.board_mut().set_alive(pos, alive);
+            }
+            None => painting = None,
+        }
+
+        if running && last_step.elapsed().as_secs_f32() >= 1.0 / speed {
+            universe.step();
+            last_step = Instant::now();
+        }
+
+        for value in buffer.iter_mut() {
+            *value = background;
+        }
+        for pos in universe.board().live_cells() {
+            let (left, top) = (pos.x as usize * cell, pos.y as usize * cell);
+            for row in buffer[top * width..(top + cell) * width].chunks_mut(width) {
+                for value in &mut row[left..left + cell] {
+                    *value = live;
+                }
+            }
+        }
+
+        window.set_title(&format!(
+            "Life - {}, {} gen/s, generation {}, population {}{}",
+            universe.rule(),
+            speed,
+            universe.generation(),
+            universe.population(),
+            if running { "" } else { ", paused" },
+        ));
+        window.update_with_buffer(&buffer, width, height).map_err(|err| err.to_string())?;
+    }
+
+    Ok(())
+}
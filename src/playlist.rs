@@ -0,0 +1,105 @@
+//! Background music: the tracks in a directory, played one after another in
+//! order of their file names and round again.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The kinds of audio file a track can be, by extension.
+pub const TRACK_EXTENSIONS: &[&str] = &["ogg", "flac", "wav", "mp3"];
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Playlist {
+    tracks: Vec<PathBuf>,
+    index: usize,
+}
+
+impl Playlist {
+    pub fn new(tracks: Vec<PathBuf>) -> Self {
+        Playlist { tracks, index: 0 }
+    }
+
+    /// The tracks in `dir`, leaving out anything that isn't audio. An empty
+    /// playlist if there's no such directory.
+    pub fn scan(dir: &Path) -> io::Result<Self> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Playlist::default()),
+            Err(err) => return Err(err),
+        };
+
+        let mut tracks = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            let is_track = path.extension().and_then(|extension| extension.to_str()).is_some_and(|extension| {
+                TRACK_EXTENSIONS.iter().any(|track| extension.eq_ignore_ascii_case(track))
+            });
+            if is_track && path.is_file() {
+                tracks.push(path);
+            }
+        }
+        tracks.sort();
+        Ok(Playlist::new(tracks))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tracks.len()
+    }
+
+    /// The track to play now, if there are any.
+    pub fn current(&self) -> Option<&Path> {
+        self.tracks.get(self.index).map(PathBuf::as_path)
+    }
+
+    /// Move on to the next track, back to the first after the last.
+    pub fn advance(&mut self) -> Option<&Path> {
+        if !self.tracks.is_empty() {
+            self.index = (self.index + 1) % self.tracks.len();
+        }
+        self.current()
+    }
+
+    /// Drop the current track, say because it won't play, leaving the one
+    /// after it current.
+    pub fn remove_current(&mut self) {
+        if self.index < self.tracks.len() {
+            self.tracks.remove(self.index);
+        }
+        if self.index >= self.tracks.len() {
+            self.index = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_play_in_order_and_round_again() {
+        let dir = std::env::temp_dir().join(format!("life-playlist-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in &["b.ogg", "a.WAV", "notes.txt", "c.mp3"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        let mut playlist = Playlist::scan(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let name = |track: Option<&Path>| track.and_then(Path::file_name).and_then(|name| name.to_str()).map(str::to_string);
+        assert_eq!(playlist.len(), 3);
+        assert_eq!(name(playlist.current()).as_deref(), Some("a.WAV"));
+        assert_eq!(name(playlist.advance()).as_deref(), Some("b.ogg"));
+        playlist.remove_current();
+        assert_eq!(name(playlist.current()).as_deref(), Some("c.mp3"));
+        assert_eq!(name(playlist.advance()).as_deref(), Some("a.WAV"));
+        playlist.remove_current();
+        playlist.remove_current();
+        assert!(playlist.is_empty());
+        assert_eq!(playlist.advance(), None);
+
+        assert!(Playlist::scan(&dir).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,84 @@
+//! Rules beyond the life-like ones, as plugins: anything implementing
+//! `RulePlugin` can be registered at startup and picked like a preset.
+
+use crate::rule::{Neighborhood, Transition};
+
+/// A named rule that can be offered alongside the presets.
+pub trait RulePlugin: Transition {
+    fn name(&self) -> &str;
+}
+
+/// The rules a frontend can offer besides the life-like ones, in the order
+/// they were registered.
+pub struct Plugins {
+    rules: Vec<Box<dyn RulePlugin>>,
+}
+
+impl Default for Plugins {
+    /// The plugins that ship with the engine.
+    fn default() -> Self {
+        let mut plugins = Plugins { rules: Vec::new() };
+        plugins.register(Box::new(Fredkin));
+        plugins
+    }
+}
+
+impl Plugins {
+    pub fn register(&mut self, rule: Box<dyn RulePlugin>) {
+        self.rules.push(rule);
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&dyn RulePlugin> {
+        self.rules.get(index).map(Box::as_ref)
+    }
+
+    /// The index of the plugin called `name`, ignoring case.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.rules.iter().position(|rule| rule.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// Fredkin's replicator on the von Neumann neighborhood: a cell is alive when
+/// an odd number of the four cells beside it are. Any pattern turns into
+/// copies of itself, which no count of all eight neighbors can do.
+struct Fredkin;
+
+impl Transition for Fredkin {
+    fn next_state(&self, neighborhood: Neighborhood) -> bool {
+        let beside = [(1, 0), (0, 1), (2, 1), (1, 2)];
+        beside.iter().filter(|&&(dx, dy)| neighborhood.is_alive(dx, dy)).count() % 2 == 1
+    }
+}
+
+impl RulePlugin for Fredkin {
+    fn name(&self) -> &str {
+        "Fredkin"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{self, Board, DenseBoard, GridPosition};
+
+    #[test]
+    fn fredkin_replicates_a_cell_beside_itself() {
+        let plugins = Plugins::default();
+        let fredkin = plugins.get(plugins.find("fredkin").expect("no Fredkin plugin")).unwrap();
+
+        let mut board = DenseBoard::new((5, 5));
+        board.set_alive(GridPosition::new(2, 2), true);
+        let next = board::step(&board, fredkin);
+
+        let live: Vec<_> = next.live_cells().collect();
+        assert_eq!(live, vec![(2, 1).into(), (1, 2).into(), (3, 2).into(), (2, 3).into()]);
+    }
+}
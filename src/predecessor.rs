@@ -0,0 +1,166 @@
+//! Searching for a predecessor of a small pattern: cells that turn into it in
+//! one generation. Only the cells in and right around the pattern affect it,
+//! so the search covers every predecessor there is, and a pattern without one
+//! can't come about except by being drawn in, like a Garden of Eden.
+
+use crate::board::GridPosition;
+use crate::pattern::Pattern;
+use crate::rule::{Neighborhood, Transition};
+
+use std::collections::HashSet;
+
+/// How a predecessor search turned out.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Search {
+    /// A pattern one cell bigger than the target on every side whose middle
+    /// becomes the target in a generation.
+    Found(Pattern),
+    /// Nothing becomes the target, whatever the cells around it are.
+    NoPredecessor,
+    /// The search ran out of steps before it could tell.
+    GaveUp,
+}
+
+/// Look for a predecessor of `target` under `rule`, trying at most
+/// `max_steps` rows of cells. The search goes a row at a time, so its cost
+/// grows exponentially with the target's width: keep it to a handful of
+/// cells across. It can't be more than 29, so a row of the predecessor,
+/// two cells wider, still fits in 31 bits.
+pub fn find_predecessor(target: &Pattern, rule: &dyn Transition, max_steps: u64) -> Search {
+    let (width, height) = (target.width as usize, target.height as usize);
+    assert!(width <= 29, "a {} cell wide pattern is too wide to search for a predecessor of", width);
+    // the next state of every 3x3 neighborhood, to save asking the rule each time
+    let next: Vec<bool> = (0..512).map(|mask| rule.next_state(Neighborhood(mask))).collect();
+
+    let mut rows = vec![0u32; height];
+    for pos in &target.cells {
+        rows[pos.y as usize] |= 1 << pos.x;
+    }
+
+    let mut search = RowSearch { width, target: rows, next, dead_ends: HashSet::new(), steps: 0, max_steps };
+    let mut predecessor = Vec::with_capacity(height + 2);
+    match search.extend(&mut predecessor) {
+        Some(true) => {
+            let cells = predecessor
+                .iter()
+                .enumerate()
+                .flat_map(|(y, &row)| (0..width + 2).filter(move |&x| row & 1 << x != 0).map(move |x| GridPosition::new(x as i16, y as i16)))
+                .collect();
+            Search::Found(Pattern { width: target.width + 2, height: target.height + 2, cells })
+        }
+        Some(false) => Search::NoPredecessor,
+        None => Search::GaveUp,
+    }
+}
+
+/// A depth-first search for the rows of a predecessor, each `width + 2` cells
+/// wide, with bit `x` of a row for the cell in column `x`.
+struct RowSearch {
+    width: usize,
+    target: Vec<u32>,
+    next: Vec<bool>,
+    /// Pairs of rows, by how many rows come before them, that are known not
+    /// to lead to a predecessor.
+    dead_ends: HashSet<(usize, u32, u32)>,
+    steps: u64,
+    max_steps: u64,
+}
+
+impl RowSearch {
+    /// Add rows to `rows` until it's a whole predecessor. False if there's no
+    /// way to, leaving `rows` as it was, or `None` if out of steps.
+    fn extend(&mut self, rows: &mut Vec<u32>) -> Option<bool> {
+        let depth = rows.len();
+        if depth == self.target.len() + 2 {
+            return Some(true);
+        }
+        if depth >= 2 && self.dead_ends.contains(&(depth, rows[depth - 2], rows[depth - 1])) {
+            return Some(false);
+        }
+
+        for row in 0..1u32 << (self.width + 2) {
+            self.steps += 1;
+            if self.steps > self.max_steps {
+                return None;
+            }
+            // with three rows the target row between them is settled
+            if depth >= 2 && self.row_after(rows[depth - 2], rows[depth - 1], row) != self.target[depth - 2] {
+                continue;
+            }
+
+            rows.push(row);
+            if self.extend(rows)? {
+                return Some(true);
+            }
+            rows.pop();
+        }
+
+        if depth >= 2 {
+            self.dead_ends.insert((depth, rows[depth - 2], rows[depth - 1]));
+        }
+        Some(false)
+    }
+
+    /// The middle row one generation after the rows `above`, `middle` and
+    /// `below`, leaving off the cells at either end.
+    fn row_after(&self, above: u32, middle: u32, below: u32) -> u32 {
+        (0..self.width).fold(0, |row, x| {
+            let mask = (above >> x & 7) | (middle >> x & 7) << 3 | (below >> x & 7) << 6;
+            if self.next[mask as usize] {
+                row | 1 << x
+            } else {
+                row
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{self, DenseBoard, Selection};
+    use crate::rule::Rule;
+
+    /// Where everything dies, so nothing alive has a predecessor.
+    struct Extinction;
+
+    impl Transition for Extinction {
+        fn next_state(&self, _: Neighborhood) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn predecessors_become_the_target() {
+        let target = Pattern::from_plaintext(".O.\n.O.\n.O.");
+        let predecessor = match find_predecessor(&target, &Rule::default(), 1_000_000) {
+            Search::Found(predecessor) => predecessor,
+            other => panic!("expected a predecessor, got {:?}", other),
+        };
+
+        let mut board = DenseBoard::new((5, 5));
+        predecessor.stamp(&mut board, GridPosition::new(0, 0));
+        let next = board::step(&board, &Rule::default());
+        let middle = Selection { start: GridPosition::new(1, 1), end: GridPosition::new(3, 3) };
+        assert_eq!(Pattern::capture(next.as_ref(), &middle), target);
+    }
+
+    #[test]
+    fn some_targets_have_none() {
+        assert_eq!(find_predecessor(&Pattern::from_plaintext("O"), &Extinction, 1_000_000), Search::NoPredecessor);
+        assert_eq!(find_predecessor(&Pattern::from_plaintext(".O.\nOOO\n.O."), &Rule::default(), 3), Search::GaveUp);
+    }
+
+    #[test]
+    fn targets_can_be_29_cells_wide() {
+        let widest = Pattern::from_plaintext(&format!("O{}O", ".".repeat(27)));
+        assert_eq!(widest.width, 29);
+        assert_eq!(find_predecessor(&widest, &Rule::default(), 1000), Search::GaveUp);
+    }
+
+    #[test]
+    #[should_panic(expected = "too wide")]
+    fn wider_targets_are_refused() {
+        find_predecessor(&Pattern::from_plaintext(&format!("O{}O", ".".repeat(28))), &Rule::default(), 1000);
+    }
+}
@@ -0,0 +1,176 @@
+//! Puzzles: a target to reach under Conway's Life from a handful of cells
+//! within a few generations. A puzzle is written as `key: value` lines for
+//! its name, how many cells it allows and how many generations, followed by
+//! the target as a library pattern name, RLE or plaintext:
+//!
+//! ```text
+//! name: Square one
+//! cells: 3
+//! generations: 1
+//! OO
+//! OO
+//! ```
+//!
+//! A puzzle file can hold any number of them, each starting at its `name:`.
+
+use crate::board::{Board, GridPosition};
+use crate::pattern::Pattern;
+
+use std::collections::BTreeSet;
+
+/// The puzzles that come with the game, easiest first.
+pub const PUZZLES: &str = "\
+name: Square one
+cells: 3
+generations: 1
+OO
+OO
+
+name: Hive
+cells: 4
+generations: 2
+.OO.
+O..O
+.OO.
+
+name: Fresh loaf
+cells: 5
+generations: 3
+.OO.
+O..O
+.O.O
+..O.
+
+name: Pond life
+cells: 4
+generations: 4
+.OO.
+O..O
+O..O
+.OO.
+
+name: Lighthouse
+cells: 6
+generations: 1
+beacon
+";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Puzzle {
+    pub name: String,
+    /// How many live cells the board can start with.
+    pub cells: usize,
+    /// How many generations the target has to be reached in.
+    pub generations: u64,
+    pub target: Pattern,
+}
+
+impl Puzzle {
+    /// Every puzzle in `text`, in the format above.
+    pub fn parse_all(text: &str) -> Result<Vec<Puzzle>, String> {
+        let mut puzzles = Vec::new();
+        let mut lines: Vec<&str> = Vec::new();
+
+        for line in text.lines() {
+            if line.starts_with("name:") && !lines.is_empty() {
+                puzzles.push(Self::parse(&lines)?);
+                lines.clear();
+            }
+            lines.push(line);
+        }
+        if lines.iter().any(|line| !line.trim().is_empty()) {
+            puzzles.push(Self::parse(&lines)?);
+        }
+        Ok(puzzles)
+    }
+
+    fn parse(lines: &[&str]) -> Result<Puzzle, String> {
+        let (mut name, mut cells, mut generations) = (None, None, None);
+        let mut target = String::new();
+
+        for line in lines {
+            match line.split_once(':') {
+                Some(("name", value)) => name = Some(value.trim().to_string()),
+                Some(("cells", value)) => cells = Some(value.trim().parse().map_err(|_| format!("{:?} isn't a number of cells", value.trim()))?),
+                Some(("generations", value)) => {
+                    generations = Some(value.trim().parse().map_err(|_| format!("{:?} isn't a number of generations", value.trim()))?)
+                }
+                _ if !line.trim().is_empty() => {
+                    target.push_str(line);
+                    target.push('\n');
+                }
+                _ => {}
+            }
+        }
+
+        let name = name.ok_or("a puzzle has no name")?;
+        let target = Pattern::from_name_or_text(target.trim_end());
+        if target.cells.is_empty() {
+            return Err(format!("puzzle {:?} has no target", name));
+        }
+        Ok(Puzzle {
+            cells: cells.ok_or_else(|| format!("puzzle {:?} doesn't say how many cells it allows", name))?,
+            generations: generations.ok_or_else(|| format!("puzzle {:?} doesn't say how many generations it allows", name))?,
+            name,
+            target,
+        })
+    }
+
+    /// Read every puzzle in a puzzle file.
+    pub fn load(path: &std::path::Path) -> Result<Vec<Puzzle>, String> {
+        let text = std::fs::read_to_string(path).map_err(|err| format!("couldn't read {}: {}", path.display(), err))?;
+        Self::parse_all(&text).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    /// The puzzles that come with the game.
+    pub fn builtin() -> Vec<Puzzle> {
+        Self::parse_all(PUZZLES).expect("the built-in puzzles parse")
+    }
+
+    /// Whether the live cells of `board` are the target, anywhere on it.
+    pub fn is_solved_by(&self, board: &dyn Board) -> bool {
+        shape(board.live_cells()) == shape(self.target.cells.iter().copied())
+    }
+}
+
+/// `cells` moved so the top and left of them are both at 0.
+fn shape(cells: impl Iterator<Item = GridPosition>) -> BTreeSet<GridPosition> {
+    let cells: Vec<_> = cells.collect();
+    let left = cells.iter().map(|pos| pos.x).min().unwrap_or(0);
+    let top = cells.iter().map(|pos| pos.y).min().unwrap_or(0);
+    cells.into_iter().map(|pos| GridPosition::new(pos.x - left, pos.y - top)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{self, DenseBoard};
+    use crate::rule::Rule;
+
+    #[test]
+    fn every_builtin_puzzle_can_be_solved() {
+        let solutions = ["OO\nO.", "OOOO", "OOO\n..O\n..O", "O..\n.OO\n.O.", "OO..\nO...\n...O\n..OO"];
+        let puzzles = Puzzle::builtin();
+        assert_eq!(puzzles.len(), solutions.len());
+
+        for (puzzle, solution) in puzzles.iter().zip(&solutions) {
+            let solution = Pattern::from_plaintext(solution);
+            assert!(solution.cells.len() <= puzzle.cells, "{} takes too many cells", puzzle.name);
+
+            let mut board: Box<dyn Board> = Box::new(DenseBoard::new((20, 20)));
+            solution.stamp(board.as_mut(), GridPosition::new(8, 8));
+            for _ in 0..puzzle.generations {
+                board = board::step(board.as_ref(), &Rule::default());
+            }
+            assert!(puzzle.is_solved_by(board.as_ref()), "{} isn't solved", puzzle.name);
+        }
+    }
+
+    #[test]
+    fn puzzles_need_every_field() {
+        assert_eq!(Puzzle::parse_all("name: Nothing\ncells: 3\ngenerations: 1\n"), Err("puzzle \"Nothing\" has no target".to_string()));
+        assert_eq!(Puzzle::parse_all("cells: 3\ngenerations: 1\nOO"), Err("a puzzle has no name".to_string()));
+        assert!(Puzzle::parse_all("name: Blinker\ncells: x\ngenerations: 1\nOOO").is_err());
+        assert_eq!(Puzzle::parse_all("\n").map(|puzzles| puzzles.len()), Ok(0));
+    }
+}
@@ -0,0 +1,114 @@
+//! A quiz on the library's patterns: one of them runs on the board and the
+//! player picks out its name from a few, the rest taken at random.
+
+use crate::library;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// A question: the pattern to name, and the names to pick from, as indexes
+/// into `library::PATTERNS`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Question {
+    pub pattern: usize,
+    pub choices: Vec<usize>,
+}
+
+impl Question {
+    /// A question about a random pattern with `choices` names to pick from,
+    /// the right one among them, or every name if there aren't that many.
+    pub fn random<R: Rng>(rng: &mut R, choices: usize) -> Self {
+        let pattern = rng.gen_range(0, library::PATTERNS.len());
+        let mut others: Vec<usize> = (0..library::PATTERNS.len()).filter(|&other| other != pattern).collect();
+        others.shuffle(rng);
+
+        let mut choices: Vec<usize> = others.into_iter().take(choices.saturating_sub(1)).collect();
+        choices.push(pattern);
+        choices.shuffle(rng);
+        Question { pattern, choices }
+    }
+
+    /// The name of the pattern being asked about.
+    pub fn answer(&self) -> &'static str {
+        library::PATTERNS[self.pattern].0
+    }
+
+    /// The names to pick from, in order.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.choices.iter().map(|&choice| library::PATTERNS[choice].0)
+    }
+
+    /// Whether the choice at `index` is the right one.
+    pub fn is_answered_by(&self, index: usize) -> bool {
+        self.choices.get(index) == Some(&self.pattern)
+    }
+}
+
+/// A quiz going on: the question being asked and how it's gone so far.
+#[derive(Clone, Debug)]
+pub struct Quiz {
+    pub question: Question,
+    pub asked: u32,
+    pub right: u32,
+    choices: usize,
+}
+
+impl Quiz {
+    /// A quiz with `choices` names to pick from for each question.
+    pub fn new<R: Rng>(rng: &mut R, choices: usize) -> Self {
+        Quiz { question: Question::random(rng, choices), asked: 0, right: 0, choices }
+    }
+
+    /// Answer the question with the choice at `index`, keeping score, and move
+    /// on to the next one. Returns the question answered, and whether the
+    /// answer was right; `None` for an index with no choice, which changes nothing.
+    pub fn answer<R: Rng>(&mut self, rng: &mut R, index: usize) -> Option<(Question, bool)> {
+        if index >= self.question.choices.len() {
+            return None;
+        }
+
+        let right = self.question.is_answered_by(index);
+        self.asked += 1;
+        if right {
+            self.right += 1;
+        }
+        let question = std::mem::replace(&mut self.question, Question::random(rng, self.choices));
+        Some((question, right))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn questions_offer_the_answer_among_others() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..20 {
+            let question = Question::random(&mut rng, 4);
+            assert_eq!(question.choices.len(), 4);
+            assert_eq!(question.choices.iter().filter(|&&choice| choice == question.pattern).count(), 1);
+
+            let mut choices = question.choices.clone();
+            choices.sort_unstable();
+            choices.dedup();
+            assert_eq!(choices.len(), 4, "{:?} has a name twice", question);
+        }
+        assert_eq!(Question::random(&mut rng, 100).choices.len(), library::PATTERNS.len());
+    }
+
+    #[test]
+    fn the_quiz_keeps_score() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut quiz = Quiz::new(&mut rng, 3);
+
+        let right = quiz.question.choices.iter().position(|&choice| choice == quiz.question.pattern).unwrap();
+        assert_eq!(quiz.answer(&mut rng, right).map(|(_, right)| right), Some(true));
+        let wrong = quiz.question.choices.iter().position(|&choice| choice != quiz.question.pattern).unwrap();
+        assert_eq!(quiz.answer(&mut rng, wrong).map(|(_, right)| right), Some(false));
+        assert_eq!(quiz.answer(&mut rng, 3), None);
+        assert_eq!((quiz.right, quiz.asked), (1, 2));
+    }
+}
@@ -0,0 +1,375 @@
+//! Session replays: the board a session started from and everything done to
+//! it since, with when, so it can be played back exactly as it went, like
+//!
+//! ```text
+//! replay 1
+//! seed 42
+//! 0 board 200 150 4,5 5,5 6,5
+//! 0 rule B3/S23
+//! 0 speed 20
+//! 1530 edits +10,10 +11,10 -5,5
+//! 4210 steps 52
+//! 4900 generation 0
+//! ```
+//!
+//! with a line for each event after the header, starting with the time it
+//! happened in milliseconds. Stepping isn't an edit, so what the steps did
+//! isn't kept, only how many there were and when the last was: played back,
+//! the board steps again under the same rule, spread evenly over the time
+//! they took.
+
+use crate::board::{self, Board, GridPosition};
+use crate::net;
+
+use std::path::Path;
+use std::time::Duration;
+
+const HEADER: &str = "replay 1";
+/// The most steps one event holds. Longer runs are recorded as several, so
+/// playing one back never has more to step at once than this.
+pub const MAX_STEPS: u64 = 10_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// The whole board, to start with and whenever it changes size.
+    Board { size: (i16, i16), cells: Vec<GridPosition> },
+    /// Cells brought to life or killed by anything but a step.
+    Edits { born: Vec<GridPosition>, died: Vec<GridPosition> },
+    /// The board stepped this many generations.
+    Steps(u64),
+    /// A rulestring, or the name of a plugin.
+    Rule(String),
+    /// Generations per second.
+    Speed(f32),
+    /// The generation count set to this, other than by stepping, as when the board is cleared.
+    Generation(u64),
+}
+
+impl Event {
+    fn to_line(&self) -> String {
+        match self {
+            Event::Board { size, cells } => format!("board {} {}{}", size.0, size.1, net::cells("", cells)),
+            Event::Edits { born, died } => format!("edits{}{}", net::cells("+", born), net::cells("-", died)),
+            Event::Steps(count) => format!("steps {}", count),
+            Event::Rule(rule) => format!("rule {}", rule),
+            Event::Speed(speed) => format!("speed {}", speed),
+            Event::Generation(generation) => format!("generation {}", generation),
+        }
+    }
+
+    fn parse(line: &str) -> Result<Event, String> {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("board") => {
+                let size: (i16, i16) = (net::number(words.next(), line)?, net::number(words.next(), line)?);
+                if size.0 <= 0 || size.1 <= 0 || size.0 > net::MAX_BOARD_SIZE || size.1 > net::MAX_BOARD_SIZE {
+                    return Err(format!("bad board size in {:?}", line));
+                }
+                let cells = words.map(|word| net::cell(word).ok_or_else(|| format!("bad cell {:?}", word))).collect::<Result<_, _>>()?;
+                Ok(Event::Board { size, cells })
+            }
+            Some("edits") => {
+                let (born, died) = net::changes(words)?;
+                Ok(Event::Edits { born, died })
+            }
+            Some("steps") => match net::number(words.next(), line)? {
+                count @ 1..=MAX_STEPS => Ok(Event::Steps(count)),
+                _ => Err(format!("bad step count in {:?}", line)),
+            },
+            Some("rule") => Ok(Event::Rule(words.collect::<Vec<_>>().join(" "))),
+            Some("speed") => Ok(Event::Speed(net::number(words.next(), line)?)),
+            Some("generation") => Ok(Event::Generation(net::number(words.next(), line)?)),
+            _ => Err(format!("unknown event {:?}", line)),
+        }
+    }
+}
+
+/// A recorded session: the seed its soups came from, and its events in the
+/// order they happened, with how long into the session each was.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub events: Vec<(Duration, Event)>,
+}
+
+impl Replay {
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\nseed {}\n", HEADER, self.seed);
+        for (time, event) in &self.events {
+            text.push_str(&format!("{} {}\n", time.as_millis(), event.to_line()));
+        }
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        if lines.next().map(|(_, line)| line.trim()) != Some(HEADER) {
+            return Err("not a replay".to_string());
+        }
+
+        let mut replay = Replay::default();
+        for (index, line) in lines {
+            let bad = |err: String| format!("line {}: {}", index + 1, err);
+            let (first, rest) = line.trim().split_once(' ').ok_or_else(|| bad(format!("bad event {:?}", line)))?;
+            if first == "seed" {
+                replay.seed = rest.trim().parse().map_err(|_| bad(format!("bad seed {:?}", rest)))?;
+                continue;
+            }
+            let time = first.parse().map(Duration::from_millis).map_err(|_| bad(format!("bad time {:?}", first)))?;
+            replay.events.push((time, Event::parse(rest).map_err(bad)?));
+        }
+        Ok(replay)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|err| format!("couldn't read {}: {}", path.display(), err))?;
+        Self::parse(&text).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_text())
+    }
+
+    /// How long the session it recorded ran, up to its last event.
+    pub fn duration(&self) -> Duration {
+        self.events.last().map_or(Duration::ZERO, |&(time, _)| time)
+    }
+}
+
+/// Records a session as it goes, by being shown the board before every step
+/// and once a frame, and told about every step.
+pub struct Recorder {
+    replay: Replay,
+    /// The board, generation, rule and speed as they were last recorded.
+    board: Box<dyn Board>,
+    generation: u64,
+    rule: String,
+    speed: f32,
+    /// Steps not yet recorded, and when the last of them was.
+    steps: u64,
+    stepped_at: Duration,
+}
+
+impl Recorder {
+    /// Start recording from `board` at `generation`, with soups from `seed`.
+    pub fn new(board: &dyn Board, generation: u64, rule: String, speed: f32, seed: u64) -> Self {
+        let start = Duration::ZERO;
+        let events = vec![
+            (start, Event::Board { size: board.size(), cells: board.live_cells().collect() }),
+            (start, Event::Generation(generation)),
+            (start, Event::Rule(rule.clone())),
+            (start, Event::Speed(speed)),
+        ];
+        Recorder { replay: Replay { seed, events }, board: board.box_clone(), generation, rule, speed, steps: 0, stepped_at: start }
+    }
+
+    /// Record whatever's changed, at `time` into the session, since the last
+    /// time the recorder saw the board. Steps in a row with nothing else
+    /// between them are kept as one event.
+    pub fn observe(&mut self, time: Duration, board: &dyn Board, generation: u64, rule: &str, speed: f32) {
+        let mut changes = Vec::new();
+        if board.size() != self.board.size() {
+            changes.push(Event::Board { size: board.size(), cells: board.live_cells().collect() });
+        } else {
+            let (born, died) = board::diff(self.board.as_ref(), board);
+            if !born.is_empty() || !died.is_empty() {
+                changes.push(Event::Edits { born, died });
+            }
+        }
+        if generation != self.generation {
+            changes.push(Event::Generation(generation));
+        }
+        if rule != self.rule {
+            changes.push(Event::Rule(rule.to_string()));
+        }
+        if speed != self.speed {
+            changes.push(Event::Speed(speed));
+        }
+        if changes.is_empty() {
+            return;
+        }
+
+        if self.steps > 0 {
+            self.replay.events.push((self.stepped_at, Event::Steps(self.steps)));
+            self.steps = 0;
+        }
+        self.replay.events.extend(changes.into_iter().map(|event| (time, event)));
+        self.board = board.box_clone();
+        self.generation = generation;
+        self.rule = rule.to_string();
+        self.speed = speed;
+    }
+
+    /// Record a step, at `time` into the session, that left the board as `board`.
+    /// The board before it should have been observed first.
+    pub fn stepped(&mut self, time: Duration, board: &dyn Board) {
+        if self.steps == MAX_STEPS {
+            self.replay.events.push((self.stepped_at, Event::Steps(self.steps)));
+            self.steps = 0;
+        }
+        self.steps += 1;
+        self.stepped_at = time;
+        self.generation += 1;
+        self.board = board.box_clone();
+    }
+
+    /// The replay so far, with any steps still to be recorded.
+    pub fn finish(mut self) -> Replay {
+        if self.steps > 0 {
+            self.replay.events.push((self.stepped_at, Event::Steps(self.steps)));
+        }
+        self.replay
+    }
+}
+
+/// Plays a replay back, handing out its events as their times come.
+#[derive(Clone, Debug)]
+pub struct Player {
+    replay: Replay,
+    next: usize,
+    /// How many of the next event's steps have been handed out, if it's steps.
+    stepped: u64,
+}
+
+impl Player {
+    pub fn new(replay: Replay) -> Self {
+        Player { replay, next: 0, stepped: 0 }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// The events due `time` into the playback that haven't been handed out
+    /// yet. Steps are handed out a few at a time, as their share of the time
+    /// between them and the event before comes.
+    pub fn advance(&mut self, time: Duration) -> Vec<Event> {
+        let mut due = Vec::new();
+        while let Some((at, event)) = self.replay.events.get(self.next) {
+            match *event {
+                Event::Steps(count) => {
+                    let start = self.next.checked_sub(1).map_or(Duration::ZERO, |previous| self.replay.events[previous].0);
+                    let done = if time >= *at {
+                        count
+                    } else if time <= start {
+                        0
+                    } else {
+                        (count as f64 * (time - start).as_secs_f64() / (*at - start).as_secs_f64()) as u64
+                    };
+                    if done > self.stepped {
+                        due.push(Event::Steps(done - self.stepped));
+                        self.stepped = done;
+                    }
+                    if self.stepped < count {
+                        break;
+                    }
+                    self.stepped = 0;
+                }
+                _ if time < *at => break,
+                _ => due.push(event.clone()),
+            }
+            self.next += 1;
+        }
+        due
+    }
+
+    /// Whether every event has been handed out.
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.replay.events.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::DenseBoard;
+    use crate::rule::Rule;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn replays_round_trip_through_text() {
+        let pos = GridPosition::new;
+        let replay = Replay {
+            seed: 42,
+            events: vec![
+                (ms(0), Event::Board { size: (20, 10), cells: vec![pos(4, 5), pos(5, 5)] }),
+                (ms(0), Event::Rule("B36/S23".to_string())),
+                (ms(1530), Event::Edits { born: vec![pos(1, 1)], died: vec![pos(4, 5)] }),
+                (ms(1600), Event::Speed(2.5)),
+                (ms(4210), Event::Steps(52)),
+                (ms(4900), Event::Generation(0)),
+            ],
+        };
+        let text = replay.to_text();
+        assert!(text.starts_with("replay 1\nseed 42\n0 board 20 10 4,5 5,5\n"));
+        assert_eq!(Replay::parse(&text), Ok(replay.clone()));
+        assert_eq!(replay.duration(), ms(4900));
+
+        assert_eq!(Replay::parse("seed 42"), Err("not a replay".to_string()));
+        assert!(Replay::parse("replay 1\n10 jump\n").unwrap_err().starts_with("line 2"));
+    }
+
+    #[test]
+    fn bad_sizes_and_step_counts_are_refused() {
+        for line in ["0 board -1 5", "0 board 5 0", "0 board 30000 30000", "0 board 20 2001", "0 steps 0", "0 steps 10001", "0 steps 18446744073709551615"] {
+            let text = format!("replay 1\n{}\n", line);
+            assert!(Replay::parse(&text).unwrap_err().starts_with("line 2: bad"), "{:?}", line);
+        }
+        assert!(Replay::parse("replay 1\n0 board 2000 1\n0 steps 10000\n").is_ok());
+    }
+
+    #[test]
+    fn long_runs_are_recorded_in_pieces() {
+        let board = DenseBoard::new((4, 4));
+        let mut recorder = Recorder::new(&board, 0, "B3/S23".to_string(), 20.0, 0);
+        for _ in 0..MAX_STEPS * 2 + 5 {
+            recorder.stepped(ms(10), &board);
+        }
+        let steps: Vec<Event> = recorder.finish().events.into_iter().skip(4).map(|(_, event)| event).collect();
+        assert_eq!(steps, [Event::Steps(MAX_STEPS), Event::Steps(MAX_STEPS), Event::Steps(5)]);
+    }
+
+    #[test]
+    fn recording_keeps_edits_and_counts_steps() {
+        let rule = Rule::default();
+        let mut board: Box<dyn Board> = Box::new(DenseBoard::new((10, 10)));
+        let mut recorder = Recorder::new(board.as_ref(), 0, rule.to_string(), 20.0, 7);
+
+        // a blinker, painted in, then stepped twice, then the count reset and the rule changed
+        for x in 3..6 {
+            board.set_alive(GridPosition::new(x, 4), true);
+        }
+        for (generation, time) in [100, 150].iter().enumerate() {
+            recorder.observe(ms(*time), board.as_ref(), generation as u64, "B3/S23", 20.0);
+            board = board::step(board.as_ref(), &rule);
+            recorder.stepped(ms(*time), board.as_ref());
+        }
+        recorder.observe(ms(300), board.as_ref(), 0, "B36/S23", 20.0);
+
+        let events: Vec<Event> = recorder.finish().events.into_iter().skip(4).map(|(_, event)| event).collect();
+        assert_eq!(events.len(), 4);
+        assert!(matches!(&events[0], Event::Edits { born, died } if born.len() == 3 && died.is_empty()));
+        assert_eq!(events[1..], [Event::Steps(2), Event::Generation(0), Event::Rule("B36/S23".to_string())]);
+    }
+
+    #[test]
+    fn steps_play_back_spread_over_their_time() {
+        let replay = Replay {
+            seed: 0,
+            events: vec![(ms(0), Event::Speed(10.0)), (ms(1000), Event::Steps(10)), (ms(1000), Event::Generation(0))],
+        };
+        let mut player = Player::new(replay);
+        assert_eq!(player.advance(ms(0)), vec![Event::Speed(10.0)]);
+        assert_eq!(player.advance(ms(450)), vec![Event::Steps(4)]);
+        assert_eq!(player.advance(ms(460)), vec![]);
+        assert!(!player.is_finished());
+        assert_eq!(player.advance(ms(2000)), vec![Event::Steps(6), Event::Generation(0)]);
+        assert!(player.is_finished());
+    }
+}
@@ -0,0 +1,169 @@
+//! A summary of a session as JSON, for looking into it with other tools: the
+//! rule and seed, how the population went, what the board settled into and
+//! the longest lived soups.
+
+use crate::census::Census;
+use crate::search::Lifespan;
+
+/// What the population did over a run of generations.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PopulationCurve {
+    /// How many generations have been recorded after the first.
+    pub generations: u64,
+    pub initial: usize,
+    pub last: usize,
+    pub min: usize,
+    pub max: usize,
+    /// The first generation the population was at its maximum, counted from
+    /// the start of the curve.
+    pub peak_generation: u64,
+    total: u64,
+}
+
+impl PopulationCurve {
+    /// A curve starting from `population`.
+    pub fn new(population: usize) -> Self {
+        PopulationCurve {
+            generations: 0,
+            initial: population,
+            last: population,
+            min: population,
+            max: population,
+            peak_generation: 0,
+            total: population as u64,
+        }
+    }
+
+    /// Add the population of the next generation.
+    pub fn record(&mut self, population: usize) {
+        self.generations += 1;
+        self.last = population;
+        self.min = self.min.min(population);
+        if population > self.max {
+            self.max = population;
+            self.peak_generation = self.generations;
+        }
+        self.total += population as u64;
+    }
+
+    /// The average population over every generation recorded, the first included.
+    pub fn mean(&self) -> f64 {
+        self.total as f64 / (self.generations + 1) as f64
+    }
+}
+
+/// Everything that goes in a report.
+#[derive(Clone, Debug)]
+pub struct Report<'a> {
+    pub rule: String,
+    pub seed: u64,
+    /// The generation the board is at.
+    pub generation: u64,
+    pub population: &'a PopulationCurve,
+    pub census: &'a Census,
+    pub longest_lived: &'a [Lifespan],
+}
+
+impl Report<'_> {
+    /// The report as a JSON object, ending in a newline.
+    pub fn to_json(&self) -> String {
+        let population = self.population;
+        let census: Vec<String> = self.census.iter().map(|(name, count)| format!("    {}: {}", quoted(name), count)).collect();
+        let longest_lived: Vec<String> = self
+            .longest_lived
+            .iter()
+            .map(|lifespan| {
+                format!(
+                    "    {{ \"seed\": {}, \"soup\": {}, \"generations\": {}, \"population\": {} }}",
+                    lifespan.seed, lifespan.soup, lifespan.generations, lifespan.population
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\n  \"rule\": {},\n  \"seed\": {},\n  \"generation\": {},\n  \"generations_run\": {},\n  \
+             \"population\": {{ \"initial\": {}, \"final\": {}, \"min\": {}, \"max\": {}, \"peak_generation\": {}, \"mean\": {:.3} }},\n  \
+             \"census\": {},\n  \"longest_lived\": {}\n}}\n",
+            quoted(&self.rule),
+            self.seed,
+            self.generation,
+            population.generations,
+            population.initial,
+            population.last,
+            population.min,
+            population.max,
+            population.peak_generation,
+            population.mean(),
+            block(&census, '{', '}'),
+            block(&longest_lived, '[', ']'),
+        )
+    }
+}
+
+/// `lines` one to a line between `open` and `close`, or just the two for none.
+fn block(lines: &[String], open: char, close: char) -> String {
+    if lines.is_empty() {
+        format!("{}{}", open, close)
+    } else {
+        format!("{}\n{}\n  {}", open, lines.join(",\n"), close)
+    }
+}
+
+/// `text` as a JSON string.
+fn quoted(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_are_json() {
+        let mut population = PopulationCurve::new(10);
+        for &count in &[14, 20, 8, 12] {
+            population.record(count);
+        }
+        assert_eq!((population.min, population.max, population.peak_generation, population.last), (8, 20, 2, 12));
+        assert_eq!(population.mean(), 12.8);
+
+        let census = vec![("block".to_string(), 3), ("unknown \"blob\"".to_string(), 1)];
+        let report = Report {
+            rule: "B3/S23".to_string(),
+            seed: 42,
+            generation: 4,
+            population: &population,
+            census: &census,
+            longest_lived: &[Lifespan { seed: 42, soup: 0, generations: 4, population: 12 }],
+        };
+
+        let expected = r#"{
+  "rule": "B3/S23",
+  "seed": 42,
+  "generation": 4,
+  "generations_run": 4,
+  "population": { "initial": 10, "final": 12, "min": 8, "max": 20, "peak_generation": 2, "mean": 12.800 },
+  "census": {
+    "block": 3,
+    "unknown \"blob\"": 1
+  },
+  "longest_lived": [
+    { "seed": 42, "soup": 0, "generations": 4, "population": 12 }
+  ]
+}
+"#;
+        assert_eq!(report.to_json(), expected);
+    }
+}
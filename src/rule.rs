@@ -0,0 +1,190 @@
+use std::fmt;
+
+/// A cell and the eight around it, as bits of a mask: the cell `dx` columns
+/// and `dy` rows into the 3x3 block is bit `3 * dy + dx`, so the middle cell
+/// is bit 4.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Neighborhood(pub u16);
+
+impl Neighborhood {
+    const CENTER: u16 = 1 << 4;
+
+    /// Whether the cell at `dx`, `dy` in the block, each from 0 to 2, is alive.
+    pub fn is_alive(self, dx: u8, dy: u8) -> bool {
+        self.0 & 1 << (3 * dy + dx) != 0
+    }
+
+    /// Whether the middle cell is alive.
+    pub fn center(self) -> bool {
+        self.0 & Self::CENTER != 0
+    }
+
+    /// How many of the eight cells around the middle one are alive.
+    pub fn live_neighbors(self) -> u8 {
+        (self.0 & !Self::CENTER).count_ones() as u8
+    }
+}
+
+/// The block as three rows of plaintext, `O` for alive and `.` for dead.
+impl fmt::Display for Neighborhood {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for dy in 0..3 {
+            if dy > 0 {
+                writeln!(f)?;
+            }
+            for dx in 0..3 {
+                write!(f, "{}", if self.is_alive(dx, dy) { 'O' } else { '.' })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Anything that decides a cell's next state from its neighborhood. Life-like
+/// rules only look at the count of live neighbors, but others can look at
+/// which neighbors are alive.
+pub trait Transition {
+    fn next_state(&self, neighborhood: Neighborhood) -> bool;
+}
+
+/// A life-like (outer totalistic) rule: which neighbor counts bring a dead
+/// cell to life and which keep a live cell alive. Bit `n` of each mask stands
+/// for `n` live neighbors.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rule {
+    birth: u16,
+    survival: u16,
+}
+
+/// Well known rules offered in the settings, by name and rulestring.
+pub const PRESETS: &[(&str, &str)] = &[
+    ("Conway's Life", "B3/S23"),
+    ("HighLife", "B36/S23"),
+    ("Seeds", "B2/S"),
+    ("Day & Night", "B3678/S34678"),
+    ("Life without Death", "B3/S012345678"),
+    ("Maze", "B3/S12345"),
+    ("Diamoeba", "B35678/S5678"),
+    ("2x2", "B36/S125"),
+    ("Morley", "B368/S245"),
+    ("Replicator", "B1357/S1357"),
+];
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::parse(PRESETS[0].1).expect("invalid default rule")
+    }
+}
+
+impl Rule {
+    /// Parse a rulestring in `B3/S23` notation, or the older `23/3` survival/birth form.
+    pub fn parse(text: &str) -> Option<Rule> {
+        let parts: Vec<&str> = text.trim().split('/').collect();
+        if parts.len() != 2 {
+            return None;
+        }
+
+        let (mut birth, mut survival) = (None, None);
+        for (index, part) in parts.into_iter().enumerate() {
+            match part.chars().next() {
+                Some('B') | Some('b') => birth = Some(&part[1..]),
+                Some('S') | Some('s') => survival = Some(&part[1..]),
+                // without letters the survival counts come first
+                _ if index == 0 => survival = Some(part),
+                _ => birth = Some(part),
+            }
+        }
+
+        Some(Rule {
+            birth: Self::parse_counts(birth?)?,
+            survival: Self::parse_counts(survival?)?,
+        })
+    }
+
+    /// Look up a preset by name, ignoring case, or else parse a rulestring.
+    pub fn from_name_or_rulestring(text: &str) -> Option<Rule> {
+        PRESETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(text.trim()))
+            .map_or_else(|| Rule::parse(text), |(_, rulestring)| Rule::parse(rulestring))
+    }
+
+    fn parse_counts(digits: &str) -> Option<u16> {
+        digits.chars().try_fold(0, |mask, digit| match digit.to_digit(10) {
+            Some(count) if count <= 8 => Some(mask | 1 << count),
+            _ => None,
+        })
+    }
+
+    /// Whether a cell is alive in the next generation.
+    pub fn next_state(self, alive: bool, neighbors: u8) -> bool {
+        let mask = if alive { self.survival } else { self.birth };
+        mask & (1 << neighbors) != 0
+    }
+
+    /// Flip whether a cell that's `alive` with `neighbors` live neighbors is
+    /// alive in the next generation.
+    pub fn toggle(&mut self, alive: bool, neighbors: u8) {
+        let mask = if alive { &mut self.survival } else { &mut self.birth };
+        *mask ^= 1 << neighbors;
+    }
+
+    /// The preset's name if this rule is one of the presets.
+    pub fn name(self) -> Option<&'static str> {
+        PRESETS
+            .iter()
+            .find(|(_, rulestring)| Rule::parse(rulestring) == Some(self))
+            .map(|(name, _)| *name)
+    }
+}
+
+impl Transition for Rule {
+    fn next_state(&self, neighborhood: Neighborhood) -> bool {
+        Rule::next_state(*self, neighborhood.center(), neighborhood.live_neighbors())
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let counts = |mask: u16| (0..=8).filter(|n| mask & (1 << n) != 0).map(|n| n.to_string()).collect::<String>();
+        write!(f, "B{}/S{}", counts(self.birth), counts(self.survival))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rulestrings_parse_in_either_notation() {
+        let conway = Rule::parse("B3/S23").unwrap();
+        assert_eq!(conway, Rule::default());
+        assert_eq!(Rule::parse("  b3/s23 "), Some(conway));
+        assert_eq!(Rule::parse("S23/B3"), Some(conway));
+        assert_eq!(Rule::parse("23/3"), Some(conway));
+        assert!(conway.next_state(false, 3));
+        assert!(conway.next_state(true, 2));
+        assert!(!conway.next_state(false, 2));
+        assert!(!conway.next_state(true, 4));
+
+        let seeds = Rule::parse("B2/S").unwrap();
+        assert_eq!(seeds.to_string(), "B2/S");
+        assert_eq!(seeds.name(), Some("Seeds"));
+        assert_eq!(Rule::parse("B012345678/S012345678").unwrap().to_string(), "B012345678/S012345678");
+    }
+
+    #[test]
+    fn malformed_rulestrings_are_refused() {
+        for text in ["", "B3", "B3/S23/S4", "B3/B23", "S23/S3", "Bx/S23", "B3/S2 3", "B3,S23"] {
+            assert_eq!(Rule::parse(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn neighbor_counts_past_eight_are_refused() {
+        assert_eq!(Rule::parse("B9/S23"), None);
+        assert_eq!(Rule::parse("B3/S239"), None);
+        assert_eq!(Rule::parse("239/3"), None);
+        assert!(Rule::parse("B3/S238").is_some());
+    }
+}
@@ -0,0 +1,145 @@
+//! Rhai scripts that drive universes, for automating experiments:
+//!
+//! ```text
+//! let u = universe(100, 100);
+//! for soup in 0..100 {
+//!     u.clear();
+//!     u.randomize(0.35);
+//!     u.step(500);
+//!     print(`soup ${soup}: ${u.population()} cells`);
+//! }
+//! ```
+//!
+//! Scripts get `universe(width, height)`, and on a universe `rule()`,
+//! `set_rule(rule)`, `cell(x, y)`, `set_cell(x, y, alive)`,
+//! `place(pattern, x, y)`, `place_centered(pattern)`, `randomize(density)`,
+//! `clear()`, `step()`, `step(count)`, `population()` and `generation()`.
+//! A pattern is the name of a built-in pattern, or RLE or plaintext.
+
+use crate::board::{self, GridPosition, Selection};
+use crate::pattern::Pattern;
+use crate::rule::Rule;
+use crate::universe::Universe;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rhai::{Engine, EvalAltResult};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// A universe as scripts see it. Rhai copies values around, so copies share
+/// the one universe.
+#[derive(Clone)]
+struct ScriptUniverse(Rc<RefCell<Universe>>);
+
+impl ScriptUniverse {
+    fn position(&self, x: i64, y: i64) -> ScriptResult<GridPosition> {
+        let (width, height) = self.0.borrow().board().size();
+        if x < 0 || x >= width as i64 || y < 0 || y >= height as i64 {
+            return Err(format!("({}, {}) is off the {}x{} board", x, y, width, height).into());
+        }
+        Ok(GridPosition::new(x as i16, y as i16))
+    }
+}
+
+fn side(length: i64) -> ScriptResult<i16> {
+    if length >= 1 && length <= i16::MAX as i64 {
+        Ok(length as i16)
+    } else {
+        Err(format!("invalid board size {}", length).into())
+    }
+}
+
+/// Run `source`, with random soups drawn from `seed`.
+pub fn run(source: &str, seed: u64) -> Result<(), String> {
+    let rng = Rc::new(RefCell::new(StdRng::seed_from_u64(seed)));
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<ScriptUniverse>("Universe")
+        .register_fn("universe", |width: i64, height: i64| -> ScriptResult<ScriptUniverse> {
+            Ok(ScriptUniverse(Rc::new(RefCell::new(Universe::new((side(width)?, side(height)?))))))
+        })
+        .register_fn("rule", |universe: &mut ScriptUniverse| universe.0.borrow().rule().to_string())
+        .register_fn("set_rule", |universe: &mut ScriptUniverse, rule: &str| -> ScriptResult<()> {
+            let rule = Rule::from_name_or_rulestring(rule).ok_or_else(|| format!("unknown rule `{}`", rule))?;
+            universe.0.borrow_mut().set_rule(rule);
+            Ok(())
+        })
+        .register_fn("cell", |universe: &mut ScriptUniverse, x: i64, y: i64| -> ScriptResult<bool> {
+            let pos = universe.position(x, y)?;
+            Ok(universe.0.borrow().is_alive(pos))
+        })
+        .register_fn("set_cell", |universe: &mut ScriptUniverse, x: i64, y: i64, alive: bool| -> ScriptResult<()> {
+            let pos = universe.position(x, y)?;
+            universe.0.borrow_mut().board_mut().set_alive(pos, alive);
+            Ok(())
+        })
+        .register_fn("place", |universe: &mut ScriptUniverse, text: &str, x: i64, y: i64| -> ScriptResult<()> {
+            let pos = universe.position(x, y)?;
+            universe.0.borrow_mut().place(&Pattern::from_name_or_text(text), pos);
+            Ok(())
+        })
+        .register_fn("place_centered", |universe: &mut ScriptUniverse, text: &str| {
+            universe.0.borrow_mut().place_centered(&Pattern::from_name_or_text(text));
+        })
+        .register_fn("randomize", move |universe: &mut ScriptUniverse, density: f64| -> ScriptResult<()> {
+            if !(0.0..=1.0).contains(&density) {
+                return Err(format!("invalid density {}, expected a fraction between 0 and 1", density).into());
+            }
+            let mut universe = universe.0.borrow_mut();
+            let whole_board = Selection::whole_board(universe.board().size());
+            board::fill_random(universe.board_mut(), &whole_board, density as f32, &mut *rng.borrow_mut());
+            Ok(())
+        })
+        .register_fn("clear", |universe: &mut ScriptUniverse| {
+            let mut universe = universe.0.borrow_mut();
+            let whole_board = Selection::whole_board(universe.board().size());
+            board::set_region(universe.board_mut(), &whole_board, true);
+        })
+        .register_fn("step", |universe: &mut ScriptUniverse| universe.0.borrow_mut().step())
+        .register_fn("step", |universe: &mut ScriptUniverse, count: i64| universe.0.borrow_mut().step_by(count.max(0) as u64))
+        .register_fn("population", |universe: &mut ScriptUniverse| universe.0.borrow().population() as i64)
+        .register_fn("generation", |universe: &mut ScriptUniverse| universe.0.borrow().generation() as i64);
+
+    engine.run(source).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_step_a_board() {
+        let script = r#"
+            let u = universe(20, 20);
+            u.place("x = 3, y = 3\nbo$2bo$3o!", 5, 5);
+            u.step(4);
+            if u.generation() != 4 || u.population() != 5 {
+                throw `generation ${u.generation()} with ${u.population()} cells`;
+            }
+            // a glider moves one cell down and right every four generations
+            for cell in [[7, 6], [8, 7], [6, 8], [7, 8], [8, 8]] {
+                if !u.cell(cell[0], cell[1]) {
+                    throw `no cell at ${cell}`;
+                }
+            }
+            u.set_rule("Seeds");
+            u.step();
+            if u.rule() != "B2/S" {
+                throw `ran under ${u.rule()}`;
+            }
+        "#;
+        assert_eq!(run(script, 0), Ok(()));
+    }
+
+    #[test]
+    fn scripts_stop_at_the_first_error() {
+        let err = run("let u = universe(10, 10); u.set_cell(10, 0, true);", 0).unwrap_err();
+        assert!(err.contains("(10, 0) is off the 10x10 board"), "{}", err);
+        assert!(run("universe(0, 10);", 0).unwrap_err().contains("invalid board size 0"));
+    }
+}
@@ -0,0 +1,130 @@
+//! The results of soup searches: random soups run until they settle, with a
+//! census of the ash each one leaves behind, and the longest lived of them.
+
+use crate::census::{self, Census};
+
+use std::fmt;
+
+/// Objects that turn up in almost every soup. Anything else in a census is a
+/// rare find worth pointing out.
+pub const COMMON_OBJECTS: &[&str] = &["block", "blinker", "beehive", "loaf", "boat", "ship", "tub", "glider"];
+
+/// The columns of a results file, in the order `SoupResult` writes them.
+pub const RESULTS_HEADER: &str = "seed\tsoup\tsettled at\tcensus";
+
+/// How one soup turned out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoupResult {
+    /// The seed the soup was numbered from, and its number.
+    pub seed: u64,
+    pub soup: u64,
+    /// The generation it settled into still lifes and oscillators, or `None`
+    /// if it was still going when the search gave up on it.
+    pub settled_at: Option<u64>,
+    pub census: Census,
+}
+
+impl SoupResult {
+    /// The kinds of object in the census that aren't common.
+    pub fn rare_objects(&self) -> Census {
+        self.census.iter().filter(|(name, _)| Self::is_rare(name)).cloned().collect()
+    }
+
+    /// Objects the census doesn't recognize are usually common ones touching,
+    /// so only the rarer known objects count.
+    fn is_rare(name: &str) -> bool {
+        census::OBJECTS.iter().any(|&(object, _)| object == name) && !COMMON_OBJECTS.contains(&name)
+    }
+}
+
+/// A line of a results file, tab separated like `RESULTS_HEADER`.
+impl fmt::Display for SoupResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let settled_at = self.settled_at.map_or("-".to_string(), |generation| generation.to_string());
+        write!(f, "{}\t{}\t{}\t{}", self.seed, self.soup, settled_at, census::summary(&self.census, usize::MAX))
+    }
+}
+
+/// How long a soup ran before it settled, and how much of it was left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lifespan {
+    pub seed: u64,
+    pub soup: u64,
+    pub generations: u64,
+    pub population: usize,
+}
+
+impl fmt::Display for Lifespan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "soup {} of seed {}: {} generations, {} cells left", self.soup, self.seed, self.generations, self.population)
+    }
+}
+
+/// The longest lived soups seen, longest first. Soups that live as long as
+/// one already on the board go after it.
+#[derive(Clone, Debug)]
+pub struct Leaderboard {
+    lifespans: Vec<Lifespan>,
+    capacity: usize,
+}
+
+impl Leaderboard {
+    /// An empty leaderboard with room for `capacity` soups.
+    pub fn new(capacity: usize) -> Self {
+        Leaderboard { lifespans: Vec::with_capacity(capacity + 1), capacity }
+    }
+
+    /// Add `lifespan` if it's long enough, replacing any earlier run of the
+    /// same soup, and return its place, counting from 0.
+    pub fn record(&mut self, lifespan: Lifespan) -> Option<usize> {
+        self.lifespans.retain(|other| (other.seed, other.soup) != (lifespan.seed, lifespan.soup));
+
+        let place = self.lifespans.iter().take_while(|other| other.generations >= lifespan.generations).count();
+        if place >= self.capacity {
+            return None;
+        }
+        self.lifespans.insert(place, lifespan);
+        self.lifespans.truncate(self.capacity);
+        Some(place)
+    }
+
+    pub fn lifespans(&self) -> &[Lifespan] {
+        &self.lifespans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_rare_known_objects_are_finds() {
+        let result = SoupResult {
+            seed: 7,
+            soup: 3,
+            settled_at: Some(812),
+            census: vec![
+                ("block".to_string(), 9),
+                ("unknown 12-cell object".to_string(), 2),
+                ("pond".to_string(), 1),
+            ],
+        };
+
+        assert_eq!(result.rare_objects(), vec![("pond".to_string(), 1)]);
+        assert_eq!(result.to_string(), "7\t3\t812\t9 block, 2 unknown 12-cell object, 1 pond");
+    }
+
+    #[test]
+    fn the_leaderboard_keeps_the_longest_lived() {
+        let soup = |soup, generations| Lifespan { seed: 1, soup, generations, population: 10 };
+        let mut leaderboard = Leaderboard::new(2);
+
+        assert_eq!(leaderboard.record(soup(0, 300)), Some(0));
+        assert_eq!(leaderboard.record(soup(1, 900)), Some(0));
+        assert_eq!(leaderboard.record(soup(2, 300)), None);
+        assert_eq!(leaderboard.record(soup(3, 500)), Some(1));
+        // running soup 1 again replaces it rather than taking a second place
+        assert_eq!(leaderboard.record(soup(1, 900)), Some(0));
+        assert_eq!(leaderboard.lifespans(), &[soup(1, 900), soup(3, 500)]);
+    }
+}
@@ -0,0 +1,149 @@
+//! Share codes: a whole board in one string, short enough to paste into a
+//! chat, so an exact setup can be passed on without a file. A code is
+//! `life:` and then, in URL-safe base64, the deflated text
+//!
+//! ```text
+//! 200 150 98 73
+//! x = 3, y = 3, rule = B3/S23
+//! bo$2bo$3o!
+//! ```
+//!
+//! which is the board's size, where its pattern goes, and the pattern with
+//! its rule as RLE.
+
+use crate::board::{Board, GridPosition};
+use crate::net::{MAX_BOARD_SIZE, MIN_BOARD_SIZE};
+use crate::pattern::Pattern;
+use crate::rule::Rule;
+
+/// What every share code starts with.
+const PREFIX: &str = "life:";
+
+/// Everything a share code holds: a board of `size` with `pattern` at
+/// `origin`, run under `rule`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Setup {
+    pub size: (i16, i16),
+    pub origin: GridPosition,
+    pub pattern: Pattern,
+    pub rule: Rule,
+}
+
+impl Setup {
+    /// The live cells on `board`, and where they are, under `rule`.
+    pub fn capture(board: &dyn Board, rule: Rule) -> Self {
+        let origin = board.bounds().map_or(GridPosition::new(0, 0), |bounds| bounds.bounds().0);
+        Setup { size: board.size(), origin, pattern: Pattern::capture_live(board), rule }
+    }
+
+    /// Put the setup's pattern on `board`, which should be blank and its size.
+    pub fn stamp(&self, board: &mut dyn Board) {
+        self.pattern.stamp(board, self.origin);
+    }
+
+    pub fn to_code(&self) -> String {
+        let text = format!("{} {} {} {}\n{}", self.size.0, self.size.1, self.origin.x, self.origin.y, self.pattern.to_rle(self.rule));
+        format!("{}{}", PREFIX, base64::encode_config(&deflate::deflate_bytes_zlib(text.as_bytes()), base64::URL_SAFE_NO_PAD))
+    }
+
+    /// The setup in a code made by `to_code`. Whitespace in it, from being
+    /// wrapped in a chat, is left out.
+    pub fn from_code(code: &str) -> Result<Self, String> {
+        let code: String = code.split_whitespace().collect();
+        let code = code.strip_prefix(PREFIX).ok_or("not a share code")?;
+        let bytes = base64::decode_config(code, base64::URL_SAFE_NO_PAD).map_err(|_| "a share code with a typo in it")?;
+        let text = inflate::inflate_bytes_zlib(&bytes).map_err(|_| "a share code that's been mangled")?;
+        let text = String::from_utf8(text).map_err(|_| "a share code that's been mangled")?;
+        // the stream stops wherever the code does, so it's only whole if the
+        // RLE gets to its end
+        if !text.trim_end().ends_with('!') {
+            return Err("a share code that's been cut short".to_string());
+        }
+
+        let bad = || "a share code that's been mangled".to_string();
+        let (placement, rle) = text.split_once('\n').ok_or_else(bad)?;
+        let numbers: Vec<i16> = placement.split_whitespace().map(str::parse).collect::<Result<_, _>>().map_err(|_| bad())?;
+        let (size, origin) = match numbers[..] {
+            [width, height, x, y] => ((width, height), GridPosition::new(x, y)),
+            _ => return Err(bad()),
+        };
+        let sizes = MIN_BOARD_SIZE..=MAX_BOARD_SIZE;
+        if !sizes.contains(&size.0) || !sizes.contains(&size.1) {
+            return Err(format!("a share code for a {}x{} board, which is too big or too small", size.0, size.1));
+        }
+        let pattern = Pattern::from_rle(rle).ok_or_else(bad)?;
+        let rule = rle_rule(rle).ok_or_else(bad)?;
+        Ok(Setup { size, origin, pattern, rule })
+    }
+}
+
+/// The rule in the header of `rle`, or Life if it doesn't say.
+fn rle_rule(rle: &str) -> Option<Rule> {
+    let header = rle.lines().next()?;
+    match header.split(',').find_map(|field| field.trim().strip_prefix("rule")) {
+        Some(rule) => Rule::parse(rule.trim_start().strip_prefix('=')?),
+        None => Some(Rule::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{DenseBoard, Selection};
+
+    #[test]
+    fn setups_round_trip_through_codes() {
+        let mut board = DenseBoard::new((200, 150));
+        Pattern::from_plaintext(".O.\n..O\nOOO").stamp(&mut board, GridPosition::new(98, 73));
+        let setup = Setup::capture(&board, Rule::parse("B36/S23").unwrap());
+        assert_eq!(setup.origin, GridPosition::new(98, 73));
+
+        let code = setup.to_code();
+        assert!(code.starts_with("life:"));
+        let wrapped = format!("  {}\n{}  ", &code[..20], &code[20..]);
+        let shared = Setup::from_code(&wrapped).unwrap();
+        assert_eq!((shared.size, shared.origin, shared.rule), (setup.size, setup.origin, setup.rule));
+
+        let mut copy = DenseBoard::new(shared.size);
+        shared.stamp(&mut copy);
+        assert_eq!(copy.live_cells().collect::<Vec<_>>(), board.live_cells().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn codes_stay_short() {
+        let mut board = DenseBoard::new((200, 150));
+        Pattern::from_plaintext(".O.\n..O\nOOO").stamp(&mut board, GridPosition::new(98, 73));
+        let code = Setup::capture(&board, Rule::default()).to_code();
+        assert!(code.len() < 80, "{} is {} long", code, code.len());
+
+        for pos in Selection::whole_board((200, 150)).positions().filter(|pos| (pos.x * 7 + pos.y * 3) % 11 == 0) {
+            board.set_alive(pos, true);
+        }
+        let setup = Setup::capture(&board, Rule::default());
+        let code = setup.to_code();
+        assert!(code.len() < setup.pattern.to_rle(setup.rule).len() / 2, "{} long", code.len());
+    }
+
+    #[test]
+    fn bad_codes_say_what_is_wrong() {
+        assert_eq!(Setup::from_code("hello"), Err("not a share code".to_string()));
+        assert!(Setup::from_code("life:!!!").is_err());
+        let mut board = DenseBoard::new((40, 40));
+        Pattern::from_plaintext("OO.O\nO..O\n..OO\nO.O.").stamp(&mut board, GridPosition::new(5, 9));
+        let code = Setup::capture(&board, Rule::default()).to_code();
+        assert_eq!(Setup::from_code(&code[..code.len() / 2]), Err("a share code that's been cut short".to_string()));
+
+        let code = Setup::capture(&DenseBoard::new((16, 16)), Rule::default()).to_code();
+        assert_eq!(Setup::from_code(&code).map(|setup| setup.pattern.cells.len()), Ok(0));
+    }
+
+    #[test]
+    fn codes_for_boards_out_of_range_are_refused() {
+        let code = |size| Setup { size, origin: GridPosition::new(0, 0), pattern: Pattern::from_plaintext("O"), rule: Rule::default() }.to_code();
+        assert_eq!(Setup::from_code(&code((32767, 32767))), Err("a share code for a 32767x32767 board, which is too big or too small".to_string()));
+        assert!(Setup::from_code(&code((2001, 100))).is_err());
+        assert!(Setup::from_code(&code((100, 15))).is_err());
+        assert!(Setup::from_code(&code((0, -3))).is_err());
+        assert!(Setup::from_code(&code((2000, 16))).is_ok());
+    }
+}
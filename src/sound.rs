@@ -0,0 +1,221 @@
+//! Sounds made from scratch rather than loaded from files: short tones,
+//! written out as WAV so any audio backend can play them, how loud the
+//! board's activity should sound, and the board played as music, with its
+//! columns as pitches and its rows as instruments.
+
+use crate::board::Board;
+
+/// Samples a second in every sound made here.
+pub const SAMPLE_RATE: u32 = 44_100;
+
+/// A tone of `frequency` Hz lasting `duration` seconds, starting at full
+/// volume and dying away to nothing, as samples between -1 and 1.
+pub fn tone(frequency: f32, duration: f32) -> Vec<f32> {
+    let count = (duration * SAMPLE_RATE as f32) as usize;
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let fade = 1.0 - i as f32 / count as f32;
+            (t * frequency * std::f32::consts::TAU).sin() * fade * fade
+        })
+        .collect()
+}
+
+/// `samples`, between -1 and 1, as a 16-bit mono WAV file.
+pub fn to_wav(samples: &[f32]) -> Vec<u8> {
+    let data_len = samples.len() as u32 * 2;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // plain PCM, one channel
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for &sample in samples {
+        wav.extend_from_slice(&((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    wav
+}
+
+/// How loud to make `changes` births or deaths a generation on a board of
+/// `cells` cells, from 0 to 1. It goes by the logarithm, so a few changes
+/// can be heard and a board in chaos doesn't drown everything out, and it's
+/// at its loudest with a tenth of the board changing.
+pub fn loudness(changes: f32, cells: usize) -> f32 {
+    let loudest = (cells as f32 / 10.0).max(1.0);
+    (changes.ln_1p() / loudest.ln_1p()).clamp(0.0, 1.0)
+}
+
+/// The notes of the major pentatonic scale, in semitones above its root,
+/// which sound fine together whichever of them a board picks.
+const SCALE: [u32; 5] = [0, 2, 4, 7, 9];
+/// How many octaves of the scale the columns are spread over.
+const OCTAVES: u32 = 3;
+/// The pitch of the leftmost column, in Hz.
+const ROOT: f32 = 220.0;
+
+/// What plays a note, from the top rows of the board down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instrument {
+    Sine,
+    Triangle,
+    Square,
+    Saw,
+}
+
+pub const INSTRUMENTS: [Instrument; 4] = [Instrument::Sine, Instrument::Triangle, Instrument::Square, Instrument::Saw];
+
+impl Instrument {
+    /// The instrument's wave at `phase`, in cycles, between -1 and 1.
+    fn wave(self, phase: f32) -> f32 {
+        let phase = phase.fract();
+        match self {
+            Instrument::Sine => (phase * std::f32::consts::TAU).sin(),
+            Instrument::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+            // the harsher waves go quieter so they don't drown out the others
+            Instrument::Square => if phase < 0.5 { 0.5 } else { -0.5 },
+            Instrument::Saw => phase - 0.5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Note {
+    pub frequency: f32,
+    pub instrument: Instrument,
+    /// From 0 to 1.
+    pub loudness: f32,
+}
+
+/// The notes `board` plays, the busiest `most` of them: a pitch up the scale
+/// for each band of columns with anything alive in it, played by the
+/// instrument for the band of rows most of those cells are in, and as loud
+/// as it has cells next to the busiest pitch.
+pub fn notes(board: &dyn Board, most: usize) -> Vec<Note> {
+    let (width, height) = board.size();
+    let pitches = SCALE.len() * OCTAVES as usize;
+    let mut counts = vec![[0usize; INSTRUMENTS.len()]; pitches];
+    for pos in board.live_cells() {
+        let pitch = pos.x as usize * pitches / width.max(1) as usize;
+        let instrument = pos.y as usize * INSTRUMENTS.len() / height.max(1) as usize;
+        counts[pitch.min(pitches - 1)][instrument.min(INSTRUMENTS.len() - 1)] += 1;
+    }
+
+    let mut notes: Vec<(usize, usize, usize)> = counts
+        .iter()
+        .enumerate()
+        .filter_map(|(pitch, bands)| {
+            let cells: usize = bands.iter().sum();
+            let busiest = (0..bands.len()).max_by_key(|&band| (bands[band], std::cmp::Reverse(band)))?;
+            if cells > 0 {
+                Some((pitch, busiest, cells))
+            } else {
+                None
+            }
+        })
+        .collect();
+    notes.sort_by_key(|&(pitch, _, cells)| (std::cmp::Reverse(cells), pitch));
+    notes.truncate(most);
+
+    let loudest = notes.first().map_or(1, |&(_, _, cells)| cells) as f32;
+    let mut notes: Vec<Note> = notes
+        .into_iter()
+        .map(|(pitch, instrument, cells)| {
+            let semitones = SCALE[pitch % SCALE.len()] + 12 * (pitch / SCALE.len()) as u32;
+            Note {
+                frequency: ROOT * 2f32.powf(semitones as f32 / 12.0),
+                instrument: INSTRUMENTS[instrument],
+                loudness: (cells as f32 / loudest).sqrt(),
+            }
+        })
+        .collect();
+    notes.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+    notes
+}
+
+/// `notes` played together for `duration` seconds, quickly coming in and
+/// slowly dying away to nothing just as the next generation's comes in.
+pub fn chord(notes: &[Note], duration: f32) -> Vec<f32> {
+    let count = (duration * SAMPLE_RATE as f32) as usize;
+    let attack = (count / 20).max(1);
+    let share = 1.0 / (notes.len().max(1) as f32).sqrt();
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let envelope = if i < attack { i as f32 / attack as f32 } else { 1.0 - (i - attack) as f32 / (count - attack) as f32 };
+            let mix: f32 = notes.iter().map(|note| note.instrument.wave(t * note.frequency) * note.loudness).sum();
+            (mix * share * envelope).clamp(-1.0, 1.0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tones_fade_out() {
+        let samples = tone(440.0, 0.05);
+        assert_eq!(samples.len(), 2205);
+        assert!(samples.iter().all(|sample| sample.abs() <= 1.0));
+        let loudest = |samples: &[f32]| samples.iter().fold(0.0f32, |loudest, sample| loudest.max(sample.abs()));
+        assert!(loudest(&samples[..200]) > 0.9);
+        assert!(loudest(&samples[2000..]) < 0.05);
+    }
+
+    #[test]
+    fn wavs_have_a_header_and_every_sample() {
+        let wav = to_wav(&[0.0, 1.0, -1.0, 2.0]);
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u32::from_le_bytes([wav[40], wav[41], wav[42], wav[43]]), 8);
+        assert_eq!(&wav[44..], &[0, 0, 0xff, 0x7f, 0x01, 0x80, 0xff, 0x7f]);
+    }
+
+    #[test]
+    fn columns_are_pitches_and_rows_are_instruments() {
+        use crate::board::{DenseBoard, GridPosition};
+
+        let mut board = DenseBoard::new((150, 40));
+        // two cells on the left near the top, one near the right at the bottom
+        board.set_alive(GridPosition::new(0, 0), true);
+        board.set_alive(GridPosition::new(1, 2), true);
+        board.set_alive(GridPosition::new(149, 39), true);
+
+        let notes = notes(&board, 8);
+        assert_eq!(notes.len(), 2);
+        assert_eq!((notes[0].frequency, notes[0].instrument, notes[0].loudness), (ROOT, Instrument::Sine, 1.0));
+        assert_eq!(notes[1].instrument, Instrument::Saw);
+        assert!((notes[1].frequency - ROOT * 2f32.powf((9 + 24) as f32 / 12.0)).abs() < 0.01);
+        assert!((notes[1].loudness - 0.5f32.sqrt()).abs() < 1e-6);
+
+        assert_eq!(super::notes(&board, 1).len(), 1);
+        assert!(super::notes(&DenseBoard::new((10, 10)), 8).is_empty());
+    }
+
+    #[test]
+    fn chords_last_a_generation() {
+        let note = Note { frequency: 440.0, instrument: Instrument::Square, loudness: 1.0 };
+        let samples = chord(&[note, note, note, note], 0.1);
+        assert_eq!(samples.len(), 4410);
+        assert_eq!(samples[0], 0.0);
+        assert!(samples.iter().all(|sample| sample.abs() <= 1.0));
+        assert!(chord(&[], 0.1).iter().all(|&sample| sample == 0.0));
+    }
+
+    #[test]
+    fn loudness_grows_slowly() {
+        assert_eq!(loudness(0.0, 30_000), 0.0);
+        assert!(loudness(10.0, 30_000) < loudness(100.0, 30_000));
+        assert!(loudness(10.0, 30_000) > 0.2);
+        assert_eq!(loudness(5000.0, 30_000), 1.0);
+        assert_eq!(loudness(3.0, 0), 1.0);
+    }
+}
@@ -0,0 +1,184 @@
+//! Numbers for studying how a board behaves over time: how much of it is
+//! alive, how disordered the live cells are, and where it's changing.
+
+use crate::board::{Board, GridPosition};
+
+use std::collections::{HashMap, VecDeque};
+
+/// The share of the board's cells that are alive, from 0 to 1.
+pub fn density(board: &dyn Board) -> f64 {
+    let (width, height) = board.size();
+    board.population() as f64 / (width as f64 * height as f64)
+}
+
+/// The Shannon entropy, in bits, of the 2x2 blocks the board divides into:
+/// 0 for a board that's all one block pattern, up to 4 when all sixteen
+/// turn up equally. Only live cells are looked at, so it costs in proportion
+/// to the population rather than the size of the board.
+pub fn block_entropy(board: &dyn Board) -> f64 {
+    let (width, height) = board.size();
+    let blocks = ((width as usize).div_ceil(2) * (height as usize).div_ceil(2)) as f64;
+
+    // which of its four cells are alive, for every block with any
+    let mut patterns: HashMap<(i16, i16), u8> = HashMap::new();
+    for pos in board.live_cells() {
+        *patterns.entry((pos.x / 2, pos.y / 2)).or_insert(0) |= 1 << ((pos.y % 2) * 2 + pos.x % 2);
+    }
+
+    let mut counts = [0usize; 16];
+    counts[0] = blocks as usize - patterns.len();
+    for &pattern in patterns.values() {
+        counts[pattern as usize] += 1;
+    }
+
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / blocks;
+            p * (1.0 / p).log2()
+        })
+        .sum()
+}
+
+/// Births and deaths in each square region of a board over its last few
+/// generations, to find where the action is.
+#[derive(Clone, Debug)]
+pub struct Activity {
+    region_size: i16,
+    columns: usize,
+    rows: usize,
+    window: usize,
+    /// Births and deaths in each region, row by row, for each generation in
+    /// the window, oldest first, and added up over all of them.
+    recent: VecDeque<Vec<(u32, u32)>>,
+    totals: Vec<(u32, u32)>,
+}
+
+impl Activity {
+    /// Follow a board `board_size` cells big in regions `region_size` cells
+    /// square, over the last `window` generations.
+    pub fn new(board_size: (i16, i16), region_size: i16, window: usize) -> Self {
+        let columns = (board_size.0 as usize).div_ceil(region_size as usize);
+        let rows = (board_size.1 as usize).div_ceil(region_size as usize);
+        Activity { region_size, columns, rows, window, recent: VecDeque::with_capacity(window), totals: vec![(0, 0); columns * rows] }
+    }
+
+    /// Count the cells born and the cells that died in a generation, forgetting
+    /// the oldest generation if the window is full.
+    pub fn record(&mut self, births: &[GridPosition], deaths: &[GridPosition]) {
+        let mut counts = if self.recent.len() == self.window { self.forget_oldest() } else { vec![(0, 0); self.totals.len()] };
+
+        for pos in births {
+            counts[self.index(*pos)].0 += 1;
+        }
+        for pos in deaths {
+            counts[self.index(*pos)].1 += 1;
+        }
+        for (total, count) in self.totals.iter_mut().zip(&counts) {
+            total.0 += count.0;
+            total.1 += count.1;
+        }
+        self.recent.push_back(counts);
+    }
+
+    /// Take the oldest generation out of the totals, and hand back its
+    /// counts zeroed for reuse.
+    fn forget_oldest(&mut self) -> Vec<(u32, u32)> {
+        let mut oldest = self.recent.pop_front().unwrap_or_default();
+        for (total, count) in self.totals.iter_mut().zip(&mut oldest) {
+            total.0 -= count.0;
+            total.1 -= count.1;
+            *count = (0, 0);
+        }
+        oldest
+    }
+
+    fn index(&self, pos: GridPosition) -> usize {
+        (pos.y / self.region_size) as usize * self.columns + (pos.x / self.region_size) as usize
+    }
+
+    pub fn region_size(&self) -> i16 {
+        self.region_size
+    }
+
+    /// How many regions across and down the board is divided into.
+    pub fn regions(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+
+    /// How many generations the counts cover, up to the window.
+    pub fn generations(&self) -> usize {
+        self.recent.len()
+    }
+
+    /// The births and deaths in the region `column` across and `row` down.
+    pub fn region(&self, column: usize, row: usize) -> (u32, u32) {
+        self.totals[row * self.columns + column]
+    }
+
+    /// The column and row of the region with the most births and deaths
+    /// together, the first of them if there's a tie, unless nothing has
+    /// happened anywhere.
+    pub fn busiest(&self) -> Option<(usize, usize)> {
+        let (index, _) = self
+            .totals
+            .iter()
+            .enumerate()
+            .filter(|&(_, &(births, deaths))| births + deaths > 0)
+            .max_by_key(|&(index, &(births, deaths))| (births + deaths, std::cmp::Reverse(index)))?;
+        Some((index % self.columns, index / self.columns))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{DenseBoard, GridPosition};
+
+    #[test]
+    fn empty_and_full_boards_are_ordered() {
+        let mut board = DenseBoard::new((4, 4));
+        assert_eq!(density(&board), 0.0);
+        assert_eq!(block_entropy(&board), 0.0);
+
+        for y in 0..4 {
+            for x in 0..4 {
+                board.set_alive(GridPosition::new(x, y), true);
+            }
+        }
+        assert_eq!(density(&board), 1.0);
+        assert_eq!(block_entropy(&board), 0.0);
+    }
+
+    #[test]
+    fn half_empty_blocks_give_one_bit() {
+        // two of the four blocks have their top-left cell alive
+        let mut board = DenseBoard::new((4, 4));
+        board.set_alive(GridPosition::new(0, 0), true);
+        board.set_alive(GridPosition::new(2, 2), true);
+
+        assert_eq!(density(&board), 2.0 / 16.0);
+        assert!((block_entropy(&board) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn activity_counts_births_and_deaths_in_a_window() {
+        let mut activity = Activity::new((20, 10), 8, 2);
+        assert_eq!(activity.regions(), (3, 2));
+        assert_eq!(activity.busiest(), None);
+
+        activity.record(&[GridPosition::new(1, 1), GridPosition::new(18, 9)], &[GridPosition::new(2, 1)]);
+        activity.record(&[GridPosition::new(17, 8)], &[]);
+        assert_eq!(activity.region(0, 0), (1, 1));
+        assert_eq!(activity.region(2, 1), (2, 0));
+        assert_eq!(activity.busiest(), Some((0, 0)));
+
+        // the first generation drops out of the window
+        activity.record(&[], &[GridPosition::new(19, 9)]);
+        assert_eq!(activity.generations(), 2);
+        assert_eq!(activity.region(0, 0), (0, 0));
+        assert_eq!(activity.region(2, 1), (1, 1));
+        assert_eq!(activity.busiest(), Some((2, 1)));
+    }
+}
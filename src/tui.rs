@@ -0,0 +1,136 @@
+//! A terminal frontend, for playing over SSH or without a GPU. Each character
+//! shows two cells, one above the other, with half block characters.
+
+use crate::options::Options;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::Print;
+use crossterm::{cursor, execute, queue, terminal};
+use life::board::{self, GridPosition, Selection};
+use life::pattern::Pattern;
+use life::universe::Universe;
+use rand::rngs::StdRng;
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+const HELP: &str = "space run/pause  n step  +/- speed  arrows pan  r soup  c clear  q quit";
+
+/// Run the game in the terminal until it's quit.
+pub fn run(options: &Options, pattern: Option<&Pattern>) -> Result<(), String> {
+    let (universe, rng, seed) = crate::start_universe(options, pattern);
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode().map_err(|err| err.to_string())?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide).map_err(|err| err.to_string())?;
+
+    let result = Terminal { universe, rng, options, view: (0, 0), running: false, speed: options.speed }.run(&mut stdout);
+
+    // put the terminal back however the game ended
+    let _ = execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+    println!("seed {} (start with --seed {} to repeat this run)", seed, seed);
+    result.map_err(|err| err.to_string())
+}
+
+struct Terminal<'a> {
+    universe: Universe,
+    rng: StdRng,
+    options: &'a Options,
+    /// The top-left cell on screen.
+    view: (i16, i16),
+    running: bool,
+    speed: f32,
+}
+
+impl Terminal<'_> {
+    fn run(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let mut last_step = Instant::now();
+
+        loop {
+            self.draw(out)?;
+
+            let wait = Duration::from_secs_f32(1.0 / self.speed).saturating_sub(last_step.elapsed());
+            if event::poll(if self.running { wait } else { Duration::from_millis(250) })? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind != KeyEventKind::Release && !self.handle_key(key.code) {
+                        return Ok(());
+                    }
+                }
+            }
+
+            if self.running && last_step.elapsed().as_secs_f32() >= 1.0 / self.speed {
+                self.universe.step();
+                last_step = Instant::now();
+            }
+        }
+    }
+
+    /// React to a key press. False means quit.
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        let (width, height) = self.universe.board().size();
+        let pan = |view: i16, by: i16, side: i16| (view + by).clamp(0, (side - 1).max(0));
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char(' ') => self.running = !self.running,
+            KeyCode::Char('n') => self.universe.step(),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.speed = (self.speed * 2.0).min(240.0),
+            KeyCode::Char('-') => self.speed = (self.speed / 2.0).max(1.0),
+            KeyCode::Char('r') => {
+                let density = self.options.density.unwrap_or(crate::DENSITY);
+                board::fill_random(self.universe.board_mut(), &Selection::whole_board((width, height)), density, &mut self.rng);
+            }
+            KeyCode::Char('c') => board::set_region(self.universe.board_mut(), &Selection::whole_board((width, height)), true),
+            KeyCode::Left => self.view.0 = pan(self.view.0, -8, width),
+            KeyCode::Right => self.view.0 = pan(self.view.0, 8, width),
+            KeyCode::Up => self.view.1 = pan(self.view.1, -8, height),
+            KeyCode::Down => self.view.1 = pan(self.view.1, 8, height),
+            _ => {},
+        }
+
+        true
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let (columns, rows) = terminal::size()?;
+        let (width, height) = self.universe.board().size();
+        let alive = |x: i16, y: i16| x < width && y < height && self.universe.is_alive(GridPosition::new(x, y));
+
+        // the last two rows are for the status line and the help
+        for row in 0..rows.saturating_sub(2) {
+            let y = self.view.1 + row as i16 * 2;
+            let line: String = (0..columns as i16)
+                .map(|column| {
+                    let x = self.view.0 + column;
+                    match (alive(x, y), alive(x, y + 1)) {
+                        (true, true) => '█',
+                        (true, false) => '▀',
+                        (false, true) => '▄',
+                        (false, false) => ' ',
+                    }
+                })
+                .collect();
+            queue!(out, cursor::MoveTo(0, row), Print(line))?;
+        }
+
+        let status = format!(
+            "{}  {} gen/s  generation {}  population {}{}",
+            self.universe.rule(),
+            self.speed,
+            self.universe.generation(),
+            self.universe.population(),
+            if self.running { "" } else { "  paused" },
+        );
+        queue!(
+            out,
+            cursor::MoveTo(0, rows.saturating_sub(2)),
+            terminal::Clear(terminal::ClearType::CurrentLine),
+            Print(status),
+            cursor::MoveTo(0, rows.saturating_sub(1)),
+            terminal::Clear(terminal::ClearType::CurrentLine),
+            Print(HELP),
+        )?;
+        out.flush()
+    }
+}
@@ -0,0 +1,105 @@
+//! A guided tour of the basics for new players: a prompt at a time, each
+//! waiting for the player to actually do what it asks before moving on.
+
+use crate::keybindings::{Action, KeyBindings};
+
+/// Something the player did that a step of the tutorial might be waiting for.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Event {
+    Performed(Action),
+    /// Cells painted with the mouse or the keyboard cursor.
+    Painted,
+    /// A pattern stamped onto the board, with its name if it came from the library.
+    Stamped(Option<&'static str>),
+    /// The board stepped on a generation.
+    Stepped,
+}
+
+struct Step {
+    /// What to do, with `{}` standing for the keys bound to `key`.
+    prompt: &'static str,
+    key: Option<Action>,
+    /// Whether `event` finishes the step, given how many generations the
+    /// board has stepped since it started.
+    done: fn(Event, u64) -> bool,
+}
+
+const STEPS: &[Step] = &[
+    Step {
+        prompt: "click or drag on the board to paint cells; clicking a live cell starts erasing",
+        key: None,
+        done: |event, _| event == Event::Painted,
+    },
+    Step {
+        prompt: "press {} to pick up a glider, then click to stamp it (Tab picks other patterns)",
+        key: Some(Action::StampMode),
+        done: |event, _| event == Event::Stamped(Some("glider")),
+    },
+    Step {
+        prompt: "press {} to start the board running",
+        key: Some(Action::ToggleRun),
+        done: |event, _| event == Event::Performed(Action::ToggleRun),
+    },
+    Step {
+        prompt: "press {} again to pause it",
+        key: Some(Action::ToggleRun),
+        done: |event, _| event == Event::Performed(Action::ToggleRun),
+    },
+    Step {
+        prompt: "press {} to step one generation at a time",
+        key: Some(Action::Step),
+        done: |event, _| event == Event::Performed(Action::Step),
+    },
+    Step {
+        prompt: "the status bar along the bottom shows the generation and population: run for 30 more generations",
+        key: None,
+        done: |event, generations| event == Event::Stepped && generations >= 30,
+    },
+    Step {
+        prompt: "press {} to see every control, and again to close it",
+        key: Some(Action::ToggleHelp),
+        done: |event, _| event == Event::Performed(Action::ToggleHelp),
+    },
+];
+
+/// How far through the tutorial the player is.
+#[derive(Clone, Debug, Default)]
+pub struct Tutorial {
+    index: usize,
+    generations: u64,
+}
+
+impl Tutorial {
+    /// Move on to the next step if `event` finishes the current one, saying
+    /// whether it did.
+    pub fn observe(&mut self, event: Event) -> bool {
+        if event == Event::Stepped {
+            self.generations += 1;
+        }
+        match STEPS.get(self.index) {
+            Some(step) if (step.done)(event, self.generations) => {
+                self.index += 1;
+                self.generations = 0;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.index >= STEPS.len()
+    }
+
+    /// The current step's prompt, numbered and naming the keys it needs.
+    pub fn prompt(&self, key_bindings: &KeyBindings) -> Option<String> {
+        let step = STEPS.get(self.index)?;
+        let keys = match step.key {
+            Some(action) => match key_bindings.chords(action) {
+                chords if chords.is_empty() => "(unbound)".to_string(),
+                chords => chords.join(" or "),
+            },
+            None => String::new(),
+        };
+        Some(format!("tutorial {} of {}: {}", self.index + 1, STEPS.len(), step.prompt.replace("{}", &keys)))
+    }
+}
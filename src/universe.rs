@@ -0,0 +1,106 @@
+//! A board together with its rule and generation count, for driving the
+//! engine from another program.
+
+use crate::board::{self, Board, DenseBoard, GridPosition, Walls};
+use crate::hooks::Hooks;
+use crate::pattern::Pattern;
+use crate::rule::Rule;
+
+/// A board that steps under a rule and counts its generations.
+pub struct Universe {
+    board: Box<dyn Board>,
+    rule: Rule,
+    generation: u64,
+    walls: Walls,
+    hooks: Hooks,
+}
+
+impl Universe {
+    /// An empty universe `size` cells big, under Conway's rule.
+    pub fn new(size: (i16, i16)) -> Self {
+        Universe::with_board(Box::new(DenseBoard::new(size)))
+    }
+
+    /// A universe starting from `board`, which can be any backend.
+    pub fn with_board(board: Box<dyn Board>) -> Self {
+        Universe {
+            board,
+            rule: Rule::default(),
+            generation: 0,
+            walls: Walls::default(),
+            hooks: Hooks::default(),
+        }
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    /// Generations stepped since the universe was made.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn board(&self) -> &dyn Board {
+        self.board.as_ref()
+    }
+
+    pub fn board_mut(&mut self) -> &mut dyn Board {
+        self.board.as_mut()
+    }
+
+    /// Cells held alive or dead whatever the rule says, as of the next step.
+    pub fn walls_mut(&mut self) -> &mut Walls {
+        &mut self.walls
+    }
+
+    /// Callbacks to run after every step.
+    pub fn hooks_mut(&mut self) -> &mut Hooks {
+        &mut self.hooks
+    }
+
+    /// Bring `pattern`'s live cells to life with its top-left corner at
+    /// `origin`. Cells that fall off the board are dropped.
+    pub fn place(&mut self, pattern: &Pattern, origin: GridPosition) {
+        pattern.stamp(self.board.as_mut(), origin);
+    }
+
+    /// Bring `pattern`'s live cells to life around the middle of the board.
+    pub fn place_centered(&mut self, pattern: &Pattern) {
+        let (width, height) = self.board.size();
+        self.place(pattern, pattern.origin_centered_on(GridPosition::new(width / 2, height / 2)));
+    }
+
+    /// Advance one generation.
+    pub fn step(&mut self) {
+        let mut next = board::step(self.board.as_ref(), &self.rule);
+        self.walls.apply(next.as_mut());
+        let previous = std::mem::replace(&mut self.board, next);
+        self.generation += 1;
+        self.hooks.stepped(&mut (), self.generation, previous.as_ref(), self.board.as_ref());
+    }
+
+    /// Advance `count` generations.
+    pub fn step_by(&mut self, count: u64) {
+        for _ in 0..count {
+            self.step();
+        }
+    }
+
+    pub fn is_alive(&self, pos: GridPosition) -> bool {
+        self.board.is_alive(pos)
+    }
+
+    pub fn population(&self) -> usize {
+        self.board.population()
+    }
+
+    /// The live part of the board, as a pattern.
+    pub fn live_pattern(&self) -> Pattern {
+        Pattern::capture_live(self.board.as_ref())
+    }
+}
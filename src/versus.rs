@@ -0,0 +1,195 @@
+//! Two players competing on one board under the Immigration rule: Conway's
+//! Life, with every live cell belonging to one of the players, and each cell
+//! born going to whoever owns most of the three cells around it.
+
+use crate::board::{Board, GridPosition};
+
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    pub fn other(self) -> Self {
+        match self {
+            Player::One => Player::Two,
+            Player::Two => Player::One,
+        }
+    }
+
+    /// 0 for the first player and 1 for the second, for indexing.
+    pub fn index(self) -> usize {
+        match self {
+            Player::One => 0,
+            Player::Two => 1,
+        }
+    }
+}
+
+/// Which player owns each live cell.
+pub type Owners = HashMap<GridPosition, Player>;
+
+/// The owners of the cells of `board`, one step on from `previous` and the
+/// same size, whose cells `owners` belong to. Survivors stay with their owner and newborn
+/// cells go to the player owning most of their live neighbors. Cells nobody
+/// owns, like ones drawn in partway through, don't count towards either.
+pub fn next_owners(previous: &dyn Board, owners: &Owners, board: &dyn Board) -> Owners {
+    let mut next = Owners::new();
+
+    for pos in board.live_cells() {
+        if let (Some(&owner), true) = (owners.get(&pos), previous.is_alive(pos)) {
+            next.insert(pos, owner);
+            continue;
+        }
+
+        let mut neighbors = [0; 2];
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let neighbor = GridPosition::new(pos.x + dx, pos.y + dy);
+                if (dx, dy) != (0, 0) {
+                    if let Some(owner) = owners.get(&neighbor) {
+                        neighbors[owner.index()] += 1;
+                    }
+                }
+            }
+        }
+        if let Some(owner) = majority(neighbors) {
+            next.insert(pos, owner);
+        }
+    }
+
+    next
+}
+
+/// The player with more of `counts`, indexed like `Player::index`, or `None`
+/// for a tie.
+fn majority(counts: [usize; 2]) -> Option<Player> {
+    match counts[0].cmp(&counts[1]) {
+        std::cmp::Ordering::Greater => Some(Player::One),
+        std::cmp::Ordering::Less => Some(Player::Two),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// A match: the players take turns placing a cell each until they've both
+/// placed their share, then the board runs for a set number of generations,
+/// and whoever owns more cells at the end wins.
+#[derive(Clone, Debug)]
+pub struct Match {
+    /// How many cells each player places.
+    pub budget: u32,
+    /// How many generations the board runs for once every cell is placed, and
+    /// how many it's run so far.
+    pub generations: u64,
+    pub generation: u64,
+    pub owners: Owners,
+    /// The player placing the next cell.
+    pub turn: Player,
+    placed: [u32; 2],
+}
+
+impl Match {
+    pub fn new(budget: u32, generations: u64) -> Self {
+        Match { budget, generations, generation: 0, owners: Owners::new(), turn: Player::One, placed: [0; 2] }
+    }
+
+    /// How many cells `player` still has to place.
+    pub fn remaining(&self, player: Player) -> u32 {
+        self.budget - self.placed[player.index()]
+    }
+
+    /// Whether cells are still being placed.
+    pub fn placing(&self) -> bool {
+        self.remaining(Player::One) + self.remaining(Player::Two) > 0
+    }
+
+    /// Whether the board has run for every generation of the match.
+    pub fn finished(&self) -> bool {
+        !self.placing() && self.generation >= self.generations
+    }
+
+    /// Place a cell on `board` for the player whose turn it is, and pass the
+    /// turn on. False, leaving the turn where it is, if the cell is already
+    /// alive or there's nothing left to place.
+    pub fn place(&mut self, board: &mut dyn Board, pos: GridPosition) -> bool {
+        if board.is_alive(pos) || self.remaining(self.turn) == 0 {
+            return false;
+        }
+
+        board.set_alive(pos, true);
+        self.owners.insert(pos, self.turn);
+        self.placed[self.turn.index()] += 1;
+        if self.remaining(self.turn.other()) > 0 {
+            self.turn = self.turn.other();
+        }
+        true
+    }
+
+    /// Follow the board stepping from `previous` to `board`.
+    pub fn step(&mut self, previous: &dyn Board, board: &dyn Board) {
+        self.owners = next_owners(previous, &self.owners, board);
+        self.generation += 1;
+    }
+
+    /// How many live cells each player owns.
+    pub fn territory(&self) -> [usize; 2] {
+        let mut territory = [0; 2];
+        for owner in self.owners.values() {
+            territory[owner.index()] += 1;
+        }
+        territory
+    }
+
+    /// The player with more cells, or `None` for a draw.
+    pub fn leader(&self) -> Option<Player> {
+        majority(self.territory())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{self, DenseBoard};
+    use crate::rule::Rule;
+
+    #[test]
+    fn players_take_turns_until_their_cells_run_out() {
+        let mut board = DenseBoard::new((10, 10));
+        let mut versus = Match::new(2, 10);
+
+        assert!(versus.place(&mut board, GridPosition::new(1, 1)));
+        assert_eq!(versus.turn, Player::Two);
+        assert!(!versus.place(&mut board, GridPosition::new(1, 1)));
+        assert!(versus.place(&mut board, GridPosition::new(5, 5)));
+        assert!(versus.place(&mut board, GridPosition::new(1, 2)));
+        assert!(versus.place(&mut board, GridPosition::new(5, 6)));
+
+        assert!(!versus.placing());
+        assert!(!versus.place(&mut board, GridPosition::new(8, 8)));
+        assert_eq!(versus.territory(), [2, 2]);
+        assert_eq!(versus.leader(), None);
+    }
+
+    #[test]
+    fn newborn_cells_go_to_the_majority() {
+        // a blinker with two cells of the first player's and one of the second's
+        let mut board = DenseBoard::new((5, 5));
+        let mut versus = Match::new(0, 10);
+        for &(x, y, owner) in &[(1, 2, Player::One), (2, 2, Player::One), (3, 2, Player::Two)] {
+            board.set_alive(GridPosition::new(x, y), true);
+            versus.owners.insert(GridPosition::new(x, y), owner);
+        }
+
+        let next = board::step(&board, &Rule::default());
+        versus.step(&board, next.as_ref());
+
+        assert_eq!(versus.owners.get(&GridPosition::new(2, 2)), Some(&Player::One));
+        assert_eq!(versus.owners.get(&GridPosition::new(2, 1)), Some(&Player::One));
+        assert_eq!(versus.owners.get(&GridPosition::new(2, 3)), Some(&Player::One));
+        assert_eq!(versus.leader(), Some(Player::One));
+        assert_eq!(versus.generation, 1);
+    }
+}
@@ -0,0 +1,122 @@
+//! JavaScript bindings for the browser frontend in `web/`, which draws the
+//! board on a canvas. Only built with the `web` feature.
+
+use crate::board::{self, GridPosition, Selection};
+use crate::pattern::Pattern;
+use crate::rule::Rule;
+use crate::share::Setup;
+use crate::universe::Universe;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use wasm_bindgen::prelude::*;
+
+/// A universe as the page sees it, with its own random number generator for soups.
+#[wasm_bindgen]
+pub struct WebUniverse {
+    universe: Universe,
+    rng: StdRng,
+}
+
+#[wasm_bindgen]
+impl WebUniverse {
+    /// An empty board, with soups drawn from `seed`. Browsers have no entropy
+    /// the engine can reach, so the page picks the seed.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: i16, height: i16, seed: u32) -> WebUniverse {
+        WebUniverse {
+            universe: Universe::new((width.max(1), height.max(1))),
+            rng: StdRng::seed_from_u64(seed as u64),
+        }
+    }
+
+    pub fn width(&self) -> i16 {
+        self.universe.board().size().0
+    }
+
+    pub fn height(&self) -> i16 {
+        self.universe.board().size().1
+    }
+
+    pub fn generation(&self) -> f64 {
+        self.universe.generation() as f64
+    }
+
+    pub fn population(&self) -> u32 {
+        self.universe.population() as u32
+    }
+
+    pub fn rule(&self) -> String {
+        self.universe.rule().to_string()
+    }
+
+    /// Switch to a preset or rulestring. False if there's no such rule.
+    pub fn set_rule(&mut self, rule: &str) -> bool {
+        match Rule::from_name_or_rulestring(rule) {
+            Some(rule) => {
+                self.universe.set_rule(rule);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn step(&mut self, count: u32) {
+        self.universe.step_by(count as u64);
+    }
+
+    /// Flip the cell at `x`, `y`, if it's on the board.
+    pub fn toggle(&mut self, x: i16, y: i16) {
+        let (width, height) = self.universe.board().size();
+        if x >= 0 && x < width && y >= 0 && y < height {
+            let pos = GridPosition::new(x, y);
+            let alive = self.universe.is_alive(pos);
+            self.universe.board_mut().set_alive(pos, !alive);
+        }
+    }
+
+    /// Stamp a built-in pattern by name, or RLE or plaintext, centered on `x`, `y`.
+    pub fn place(&mut self, pattern: &str, x: i16, y: i16) {
+        let pattern = Pattern::from_name_or_text(pattern);
+        self.universe.place(&pattern, pattern.origin_centered_on(GridPosition::new(x, y)));
+    }
+
+    pub fn randomize(&mut self, density: f32) {
+        let whole_board = Selection::whole_board(self.universe.board().size());
+        board::fill_random(self.universe.board_mut(), &whole_board, density.clamp(0.0, 1.0), &mut self.rng);
+    }
+
+    pub fn clear(&mut self) {
+        let whole_board = Selection::whole_board(self.universe.board().size());
+        board::set_region(self.universe.board_mut(), &whole_board, true);
+    }
+
+    /// The live cells as `x, y` pairs one after the other, for drawing.
+    pub fn live_cells(&self) -> Vec<i16> {
+        let mut cells = Vec::with_capacity(self.universe.population() * 2);
+        for pos in self.universe.board().live_cells() {
+            cells.push(pos.x);
+            cells.push(pos.y);
+        }
+        cells
+    }
+
+    /// The board and its rule as a share code, for the page to put in its URL.
+    pub fn share_code(&self) -> String {
+        Setup::capture(self.universe.board(), self.universe.rule()).to_code()
+    }
+
+    /// Replace the board and the rule with the ones in a share code, which
+    /// can change the board's size. False if it isn't a share code.
+    pub fn load_share_code(&mut self, code: &str) -> bool {
+        match Setup::from_code(code) {
+            Ok(setup) => {
+                self.universe = Universe::new(setup.size);
+                self.universe.set_rule(setup.rule);
+                self.universe.place(&setup.pattern, setup.origin);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
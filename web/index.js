@@ -0,0 +1,73 @@
+import init, { WebUniverse } from "./pkg/life.js";
+
+const GRID = [200, 150];
+const CELL_SIZE = 5;
+const DENSITY = 0.3;
+
+await init();
+
+const universe = new WebUniverse(GRID[0], GRID[1], Math.floor(Math.random() * 2 ** 32));
+const canvas = document.getElementById("board");
+const status = document.getElementById("status");
+const ctx = canvas.getContext("2d");
+
+// a share code in the URL, from the game or from S here, sets up the board
+function fitCanvas() {
+  canvas.width = universe.width() * CELL_SIZE;
+  canvas.height = universe.height() * CELL_SIZE;
+}
+if (location.hash.length > 1) {
+  universe.load_share_code(decodeURIComponent(location.hash.slice(1)));
+}
+fitCanvas();
+addEventListener("hashchange", () => {
+  if (universe.load_share_code(decodeURIComponent(location.hash.slice(1)))) {
+    fitCanvas();
+  }
+});
+
+let running = false;
+let speed = 10;
+let lastStep = 0;
+
+function draw() {
+  ctx.fillStyle = "#000";
+  ctx.fillRect(0, 0, canvas.width, canvas.height);
+  ctx.fillStyle = "#fff";
+
+  const cells = universe.live_cells();
+  for (let i = 0; i < cells.length; i += 2) {
+    ctx.fillRect(cells[i] * CELL_SIZE, cells[i + 1] * CELL_SIZE, CELL_SIZE, CELL_SIZE);
+  }
+
+  status.textContent = `${universe.rule()}    ${speed} gen/s    generation ${universe.generation()}    population ${universe.population()}${running ? "" : "    paused"}`;
+}
+
+function frame(time) {
+  if (running && time - lastStep >= 1000 / speed) {
+    universe.step(1);
+    lastStep = time;
+  }
+  draw();
+  requestAnimationFrame(frame);
+}
+
+canvas.addEventListener("click", (event) => {
+  const rect = canvas.getBoundingClientRect();
+  universe.toggle(Math.floor((event.clientX - rect.left) / CELL_SIZE), Math.floor((event.clientY - rect.top) / CELL_SIZE));
+});
+
+document.addEventListener("keydown", (event) => {
+  switch (event.key) {
+    case " ": running = !running; event.preventDefault(); break;
+    case "n": universe.step(1); break;
+    case "r": universe.randomize(DENSITY); break;
+    case "c": universe.clear(); break;
+    case "g": universe.place("glider", universe.width() / 2, universe.height() / 2); break;
+    case "s": history.replaceState(null, "", `#${universe.share_code()}`); break;
+    case "+": case "=": speed = Math.min(speed * 2, 240); break;
+    case "-": speed = Math.max(speed / 2, 1); break;
+  }
+});
+
+requestAnimationFrame(frame);